@@ -0,0 +1,298 @@
+// std
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+// third-party
+use serde_json::Value;
+// internal
+use crate::Error;
+use massa_wasm_tools::{PostOptions, PostReport, post_process};
+
+pub(crate) const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+/// RUSTFLAGS used with a nightly toolchain (std is rebuilt with only MVP + bulk memory)
+const NIGHTLY_RUSTFLAGS: &str = "-Ctarget-cpu=mvp -Ctarget-feature=+bulk-memory";
+const NIGHTLY_BUILD_STD: &str = "-Zbuild-std=panic_abort,std";
+
+/// Options shared by `cargo massa build` & `cargo massa test`
+#[derive(Debug, Clone, clap::Args)]
+pub(crate) struct BuildOptions {
+    /// Package to build (e.g. hello_world)
+    #[arg(short, long)]
+    pub package: Option<String>,
+    /// Build with a nightly toolchain & rebuild std (otherwise: stable + post processing)
+    #[arg(long)]
+    pub nightly: bool,
+    /// Example to build instead of the library (e.g. hello_wasmv1)
+    #[arg(long)]
+    pub example: Option<String>,
+    /// Features to enable (e.g. wasmv1)
+    #[arg(short = 'F', long)]
+    pub features: Vec<String>,
+    /// Remove the custom sections (except the ABI section)
+    #[arg(long)]
+    pub strip_custom_sections: bool,
+}
+
+/// A wasm file produced by cargo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Artifact {
+    pub path: PathBuf,
+    /// true for unit tests (cargo test)
+    pub test: bool,
+}
+
+/// Run cargo for the wasm target and return the produced wasm files
+///
+/// `subcommand` is either build or test (for test, --no-run is added)
+pub(crate) fn run_cargo(
+    subcommand: &str,
+    release: bool,
+    options: &BuildOptions,
+) -> Result<Vec<Artifact>, Error> {
+    let mut cmd = if options.nightly {
+        // Note: use cargo from PATH (rustup proxy) to select the toolchain
+        let mut cmd = Command::new("cargo");
+        cmd.arg("+nightly").arg(subcommand).arg(NIGHTLY_BUILD_STD);
+        let (var, rustflags) = nightly_rustflags(
+            std::env::var("CARGO_ENCODED_RUSTFLAGS").ok(),
+            std::env::var("RUSTFLAGS").ok(),
+        );
+        cmd.env(var, rustflags);
+        cmd
+    } else {
+        // When run as a cargo subcommand, CARGO is the cargo binary that runs us
+        let mut cmd = Command::new(std::env::var("CARGO").unwrap_or("cargo".to_string()));
+        cmd.arg(subcommand);
+        cmd
+    };
+
+    cmd.args(["--target", WASM_TARGET])
+        .arg("--message-format=json-render-diagnostics")
+        .stdout(Stdio::piped());
+    if release {
+        cmd.arg("--release");
+    }
+    if subcommand == "test" {
+        cmd.arg("--no-run");
+    }
+    if let Some(package) = &options.package {
+        cmd.args(["-p", package]);
+    }
+    if let Some(example) = &options.example {
+        cmd.args(["--example", example]);
+    }
+    if !options.features.is_empty() {
+        cmd.args(["--features", &options.features.join(",")]);
+    }
+
+    let output = cmd.spawn()?.wait_with_output()?;
+    if !output.status.success() {
+        return Err(Error::Cargo(output.status));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_artifacts(&stdout))
+}
+
+/// Environment variable & value of the rustflags of a nightly build: NIGHTLY_RUSTFLAGS appended to
+/// the flags of the user (CARGO_ENCODED_RUSTFLAGS, used by cargo over RUSTFLAGS, or RUSTFLAGS)
+fn nightly_rustflags(encoded: Option<String>, rustflags: Option<String>) -> (&'static str, String) {
+    match (encoded, rustflags) {
+        (Some(encoded), _) => {
+            // Flags separated by 0x1f
+            let mut flags: Vec<&str> = encoded.split('\x1f').filter(|f| !f.is_empty()).collect();
+            flags.extend(NIGHTLY_RUSTFLAGS.split(' '));
+            ("CARGO_ENCODED_RUSTFLAGS", flags.join("\x1f"))
+        }
+        (None, Some(rustflags)) if !rustflags.trim().is_empty() => (
+            "RUSTFLAGS",
+            format!("{} {NIGHTLY_RUSTFLAGS}", rustflags.trim()),
+        ),
+        _ => ("RUSTFLAGS", NIGHTLY_RUSTFLAGS.to_string()),
+    }
+}
+
+/// Extract the wasm files from cargo json messages (--message-format=json)
+fn parse_artifacts(messages: &str) -> Vec<Artifact> {
+    messages
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|msg| msg["reason"] == "compiler-artifact")
+        .flat_map(|msg| {
+            let test = msg["profile"]["test"].as_bool().unwrap_or(false);
+            let filenames = msg["filenames"].as_array().cloned().unwrap_or_default();
+            filenames
+                .into_iter()
+                .filter_map(|f| f.as_str().map(PathBuf::from))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+                .map(move |path| Artifact { path, test })
+        })
+        .collect()
+}
+
+/// Post process a wasm file in place (see massa_wasm_tools::post_process)
+///
+/// `wasmv1_prefix`: prefix a wasmv1 smart contract with 0x01 (for deployment, massa_sc_runner
+/// loads unit tests without the prefix)
+pub(crate) fn post_process_file(
+    path: &Path,
+    options: &BuildOptions,
+    wasmv1_prefix: bool,
+) -> Result<PostReport, Error> {
+    let bytecode = std::fs::read(path)?;
+    let post_options = PostOptions {
+        strip_custom_sections: options.strip_custom_sections,
+        keep_sections: vec![],
+        wasmv1_prefix,
+    };
+    let (bytecode, report) = post_process(&bytecode, &post_options)?;
+    std::fs::write(path, bytecode)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_artifacts() {
+        let messages = concat!(
+            r#"{"reason":"compiler-artifact","package_id":"massa_rust_sc","profile":{"test":false},"filenames":["/p/target/wasm32-unknown-unknown/release/deps/libmassa_rust_sc.rlib"]}"#,
+            "\n",
+            r#"{"reason":"compiler-artifact","package_id":"hello_world","profile":{"test":false},"filenames":["/p/target/wasm32-unknown-unknown/release/hello_world.wasm"]}"#,
+            "\n",
+            r#"{"reason":"compiler-artifact","package_id":"hello_world","profile":{"test":true},"filenames":["/p/target/wasm32-unknown-unknown/debug/deps/hello_world-1a2b.wasm"],"executable":"/p/target/wasm32-unknown-unknown/debug/deps/hello_world-1a2b.wasm"}"#,
+            "\n",
+            r#"{"reason":"build-finished","success":true}"#,
+            "\n",
+        );
+
+        assert_eq!(
+            parse_artifacts(messages),
+            vec![
+                Artifact {
+                    path: PathBuf::from(
+                        "/p/target/wasm32-unknown-unknown/release/hello_world.wasm"
+                    ),
+                    test: false,
+                },
+                Artifact {
+                    path: PathBuf::from(
+                        "/p/target/wasm32-unknown-unknown/debug/deps/hello_world-1a2b.wasm"
+                    ),
+                    test: true,
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nightly_rustflags() {
+        assert_eq!(
+            nightly_rustflags(None, None),
+            ("RUSTFLAGS", NIGHTLY_RUSTFLAGS.to_string())
+        );
+        assert_eq!(
+            nightly_rustflags(None, Some(" ".to_string())),
+            ("RUSTFLAGS", NIGHTLY_RUSTFLAGS.to_string())
+        );
+        assert_eq!(
+            nightly_rustflags(None, Some("-Dwarnings".to_string())),
+            ("RUSTFLAGS", format!("-Dwarnings {NIGHTLY_RUSTFLAGS}"))
+        );
+        assert_eq!(
+            nightly_rustflags(
+                Some("-Dwarnings\x1f--cfg=a b".to_string()),
+                Some("-g".to_string())
+            ),
+            (
+                "CARGO_ENCODED_RUSTFLAGS",
+                "-Dwarnings\x1f--cfg=a b\x1f-Ctarget-cpu=mvp\x1f-Ctarget-feature=+bulk-memory"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            nightly_rustflags(Some(String::new()), None),
+            (
+                "CARGO_ENCODED_RUSTFLAGS",
+                "-Ctarget-cpu=mvp\x1f-Ctarget-feature=+bulk-memory".to_string()
+            )
+        );
+    }
+
+    /// Build with cargo & post process (as cargo massa build), return the wasm files
+    fn build_and_post_process(subcommand: &str, package: &str) -> Vec<Vec<u8>> {
+        let options = BuildOptions {
+            package: Some(package.to_string()),
+            nightly: false,
+            example: None,
+            features: vec![],
+            strip_custom_sections: false,
+        };
+        let artifacts = run_cargo(subcommand, subcommand == "build", &options).unwrap();
+        assert!(!artifacts.is_empty());
+        artifacts
+            .iter()
+            .map(|artifact| {
+                post_process_file(&artifact.path, &options, false).unwrap();
+                std::fs::read(&artifact.path).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    #[ignore = "builds smart contracts (requires the wasm32-unknown-unknown target)"]
+    fn test_validate_sdk_imports() {
+        // The ABI functions imported by a smart contract & by the massa_rust_sc unit tests (most
+        // of the AssemblyScript API) must be accepted, with the signatures declared by the sdk
+        for (subcommand, package) in [
+            ("build", "hello_world"),
+            ("test", "hello_world"),
+            ("test", "massa_rust_sc"),
+        ] {
+            for bytecode in build_and_post_process(subcommand, package) {
+                massa_wasm_tools::validate_imports(&bytecode, false).unwrap();
+                massa_wasm_tools::validate_features(&bytecode).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "builds a smart contract (requires the wasm32-unknown-unknown target)"]
+    fn test_build_wasmv1_contract() {
+        // massa_rust_sc/examples/hello_wasmv1.rs
+        let options = BuildOptions {
+            package: Some("massa_rust_sc".to_string()),
+            nightly: false,
+            example: Some("hello_wasmv1".to_string()),
+            features: vec!["wasmv1".to_string()],
+            strip_custom_sections: false,
+        };
+        let artifacts = run_cargo("build", true, &options).unwrap();
+        assert_eq!(artifacts.len(), 1);
+        let path = &artifacts[0].path;
+        let unprefixed = std::fs::read(path).unwrap();
+        // The wasmv1 ABI is only accepted with the prefix
+        assert!(massa_wasm_tools::validate(&unprefixed).is_err());
+
+        // cargo massa build
+        let report = post_process_file(path, &options, true).unwrap();
+        assert!(report.wasmv1);
+        let bytecode = std::fs::read(path).unwrap();
+        assert_eq!(bytecode[0], 0x01);
+        massa_wasm_tools::validate(&bytecode).unwrap();
+
+        // Deployment: an already prefixed bytecode is left untouched
+        assert_eq!(
+            massa_rust_web3::prepare_smart_contract(bytecode.clone()).unwrap(),
+            bytecode
+        );
+        // Note: the unprefixed bytecode (no post processing) is prefixed before deployment
+        let (lowered, _) =
+            massa_wasm_tools::post_process(&unprefixed, &Default::default()).unwrap();
+        assert_eq!(
+            massa_rust_web3::prepare_smart_contract(lowered).unwrap(),
+            bytecode
+        );
+    }
+}
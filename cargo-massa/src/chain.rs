@@ -0,0 +1,210 @@
+// std
+use std::path::Path;
+use std::str::FromStr;
+// internal
+use crate::Error;
+use massa_rust_web3::{
+    Address, Amount, Args, CallOptions, ContractClient, DeployerArgs, KeyPair,
+    deploy_smart_contract,
+};
+
+/// Options for `cargo massa deploy` & `cargo massa call`
+#[derive(Debug, Clone, clap::Args)]
+pub(crate) struct ChainOptions {
+    /// JsonRPC url of a Massa node
+    #[arg(long, default_value = massa_rust_web3::BUILDNET_URL)]
+    pub url: String,
+    /// Private key of the wallet (Default to: PRIVATE_KEY env var, .env file is loaded)
+    #[arg(long)]
+    pub private_key: Option<String>,
+    /// Coins sent along the operation (e.g. 0.01)
+    #[arg(long)]
+    pub coins: Option<Amount>,
+    /// Fee for the operation (Default to: the minimal fee of the node)
+    #[arg(long)]
+    pub fee: Option<Amount>,
+    /// Max gas (Default to: estimated with a read only call)
+    #[arg(long)]
+    pub max_gas: Option<u64>,
+    /// Argument (Args encoded): type:value, can be repeated
+    /// (types: u8, u16, u32, u64, i8, i16, i32, i64, bool, string, amount, address, bytes (hex))
+    #[arg(long = "arg", value_parser = parse_arg)]
+    pub args: Vec<Arg>,
+}
+
+impl ChainOptions {
+    fn keypair(&self) -> Result<KeyPair, Error> {
+        let private_key = match &self.private_key {
+            Some(private_key) => private_key.clone(),
+            None => {
+                dotenv::dotenv().ok();
+                std::env::var("PRIVATE_KEY").map_err(|_| Error::MissingPrivateKey)?
+            }
+        };
+        KeyPair::from_str(private_key.as_str())
+            .map_err(|e| Error::InvalidArg(format!("Invalid private key: {e}")))
+    }
+
+    fn args(&self) -> Args {
+        let mut args = Args::new();
+        for arg in self.args.iter() {
+            arg.add_to(&mut args);
+        }
+        args
+    }
+}
+
+/// A smart contract function argument given on the command line
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Arg {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    Bool(bool),
+    String(String),
+    Amount(Amount),
+    Address(Address),
+    Bytes(Vec<u8>),
+}
+
+impl Arg {
+    fn add_to(&self, args: &mut Args) {
+        match self {
+            Arg::U8(v) => args.add(v),
+            Arg::U16(v) => args.add(v),
+            Arg::U32(v) => args.add(v),
+            Arg::U64(v) => args.add(v),
+            Arg::I8(v) => args.add(v),
+            Arg::I16(v) => args.add(v),
+            Arg::I32(v) => args.add(v),
+            Arg::I64(v) => args.add(v),
+            Arg::Bool(v) => args.add(v),
+            Arg::String(v) => args.add(v),
+            // Like massa_rust_sc::Amount: number of nanoMAS (u64)
+            Arg::Amount(v) => args.add(&v.to_raw()),
+            Arg::Address(v) => args.add_str(&v.to_string()),
+            Arg::Bytes(v) => args.add(v),
+        };
+    }
+}
+
+fn parse_hex(value: &str) -> Result<Vec<u8>, String> {
+    let value = value.strip_prefix("0x").unwrap_or(value);
+    if !value.len().is_multiple_of(2) {
+        return Err("odd number of hex digits".to_string());
+    }
+    value
+        .as_bytes()
+        .chunks(2)
+        .map(|digits| {
+            std::str::from_utf8(digits)
+                .ok()
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .ok_or("invalid hex digit".to_string())
+        })
+        .collect()
+}
+
+/// Parse an argument: type:value (e.g. u64:42, string:hello)
+pub(crate) fn parse_arg(arg: &str) -> Result<Arg, String> {
+    let (ty, value) = arg
+        .split_once(':')
+        .ok_or(format!("Invalid argument: {arg} (expected: type:value)"))?;
+    let e = |e: &dyn std::fmt::Display| format!("Invalid {ty} value: {value} ({e})");
+    let arg = match ty {
+        "u8" => Arg::U8(value.parse().map_err(|err| e(&err))?),
+        "u16" => Arg::U16(value.parse().map_err(|err| e(&err))?),
+        "u32" => Arg::U32(value.parse().map_err(|err| e(&err))?),
+        "u64" => Arg::U64(value.parse().map_err(|err| e(&err))?),
+        "i8" => Arg::I8(value.parse().map_err(|err| e(&err))?),
+        "i16" => Arg::I16(value.parse().map_err(|err| e(&err))?),
+        "i32" => Arg::I32(value.parse().map_err(|err| e(&err))?),
+        "i64" => Arg::I64(value.parse().map_err(|err| e(&err))?),
+        "bool" => Arg::Bool(value.parse().map_err(|err| e(&err))?),
+        "string" => Arg::String(value.to_string()),
+        "amount" => Arg::Amount(Amount::from_str(value).map_err(|err| e(&err))?),
+        "address" => Arg::Address(Address::from_str(value).map_err(|err| e(&err))?),
+        "bytes" => Arg::Bytes(parse_hex(value).map_err(|err| e(&err))?),
+        _ => return Err(format!("Unknown argument type: {ty}")),
+    };
+    Ok(arg)
+}
+
+/// Deploy a smart contract (the constructor is called with the given arguments)
+pub(crate) async fn deploy(wasm: &Path, options: &ChainOptions) -> Result<Address, Error> {
+    let keypair = options.keypair()?;
+    let deploy_args = DeployerArgs {
+        constructor_arguments: (!options.args.is_empty()).then(|| options.args().into_bytes()),
+        coins: options.coins.map(|coins| coins.to_raw()),
+        fee: options.fee,
+        max_gas: options.max_gas,
+    };
+    let address = deploy_smart_contract(options.url.as_str(), &keypair, wasm, deploy_args).await?;
+    Ok(address)
+}
+
+/// Call a smart contract function (read only call or CallSC operation)
+pub(crate) async fn call(
+    address: Address,
+    function: &str,
+    read_only: bool,
+    options: &ChainOptions,
+) -> Result<(), Error> {
+    let contract = ContractClient::new(options.url.as_str(), address);
+
+    if read_only {
+        // Note: the caller is only required if the function uses it
+        let contract = match options.keypair() {
+            Ok(keypair) => contract.with_keypair(keypair),
+            Err(_) => contract,
+        };
+        let result = contract.read(function, options.args()).await?;
+        let bytes = result.as_bytes();
+        println!("Result ({} bytes): {:?}", bytes.len(), bytes);
+        if let Ok(s) = std::str::from_utf8(bytes) {
+            println!("Result as utf-8 string: {s:?}");
+        }
+    } else {
+        let contract = contract.with_keypair(options.keypair()?);
+        let call_options = CallOptions {
+            coins: options.coins,
+            fee: options.fee,
+            max_gas: options.max_gas,
+        };
+        let op_id = contract
+            .call(function, options.args(), call_options)
+            .await?;
+        println!("Operation id: {op_id}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_arg() {
+        assert_eq!(parse_arg("u64:42"), Ok(Arg::U64(42)));
+        assert_eq!(parse_arg("bool:true"), Ok(Arg::Bool(true)));
+        assert_eq!(
+            parse_arg("string:hello:world"),
+            Ok(Arg::String("hello:world".to_string()))
+        );
+        assert_eq!(
+            parse_arg("amount:0.01"),
+            Ok(Arg::Amount(Amount::from_raw(10_000_000)))
+        );
+        assert_eq!(parse_arg("bytes:0x0aff"), Ok(Arg::Bytes(vec![10, 255])));
+        assert!(parse_arg("u8:256").is_err());
+        assert!(parse_arg("bytes:abc").is_err());
+        assert!(parse_arg("bytes:zz").is_err());
+        assert!(parse_arg("u128:1").is_err());
+        assert!(parse_arg("42").is_err());
+    }
+}
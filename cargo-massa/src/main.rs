@@ -0,0 +1,222 @@
+mod cargo;
+mod chain;
+
+// std
+use std::path::PathBuf;
+use std::process::ExitStatus;
+// third-party
+use clap::{Parser, Subcommand};
+// internal
+use cargo::{BuildOptions, post_process_file, run_cargo};
+use chain::ChainOptions;
+use massa_rust_web3::{Address, ContractError, DeployError};
+use massa_wasm_tools::WasmToolsError;
+
+/// massa_sc_runner built in the workspace (see .cargo/config.toml)
+const DEFAULT_RUNNER: &str = "target/debug/massa_sc_runner";
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("Io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("cargo failed: {0}")]
+    Cargo(ExitStatus),
+    #[error("No wasm file produced by cargo")]
+    NoArtifact,
+    #[error(transparent)]
+    WasmTools(#[from] WasmToolsError),
+    #[error("Cannot find the unit tests runner: {0} (Please run: cargo build -p massa_sc_runner)")]
+    RunnerNotFound(PathBuf),
+    #[error("Unit tests failed: {0}")]
+    TestFailed(ExitStatus),
+    #[error("No private key (Please use --private-key or set PRIVATE_KEY in .env)")]
+    MissingPrivateKey,
+    #[error("{0}")]
+    InvalidArg(String),
+    #[error(transparent)]
+    Deploy(#[from] DeployError),
+    #[error(transparent)]
+    Contract(#[from] ContractError),
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "cargo", bin_name = "cargo")]
+enum CargoCli {
+    Massa(MassaCli),
+}
+
+#[derive(Debug, clap::Args)]
+#[command(about = "Build, test & deploy Massa smart contracts written in Rust", long_about = None)]
+struct MassaCli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Build smart contract(s) (release) and make the wasm files compatible with Massa
+    Build {
+        #[command(flatten)]
+        options: BuildOptions,
+    },
+    /// Build the unit tests (wasm) and run them with massa_sc_runner
+    Test {
+        #[command(flatten)]
+        options: BuildOptions,
+        /// Path to massa_sc_runner
+        #[arg(long, default_value = DEFAULT_RUNNER)]
+        runner: PathBuf,
+        /// Only run the tests whose name contains the filter
+        filter: Option<String>,
+        /// Arguments passed to massa_sc_runner (libtest arguments, e.g. -- --exact --nocapture)
+        #[arg(last = true)]
+        runner_args: Vec<String>,
+    },
+    /// Deploy a smart contract
+    Deploy {
+        /// Wasm file (e.g. target/wasm32-unknown-unknown/release/hello_world.wasm)
+        wasm: PathBuf,
+        #[command(flatten)]
+        options: ChainOptions,
+    },
+    /// Call a function of a deployed smart contract
+    Call {
+        /// Smart contract address
+        address: Address,
+        /// Function name
+        function: String,
+        /// Read only call (no operation is sent, the result is printed)
+        #[arg(long)]
+        read_only: bool,
+        #[command(flatten)]
+        options: ChainOptions,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    let CargoCli::Massa(cli) = CargoCli::parse();
+
+    let res = match cli.command {
+        Command::Build { options } => build(&options),
+        Command::Test {
+            options,
+            runner,
+            filter,
+            runner_args,
+        } => test(&options, &runner, filter, &runner_args),
+        Command::Deploy { wasm, options } => chain::deploy(&wasm, &options)
+            .await
+            .map(|address| println!("SC address: {address}")),
+        Command::Call {
+            address,
+            function,
+            read_only,
+            options,
+        } => chain::call(address, &function, read_only, &options).await,
+    };
+
+    if let Err(e) = res {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn build(options: &BuildOptions) -> Result<(), Error> {
+    let artifacts = run_cargo("build", true, options)?;
+    if artifacts.is_empty() {
+        return Err(Error::NoArtifact);
+    }
+
+    for artifact in artifacts.iter() {
+        let report = post_process_file(&artifact.path, options, true)?;
+        println!("Generated wasm file: {}", artifact.path.display());
+        if report.wasmv1 {
+            println!("  wasmv1 smart contract (prefixed with 0x01)");
+        }
+        println!(
+            "  lowered instructions: sign-ext: {}, nontrapping-fptoint: {}",
+            report.lowered.sign_ext, report.lowered.trunc_sat
+        );
+        println!(
+            "  size: {} bytes (before post processing: {} bytes)",
+            report.output_size, report.input_size
+        );
+    }
+    Ok(())
+}
+
+fn test(
+    options: &BuildOptions,
+    runner: &PathBuf,
+    filter: Option<String>,
+    runner_args: &[String],
+) -> Result<(), Error> {
+    if !runner.exists() {
+        return Err(Error::RunnerNotFound(runner.clone()));
+    }
+
+    let artifacts: Vec<_> = run_cargo("test", false, options)?
+        .into_iter()
+        .filter(|artifact| artifact.test)
+        .collect();
+    if artifacts.is_empty() {
+        return Err(Error::NoArtifact);
+    }
+
+    for artifact in artifacts.iter() {
+        post_process_file(&artifact.path, options, false)?;
+        println!("Running: {}", artifact.path.display());
+        let mut cmd = std::process::Command::new(runner);
+        cmd.arg(&artifact.path);
+        if let Some(filter) = &filter {
+            cmd.arg(filter);
+        }
+        cmd.args(runner_args);
+        let status = cmd.status()?;
+        if !status.success() {
+            return Err(Error::TestFailed(status));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_runner_args() {
+        let CargoCli::Massa(cli) = CargoCli::parse_from([
+            "cargo",
+            "massa",
+            "test",
+            "-p",
+            "hello_world",
+            "hello",
+            "--",
+            "--exact",
+            "--skip",
+            "hello_2",
+            "--nocapture",
+        ]);
+        let Command::Test {
+            options,
+            filter,
+            runner_args,
+            ..
+        } = cli.command
+        else {
+            panic!("not a test command");
+        };
+        assert_eq!(options.package.as_deref(), Some("hello_world"));
+        assert_eq!(filter.as_deref(), Some("hello"));
+        assert_eq!(runner_args, ["--exact", "--skip", "hello_2", "--nocapture"]);
+
+        let CargoCli::Massa(cli) = CargoCli::parse_from(["cargo", "massa", "test"]);
+        assert!(matches!(
+            cli.command,
+            Command::Test { filter: None, runner_args, .. } if runner_args.is_empty()
+        ));
+    }
+}
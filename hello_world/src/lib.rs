@@ -3,10 +3,11 @@
 extern crate alloc;
 // rust crates
 use alloc::format;
+use alloc::string::String;
 // internal
 use massa_rust_sc::{
-    generate_event, get_data, is_deploying_contract, set_data, to_as_array, to_as_slice, AsSlice,
-    AsVec,
+    event, export, generate_event, get_data, is_deploying_contract, set_data, to_as_array,
+    to_as_slice, AsSlice, AsVec,
 };
 // third-party
 use utf16_lit::utf16;
@@ -32,6 +33,9 @@ extern "C" fn constructor() {
     let msg_utf16 = msg.encode_utf16().collect::<AsVec<u16>>();
     generate_event(msg_utf16);
 
+    // Use a structured event (formatted as: "Greeting:hello there,42")
+    event!("Greeting", "hello there", 42);
+
     // Storage set
     {
         // Set our value in smart contract storage
@@ -45,6 +49,14 @@ extern "C" fn hello() -> *const u8 {
     ptr as *const u8
 }
 
+/// Read only function with typed arguments & returned value (Args encoded)
+///
+/// See hello_world_scripts/examples/read_only_call.rs for the generated client
+#[export]
+fn greet(name: String) -> String {
+    format!("Hello, {name}!")
+}
+
 #[cfg_attr(not(test), panic_handler)]
 fn panic(_panic: &core::panic::PanicInfo<'_>) -> ! {
     // emit a wasm unreachable instruction if a panic occurs in our code
@@ -86,4 +98,9 @@ mod tests {
             assert_eq!(res.deref(), utf16!("hellw"));
         }
     }
+
+    #[wasm_test]
+    fn test_greet() {
+        assert_eq!(greet("Massa".to_string()), "Hello, Massa!");
+    }
 }
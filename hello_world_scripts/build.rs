@@ -0,0 +1,14 @@
+// std
+use std::path::PathBuf;
+
+fn main() {
+    // Generate a typed client (HelloWorld struct) for the hello_world smart contract
+    // Note: the ABI can also be read from the wasm file (massa_abi custom section)
+    let abi_path = "abi/hello_world.json";
+    println!("cargo:rerun-if-changed={abi_path}");
+
+    let code = massa_rust_web3::abigen_from_file(abi_path, "HelloWorld")
+        .expect("Unable to generate the HelloWorld client");
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    std::fs::write(out_dir.join("hello_world.rs"), code).unwrap();
+}
@@ -1,9 +1,10 @@
 use clap::Parser;
-use massa_rust_web3::{
-    Address, BUILDNET_URL, MassaJsonRpc, MassaRpcClient, ReadOnlyCall, ReadOnlyResult,
-};
+use massa_rust_web3::{Address, BUILDNET_URL, ContractClient};
 use std::str::FromStr;
 
+// Typed client for the hello_world smart contract (generated from abi/hello_world.json)
+include!(concat!(env!("OUT_DIR"), "/hello_world.rs"));
+
 const CONTRACT_ADDRESS: &str = "AS1AArefHYqYd9KB8wcCkvgesDap2teidRdwPJA22DpZqFxPUxuY";
 const CALLER_ADDRESS: &str = "AU12NTxUbAFvHzrLH3XKwxkNgsjPqiAadnbthJz2v1TuNJEyWU2Cx";
 
@@ -28,36 +29,26 @@ async fn main() {
         .expect("Please provide a valid address (caller address)");
     println!("Caller address: {:?}", caller_address);
 
+    let contract = ContractClient::new(BUILDNET_URL, sc_address).with_caller(caller_address);
+
     // Call hello() function from SC
+    // Note: hello() returns an utf-16 string (not Args encoded)
 
-    let read_function = "hello".to_string();
-    // TODO: Builder pattern
-    let read_params_ = ReadOnlyCall {
-        max_gas: 4294167295, // FIXME: MAX_GAS ?
-        target_address: sc_address,
-        target_function: read_function.clone(),
-        parameter: vec![],
-        caller_address: Some(caller_address),
-        coins: None,
-        // fee: Some(Amount::from_str("0.01").unwrap()),
-        // TODO / FIXME: should always be None otherwise got error
-        //               Runtime error: spending address AUXXXXX... not found
-        fee: None,
-    };
-
-    let client = MassaRpcClient::new(BUILDNET_URL);
-    let hello = client
-        .execute_read_only_call(vec![read_params_])
+    let hello = contract
+        .read("hello", massa_rust_web3::Args::new())
         .await
         .unwrap();
-
+    let res = String::from_utf16_lossy(bytemuck::cast_slice(hello.as_bytes()));
     println!("{}", "#".repeat(20));
-    println!("Read (function: `{}`): {:#?}", read_function, hello);
+    println!(
+        "Read (function: `hello`) result as utf-16 string: {:?}",
+        res
+    );
 
-    let res = match &hello[0].result {
-        ReadOnlyResult::Ok(res) => String::from_utf16_lossy(bytemuck::cast_slice(res.as_slice())),
-        ReadOnlyResult::Error(e) => e.clone(),
-    };
+    // Call greet() function from SC with the generated client (see build.rs)
 
-    println!("result as utf-16 string: {:?}", res);
+    let hello_world = HelloWorld::new(contract);
+    let greeting = hello_world.greet("Massa".to_string()).await.unwrap();
+    println!("{}", "#".repeat(20));
+    println!("Read (function: `greet`): {:?}", greeting);
 }
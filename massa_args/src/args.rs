@@ -0,0 +1,257 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Error returned when deserializing from [Args]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgsError {
+    /// Not enough bytes left to deserialize the requested value
+    OutOfBounds { offset: usize, needed: usize },
+    /// A string argument is not a valid utf-8 string
+    InvalidUtf8,
+    /// A value could be read but is not valid for the requested type
+    InvalidValue,
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgsError::OutOfBounds { offset, needed } => {
+                write!(f, "cannot read {needed} bytes at offset {offset}")
+            }
+            ArgsError::InvalidUtf8 => write!(f, "invalid utf-8 string"),
+            ArgsError::InvalidValue => write!(f, "invalid value"),
+        }
+    }
+}
+
+impl core::error::Error for ArgsError {}
+
+/// A type that can be (de)serialized with [Args]
+///
+/// The encoding of every implementation in this crate (and in massa_rust_sc) is the same as the one used by the `Args`
+/// class of massa-as-sdk && massa-web3, so arguments can be exchanged with AssemblyScript
+/// contracts and with the usual Massa tooling.
+pub trait Serializable: Sized {
+    /// Append the serialized value to the buffer
+    fn serialize(&self, buffer: &mut Vec<u8>);
+
+    /// Read a value from the buffer, starting at offset
+    ///
+    /// On success, offset is moved right after the value
+    fn deserialize(buffer: &[u8], offset: &mut usize) -> Result<Self, ArgsError>;
+}
+
+/// Read N bytes from the buffer (and advance the offset)
+pub(crate) fn read_bytes<'a>(
+    buffer: &'a [u8],
+    offset: &mut usize,
+    needed: usize,
+) -> Result<&'a [u8], ArgsError> {
+    let bytes = offset
+        .checked_add(needed)
+        .and_then(|end| buffer.get(*offset..end))
+        .ok_or(ArgsError::OutOfBounds {
+            offset: *offset,
+            needed,
+        })?;
+    *offset += needed;
+    Ok(bytes)
+}
+
+/// Read a N bytes array from the buffer (and advance the offset)
+pub(crate) fn read_array<const N: usize>(
+    buffer: &[u8],
+    offset: &mut usize,
+) -> Result<[u8; N], ArgsError> {
+    let bytes = read_bytes(buffer, offset, N)?;
+    // Unwrap safe: read_bytes returns exactly N bytes
+    Ok(bytes.try_into().unwrap())
+}
+
+macro_rules! impl_serializable_for_num {
+    ($($t:ty),+) => {
+        $(
+            impl Serializable for $t {
+                fn serialize(&self, buffer: &mut Vec<u8>) {
+                    buffer.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn deserialize(buffer: &[u8], offset: &mut usize) -> Result<Self, ArgsError> {
+                    read_array(buffer, offset).map(<$t>::from_le_bytes)
+                }
+            }
+        )+
+    };
+}
+
+impl_serializable_for_num!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl Serializable for bool {
+    fn serialize(&self, buffer: &mut Vec<u8>) {
+        buffer.push(*self as u8);
+    }
+
+    fn deserialize(buffer: &[u8], offset: &mut usize) -> Result<Self, ArgsError> {
+        match u8::deserialize(buffer, offset)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(ArgsError::InvalidValue),
+        }
+    }
+}
+
+/// Serialized as: length (u32) + utf-8 bytes (like Args.addString)
+impl Serializable for String {
+    fn serialize(&self, buffer: &mut Vec<u8>) {
+        (self.len() as u32).serialize(buffer);
+        buffer.extend_from_slice(self.as_bytes());
+    }
+
+    fn deserialize(buffer: &[u8], offset: &mut usize) -> Result<Self, ArgsError> {
+        let len = u32::deserialize(buffer, offset)?;
+        let bytes = read_bytes(buffer, offset, len as usize)?;
+        core::str::from_utf8(bytes)
+            .map(String::from)
+            .map_err(|_| ArgsError::InvalidUtf8)
+    }
+}
+
+/// Serialized as: length (u32) + bytes (like Args.addUint8Array)
+impl Serializable for Vec<u8> {
+    fn serialize(&self, buffer: &mut Vec<u8>) {
+        (self.len() as u32).serialize(buffer);
+        buffer.extend_from_slice(self.as_slice());
+    }
+
+    fn deserialize(buffer: &[u8], offset: &mut usize) -> Result<Self, ArgsError> {
+        let len = u32::deserialize(buffer, offset)?;
+        read_bytes(buffer, offset, len as usize).map(<[u8]>::to_vec)
+    }
+}
+
+/// Serialize / Deserialize arguments for smart contract functions
+///
+/// Arguments are serialized one after the other, in the order they were added, and should
+/// be deserialized in the very same order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Args {
+    data: Vec<u8>,
+    offset: usize,
+}
+
+impl Args {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serialize a value (after the already serialized values)
+    pub fn add<T: Serializable>(&mut self, value: &T) -> &mut Self {
+        value.serialize(&mut self.data);
+        self
+    }
+
+    /// Serialize a string slice (same encoding as String)
+    pub fn add_str(&mut self, value: &str) -> &mut Self {
+        (value.len() as u32).serialize(&mut self.data);
+        self.data.extend_from_slice(value.as_bytes());
+        self
+    }
+
+    /// Deserialize the next value
+    pub fn next_arg<T: Serializable>(&mut self) -> Result<T, ArgsError> {
+        T::deserialize(self.data.as_slice(), &mut self.offset)
+    }
+
+    /// Current read offset
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl From<Vec<u8>> for Args {
+    fn from(data: Vec<u8>) -> Self {
+        Self { data, offset: 0 }
+    }
+}
+
+impl From<&[u8]> for Args {
+    fn from(data: &[u8]) -> Self {
+        Self::from(data.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn test_args_roundtrip() {
+        let mut args = Args::new();
+        args.add(&42u64)
+            .add(&-7i32)
+            .add(&true)
+            .add(&"massa".to_string())
+            .add(&vec![1u8, 2, 3]);
+
+        let mut args = Args::from(args.into_bytes());
+        assert_eq!(args.next_arg::<u64>(), Ok(42));
+        assert_eq!(args.next_arg::<i32>(), Ok(-7));
+        assert_eq!(args.next_arg::<bool>(), Ok(true));
+        assert_eq!(args.next_arg::<String>(), Ok("massa".to_string()));
+        assert_eq!(args.next_arg::<Vec<u8>>(), Ok(vec![1, 2, 3]));
+        assert_eq!(
+            args.next_arg::<u8>(),
+            Err(ArgsError::OutOfBounds {
+                offset: 29,
+                needed: 1
+            })
+        );
+
+        // 128 bits integers (like Args.addU128 / Args.addI128)
+        let mut args = Args::new();
+        args.add(&u128::MAX).add(&-1i128);
+        assert_eq!(args.as_bytes(), &[0xff; 32]);
+        assert_eq!(args.next_arg::<u128>(), Ok(u128::MAX));
+        assert_eq!(args.next_arg::<i128>(), Ok(-1));
+    }
+
+    #[test]
+    fn test_args_massa_web3_encoding() {
+        // new Args().addString("Massa").serialize() (massa-web3)
+        let mut args = Args::new();
+        args.add_str("Massa");
+        assert_eq!(args.as_bytes(), &[5, 0, 0, 0, 77, 97, 115, 115, 97]);
+
+        // new Args().addU64(10000000n).serialize() (massa-web3)
+        let mut args = Args::new();
+        args.add(&10000000u64);
+        assert_eq!(args.as_bytes(), &[128, 150, 152, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_args_invalid() {
+        let mut args = Args::from(vec![2u8]);
+        assert_eq!(args.next_arg::<bool>(), Err(ArgsError::InvalidValue));
+
+        // String length (4) is larger than the remaining bytes
+        let mut args = Args::from(vec![4u8, 0, 0, 0, 77]);
+        assert_eq!(
+            args.next_arg::<String>(),
+            Err(ArgsError::OutOfBounds {
+                offset: 4,
+                needed: 4
+            })
+        );
+    }
+}
@@ -0,0 +1,1048 @@
+// Wide integers, compatible with as-bignum (the AssemblyScript library used by massa-standards)
+// https://github.com/MaxGraey/as-bignum
+//
+// Values are stored as little-endian u64 limbs (for U256: lo1, lo2, hi1, hi2 in as-bignum) and
+// serialized as little-endian bytes, like toBytes() in as-bignum && Args.addU256 in massa-as-sdk
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Rem, Shl, Shr, Sub};
+use core::str::FromStr;
+// internal
+use crate::args::{read_array, ArgsError, Serializable};
+
+/// Error returned when parsing a wide integer from a string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseBigNumError {
+    /// Empty string (or only a sign)
+    Empty,
+    /// A character is not a decimal digit
+    InvalidDigit,
+    /// Value does not fit in the integer type
+    Overflow,
+}
+
+impl fmt::Display for ParseBigNumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBigNumError::Empty => write!(f, "cannot parse integer from empty string"),
+            ParseBigNumError::InvalidDigit => write!(f, "invalid digit found in string"),
+            ParseBigNumError::Overflow => write!(f, "number too large to fit in target type"),
+        }
+    }
+}
+
+// Helpers on little-endian limb slices (shared by all the wide integer types)
+mod limbs {
+    use core::cmp::Ordering;
+
+    /// Divisor max size (U256)
+    const MAX_DIVISOR_LIMBS: usize = 4;
+
+    /// a += b, return the carry
+    pub(super) fn add_assign(a: &mut [u64], b: &[u64]) -> bool {
+        let mut carry = false;
+        for (x, y) in a.iter_mut().zip(b.iter()) {
+            let (r1, c1) = x.overflowing_add(*y);
+            let (r2, c2) = r1.overflowing_add(carry as u64);
+            *x = r2;
+            carry = c1 || c2;
+        }
+        carry
+    }
+
+    /// a -= b, return the borrow
+    pub(super) fn sub_assign(a: &mut [u64], b: &[u64]) -> bool {
+        let mut borrow = false;
+        for (x, y) in a.iter_mut().zip(b.iter()) {
+            let (r1, b1) = x.overflowing_sub(*y);
+            let (r2, b2) = r1.overflowing_sub(borrow as u64);
+            *x = r2;
+            borrow = b1 || b2;
+        }
+        borrow
+    }
+
+    /// Full product: out = a * b (out.len() must be >= a.len() + b.len())
+    pub(super) fn mul(a: &[u64], b: &[u64], out: &mut [u64]) {
+        out.iter_mut().for_each(|x| *x = 0);
+        for (i, x) in a.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, y) in b.iter().enumerate() {
+                let t = (*x as u128) * (*y as u128) + out[i + j] as u128 + carry;
+                out[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            let mut k = i + b.len();
+            while carry != 0 && k < out.len() {
+                let t = out[k] as u128 + carry;
+                out[k] = t as u64;
+                carry = t >> 64;
+                k += 1;
+            }
+        }
+    }
+
+    pub(super) fn cmp(a: &[u64], b: &[u64]) -> Ordering {
+        debug_assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().rev().zip(b.iter().rev()) {
+            match x.cmp(y) {
+                Ordering::Equal => continue,
+                ord => return ord,
+            }
+        }
+        Ordering::Equal
+    }
+
+    pub(super) fn bits(a: &[u64]) -> u32 {
+        for (i, x) in a.iter().enumerate().rev() {
+            if *x != 0 {
+                return (i as u32 + 1) * 64 - x.leading_zeros();
+            }
+        }
+        0
+    }
+
+    pub(super) fn bit(a: &[u64], index: u32) -> bool {
+        (a[(index / 64) as usize] >> (index % 64)) & 1 == 1
+    }
+
+    /// a = (a << 1) | bit
+    fn shl1_with(a: &mut [u64], bit: bool) {
+        let mut carry = bit as u64;
+        for x in a.iter_mut() {
+            let next = *x >> 63;
+            *x = (*x << 1) | carry;
+            carry = next;
+        }
+    }
+
+    /// Long division: quotient = num / den && remainder = num % den
+    ///
+    /// quotient.len() == num.len() and remainder.len() == den.len(), den must not be zero
+    pub(super) fn div_rem(num: &[u64], den: &[u64], quotient: &mut [u64], remainder: &mut [u64]) {
+        quotient.iter_mut().for_each(|x| *x = 0);
+        remainder.iter_mut().for_each(|x| *x = 0);
+
+        if bits(den) <= 64 {
+            // Fast path: divide by a single limb
+            let d = den[0] as u128;
+            let mut r = 0u128;
+            for (q, n) in quotient.iter_mut().zip(num.iter()).rev() {
+                let cur = (r << 64) | *n as u128;
+                *q = (cur / d) as u64;
+                r = cur % d;
+            }
+            remainder[0] = r as u64;
+            return;
+        }
+
+        // Shift-subtract division, 1 bit at a time
+        // Note: remainder can be temporarily 1 bit larger than den (so use an extra limb)
+        let mut rem = [0u64; MAX_DIVISOR_LIMBS + 1];
+        let rem = &mut rem[..den.len() + 1];
+        let mut den_ext = [0u64; MAX_DIVISOR_LIMBS + 1];
+        let den_ext = &mut den_ext[..den.len() + 1];
+        den_ext[..den.len()].copy_from_slice(den);
+
+        for i in (0..bits(num)).rev() {
+            shl1_with(rem, bit(num, i));
+            if cmp(rem, den_ext) != Ordering::Less {
+                sub_assign(rem, den_ext);
+                quotient[(i / 64) as usize] |= 1 << (i % 64);
+            }
+        }
+        remainder.copy_from_slice(&rem[..den.len()]);
+    }
+}
+
+macro_rules! construct_uint {
+    ($(#[$attr:meta])* $name:ident, $n_limbs:literal) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+        pub struct $name([u64; $n_limbs]);
+
+        impl $name {
+            /// Size of the integer in bits
+            pub const BITS: u32 = $n_limbs * 64;
+            /// Size of the integer in bytes (once serialized)
+            pub const BYTES: usize = $n_limbs * 8;
+            pub const ZERO: Self = Self([0; $n_limbs]);
+            pub const ONE: Self = Self::from_u64(1);
+            pub const MIN: Self = Self::ZERO;
+            pub const MAX: Self = Self([u64::MAX; $n_limbs]);
+
+            /// Create from little-endian u64 limbs (least significant limb first)
+            pub const fn from_limbs(limbs: [u64; $n_limbs]) -> Self {
+                Self(limbs)
+            }
+
+            /// Little-endian u64 limbs (least significant limb first)
+            pub const fn as_limbs(&self) -> &[u64; $n_limbs] {
+                &self.0
+            }
+
+            pub const fn from_u64(value: u64) -> Self {
+                let mut limbs = [0; $n_limbs];
+                limbs[0] = value;
+                Self(limbs)
+            }
+
+            pub const fn from_u128(value: u128) -> Self {
+                let mut limbs = [0; $n_limbs];
+                limbs[0] = value as u64;
+                limbs[1] = (value >> 64) as u64;
+                Self(limbs)
+            }
+
+            /// Return the value as an u64 if it fits
+            pub fn to_u64(&self) -> Option<u64> {
+                if self.0[1..].iter().all(|x| *x == 0) {
+                    Some(self.0[0])
+                } else {
+                    None
+                }
+            }
+
+            /// Return the value as an u128 if it fits
+            pub fn to_u128(&self) -> Option<u128> {
+                if self.0[2..].iter().all(|x| *x == 0) {
+                    Some(((self.0[1] as u128) << 64) | self.0[0] as u128)
+                } else {
+                    None
+                }
+            }
+
+            /// Return the least significant 64 bits
+            pub const fn low_u64(&self) -> u64 {
+                self.0[0]
+            }
+
+            pub fn is_zero(&self) -> bool {
+                self.0.iter().all(|x| *x == 0)
+            }
+
+            /// Minimum number of bits needed to represent the value
+            pub fn bits(&self) -> u32 {
+                limbs::bits(&self.0)
+            }
+
+            pub fn leading_zeros(&self) -> u32 {
+                Self::BITS - self.bits()
+            }
+
+            /// Serialize as little-endian bytes (same as toBytes() in as-bignum)
+            pub fn to_le_bytes(&self) -> [u8; $n_limbs * 8] {
+                let mut bytes = [0u8; $n_limbs * 8];
+                for (chunk, limb) in bytes.chunks_exact_mut(8).zip(self.0.iter()) {
+                    chunk.copy_from_slice(&limb.to_le_bytes());
+                }
+                bytes
+            }
+
+            /// Deserialize from little-endian bytes (same as fromBytes() in as-bignum)
+            pub fn from_le_bytes(bytes: [u8; $n_limbs * 8]) -> Self {
+                let mut limbs = [0u64; $n_limbs];
+                for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+                    // Unwrap safe: chunks_exact returns chunks of 8 bytes
+                    *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+                }
+                Self(limbs)
+            }
+
+            pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+                let mut res = self;
+                let carry = limbs::add_assign(&mut res.0, &rhs.0);
+                (res, carry)
+            }
+
+            pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+                let mut res = self;
+                let borrow = limbs::sub_assign(&mut res.0, &rhs.0);
+                (res, borrow)
+            }
+
+            pub fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+                let mut wide = [0u64; $n_limbs * 2];
+                limbs::mul(&self.0, &rhs.0, &mut wide);
+                let mut res = [0u64; $n_limbs];
+                res.copy_from_slice(&wide[..$n_limbs]);
+                (Self(res), wide[$n_limbs..].iter().any(|x| *x != 0))
+            }
+
+            pub fn checked_add(self, rhs: Self) -> Option<Self> {
+                match self.overflowing_add(rhs) {
+                    (res, false) => Some(res),
+                    _ => None,
+                }
+            }
+
+            pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+                match self.overflowing_sub(rhs) {
+                    (res, false) => Some(res),
+                    _ => None,
+                }
+            }
+
+            pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+                match self.overflowing_mul(rhs) {
+                    (res, false) => Some(res),
+                    _ => None,
+                }
+            }
+
+            /// Return (self / rhs, self % rhs) or None if rhs == 0
+            pub fn checked_div_rem(self, rhs: Self) -> Option<(Self, Self)> {
+                if rhs.is_zero() {
+                    return None;
+                }
+                let mut quotient = Self::ZERO;
+                let mut remainder = Self::ZERO;
+                limbs::div_rem(&self.0, &rhs.0, &mut quotient.0, &mut remainder.0);
+                Some((quotient, remainder))
+            }
+
+            pub fn checked_div(self, rhs: Self) -> Option<Self> {
+                self.checked_div_rem(rhs).map(|(q, _r)| q)
+            }
+
+            pub fn checked_rem(self, rhs: Self) -> Option<Self> {
+                self.checked_div_rem(rhs).map(|(_q, r)| r)
+            }
+
+            pub fn wrapping_add(self, rhs: Self) -> Self {
+                self.overflowing_add(rhs).0
+            }
+
+            pub fn wrapping_sub(self, rhs: Self) -> Self {
+                self.overflowing_sub(rhs).0
+            }
+
+            pub fn wrapping_mul(self, rhs: Self) -> Self {
+                self.overflowing_mul(rhs).0
+            }
+
+            pub fn saturating_add(self, rhs: Self) -> Self {
+                self.checked_add(rhs).unwrap_or(Self::MAX)
+            }
+
+            pub fn saturating_sub(self, rhs: Self) -> Self {
+                self.checked_sub(rhs).unwrap_or(Self::ZERO)
+            }
+
+            pub fn saturating_mul(self, rhs: Self) -> Self {
+                self.checked_mul(rhs).unwrap_or(Self::MAX)
+            }
+
+            pub fn checked_pow(self, mut exp: u32) -> Option<Self> {
+                let mut base = self;
+                let mut res = Self::ONE;
+                while exp > 0 {
+                    if exp & 1 == 1 {
+                        res = res.checked_mul(base)?;
+                    }
+                    exp >>= 1;
+                    if exp > 0 {
+                        base = base.checked_mul(base)?;
+                    }
+                }
+                Some(res)
+            }
+
+            /// Compute self * mul / div without intermediate overflow
+            ///
+            /// The product is computed on twice the number of bits, so this only returns None
+            /// if div == 0 or if the final result does not fit.
+            /// Result is rounded down (like mulDiv in Solidity / Uniswap FullMath).
+            pub fn mul_div(self, mul: Self, div: Self) -> Option<Self> {
+                self.mul_div_rem(mul, div).map(|(q, _r)| q)
+            }
+
+            /// Same as mul_div but the result is rounded up
+            pub fn mul_div_ceil(self, mul: Self, div: Self) -> Option<Self> {
+                let (q, r) = self.mul_div_rem(mul, div)?;
+                if r.is_zero() {
+                    Some(q)
+                } else {
+                    q.checked_add(Self::ONE)
+                }
+            }
+
+            fn mul_div_rem(self, mul: Self, div: Self) -> Option<(Self, Self)> {
+                if div.is_zero() {
+                    return None;
+                }
+                let mut wide = [0u64; $n_limbs * 2];
+                limbs::mul(&self.0, &mul.0, &mut wide);
+                let mut quotient = [0u64; $n_limbs * 2];
+                let mut remainder = Self::ZERO;
+                limbs::div_rem(&wide, &div.0, &mut quotient, &mut remainder.0);
+                if quotient[$n_limbs..].iter().any(|x| *x != 0) {
+                    return None;
+                }
+                let mut res = Self::ZERO;
+                res.0.copy_from_slice(&quotient[..$n_limbs]);
+                Some((res, remainder))
+            }
+
+            /// Integer square root (rounded down)
+            pub fn sqrt(self) -> Self {
+                if self.is_zero() {
+                    return Self::ZERO;
+                }
+                // Newton's method, starting from a value >= sqrt(self)
+                let mut x = Self::ONE << self.bits().div_ceil(2);
+                loop {
+                    // Unwrap safe: x > 0
+                    let y = (x + self.checked_div(x).unwrap()) >> 1;
+                    if y >= x {
+                        return x;
+                    }
+                    x = y;
+                }
+            }
+        }
+
+        impl From<u64> for $name {
+            fn from(value: u64) -> Self {
+                Self::from_u64(value)
+            }
+        }
+
+        impl From<u128> for $name {
+            fn from(value: u128) -> Self {
+                Self::from_u128(value)
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                limbs::cmp(&self.0, &other.0)
+            }
+        }
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                self.checked_add(rhs).expect("attempt to add with overflow")
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                self.checked_sub(rhs).expect("attempt to subtract with overflow")
+            }
+        }
+
+        impl Mul for $name {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                self.checked_mul(rhs).expect("attempt to multiply with overflow")
+            }
+        }
+
+        impl Div for $name {
+            type Output = Self;
+            fn div(self, rhs: Self) -> Self {
+                self.checked_div(rhs).expect("attempt to divide by zero")
+            }
+        }
+
+        impl Rem for $name {
+            type Output = Self;
+            fn rem(self, rhs: Self) -> Self {
+                self.checked_rem(rhs)
+                    .expect("attempt to calculate the remainder with a divisor of zero")
+            }
+        }
+
+        impl Shl<u32> for $name {
+            type Output = Self;
+            fn shl(self, shift: u32) -> Self {
+                let mut res = Self::ZERO;
+                let limb_shift = (shift / 64) as usize;
+                let bit_shift = shift % 64;
+                for i in (limb_shift..$n_limbs).rev() {
+                    res.0[i] = self.0[i - limb_shift] << bit_shift;
+                    if bit_shift > 0 && i > limb_shift {
+                        res.0[i] |= self.0[i - limb_shift - 1] >> (64 - bit_shift);
+                    }
+                }
+                res
+            }
+        }
+
+        impl Shr<u32> for $name {
+            type Output = Self;
+            fn shr(self, shift: u32) -> Self {
+                let mut res = Self::ZERO;
+                let limb_shift = (shift / 64) as usize;
+                let bit_shift = shift % 64;
+                for i in 0..($n_limbs as usize).saturating_sub(limb_shift) {
+                    res.0[i] = self.0[i + limb_shift] >> bit_shift;
+                    if bit_shift > 0 && i + limb_shift + 1 < $n_limbs {
+                        res.0[i] |= self.0[i + limb_shift + 1] << (64 - bit_shift);
+                    }
+                }
+                res
+            }
+        }
+
+        impl BitAnd for $name {
+            type Output = Self;
+            fn bitand(mut self, rhs: Self) -> Self {
+                self.0.iter_mut().zip(rhs.0.iter()).for_each(|(x, y)| *x &= y);
+                self
+            }
+        }
+
+        impl BitOr for $name {
+            type Output = Self;
+            fn bitor(mut self, rhs: Self) -> Self {
+                self.0.iter_mut().zip(rhs.0.iter()).for_each(|(x, y)| *x |= y);
+                self
+            }
+        }
+
+        impl BitXor for $name {
+            type Output = Self;
+            fn bitxor(mut self, rhs: Self) -> Self {
+                self.0.iter_mut().zip(rhs.0.iter()).for_each(|(x, y)| *x ^= y);
+                self
+            }
+        }
+
+        impl Not for $name {
+            type Output = Self;
+            fn not(mut self) -> Self {
+                self.0.iter_mut().for_each(|x| *x = !*x);
+                self
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                if self.is_zero() {
+                    return f.pad_integral(true, "", "0");
+                }
+                // Max number of decimal digits for a 256 bits number is 78
+                let mut buf = [0u8; $n_limbs * 20];
+                let mut i = buf.len();
+                let ten_pow_19 = Self::from_u64(10_000_000_000_000_000_000);
+                let mut n = *self;
+                while !n.is_zero() {
+                    // Unwrap safe: divisor is not 0
+                    let (q, r) = n.checked_div_rem(ten_pow_19).unwrap();
+                    let mut chunk = r.low_u64();
+                    for _ in 0..19 {
+                        i -= 1;
+                        buf[i] = b'0' + (chunk % 10) as u8;
+                        chunk /= 10;
+                        if q.is_zero() && chunk == 0 {
+                            break;
+                        }
+                    }
+                    n = q;
+                }
+                // Unwrap safe: only ascii digits
+                f.pad_integral(true, "", core::str::from_utf8(&buf[i..]).unwrap())
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(self, f)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseBigNumError;
+
+            /// Parse a decimal string (e.g. "115792089237316195423570985008687907853269984665640564039457584007913129639935")
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                if s.is_empty() {
+                    return Err(ParseBigNumError::Empty);
+                }
+                let ten = Self::from_u64(10);
+                let mut res = Self::ZERO;
+                for b in s.bytes() {
+                    if !b.is_ascii_digit() {
+                        return Err(ParseBigNumError::InvalidDigit);
+                    }
+                    res = res
+                        .checked_mul(ten)
+                        .and_then(|r| r.checked_add(Self::from_u64((b - b'0') as u64)))
+                        .ok_or(ParseBigNumError::Overflow)?;
+                }
+                Ok(res)
+            }
+        }
+
+        /// Serialized as little-endian bytes (like Args.addU128 / Args.addU256 in massa-as-sdk)
+        impl Serializable for $name {
+            fn serialize(&self, buffer: &mut Vec<u8>) {
+                buffer.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn deserialize(buffer: &[u8], offset: &mut usize) -> Result<Self, ArgsError> {
+                read_array(buffer, offset).map(Self::from_le_bytes)
+            }
+        }
+    };
+}
+
+macro_rules! construct_int {
+    ($(#[$attr:meta])* $name:ident, $uint:ident, $n_limbs:literal) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+        pub struct $name($uint);
+
+        impl $name {
+            /// Size of the integer in bits
+            pub const BITS: u32 = $n_limbs * 64;
+            /// Size of the integer in bytes (once serialized)
+            pub const BYTES: usize = $n_limbs * 8;
+            pub const ZERO: Self = Self($uint::ZERO);
+            pub const ONE: Self = Self($uint::ONE);
+            pub const MIN: Self = {
+                let mut limbs = [0u64; $n_limbs];
+                limbs[$n_limbs - 1] = 1 << 63;
+                Self($uint::from_limbs(limbs))
+            };
+            pub const MAX: Self = {
+                let mut limbs = [u64::MAX; $n_limbs];
+                limbs[$n_limbs - 1] = u64::MAX >> 1;
+                Self($uint::from_limbs(limbs))
+            };
+
+            pub const fn from_i64(value: i64) -> Self {
+                let fill = if value < 0 { u64::MAX } else { 0 };
+                let mut limbs = [fill; $n_limbs];
+                limbs[0] = value as u64;
+                Self($uint::from_limbs(limbs))
+            }
+
+            pub const fn from_i128(value: i128) -> Self {
+                let fill = if value < 0 { u64::MAX } else { 0 };
+                let mut limbs = [fill; $n_limbs];
+                limbs[0] = value as u64;
+                limbs[1] = (value >> 64) as u64;
+                Self($uint::from_limbs(limbs))
+            }
+
+            /// Reinterpret the bits of an unsigned integer (two's complement)
+            pub const fn from_bits(bits: $uint) -> Self {
+                Self(bits)
+            }
+
+            /// Raw bits of the integer (two's complement)
+            pub const fn to_bits(self) -> $uint {
+                self.0
+            }
+
+            pub const fn is_negative(&self) -> bool {
+                (self.0.as_limbs()[$n_limbs - 1] >> 63) == 1
+            }
+
+            pub fn is_zero(&self) -> bool {
+                self.0.is_zero()
+            }
+
+            /// Return the absolute value as an unsigned integer (never overflows)
+            pub fn unsigned_abs(self) -> $uint {
+                if self.is_negative() {
+                    (!self.0).wrapping_add($uint::ONE)
+                } else {
+                    self.0
+                }
+            }
+
+            pub fn overflowing_neg(self) -> (Self, bool) {
+                let res = Self((!self.0).wrapping_add($uint::ONE));
+                (res, self == Self::MIN)
+            }
+
+            pub fn checked_neg(self) -> Option<Self> {
+                match self.overflowing_neg() {
+                    (res, false) => Some(res),
+                    _ => None,
+                }
+            }
+
+            pub fn checked_abs(self) -> Option<Self> {
+                if self.is_negative() {
+                    self.checked_neg()
+                } else {
+                    Some(self)
+                }
+            }
+
+            pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+                let res = Self(self.0.wrapping_add(rhs.0));
+                let overflow = self.is_negative() == rhs.is_negative()
+                    && res.is_negative() != self.is_negative();
+                (res, overflow)
+            }
+
+            pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+                let res = Self(self.0.wrapping_sub(rhs.0));
+                let overflow = self.is_negative() != rhs.is_negative()
+                    && res.is_negative() != self.is_negative();
+                (res, overflow)
+            }
+
+            pub fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+                let res = Self(self.0.wrapping_mul(rhs.0));
+                let overflow = Self::from_sign_abs(
+                    self.is_negative() != rhs.is_negative(),
+                    self.unsigned_abs().checked_mul(rhs.unsigned_abs()),
+                ) != Some(res);
+                (res, overflow)
+            }
+
+            pub fn checked_add(self, rhs: Self) -> Option<Self> {
+                match self.overflowing_add(rhs) {
+                    (res, false) => Some(res),
+                    _ => None,
+                }
+            }
+
+            pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+                match self.overflowing_sub(rhs) {
+                    (res, false) => Some(res),
+                    _ => None,
+                }
+            }
+
+            pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+                match self.overflowing_mul(rhs) {
+                    (res, false) => Some(res),
+                    _ => None,
+                }
+            }
+
+            /// Division rounded toward zero (like Rust primitive integers)
+            ///
+            /// Return None if rhs == 0 or for MIN / -1
+            pub fn checked_div(self, rhs: Self) -> Option<Self> {
+                let q = self.unsigned_abs().checked_div(rhs.unsigned_abs())?;
+                Self::from_sign_abs(self.is_negative() != rhs.is_negative(), Some(q))
+            }
+
+            /// Remainder (has the sign of self, like Rust primitive integers)
+            pub fn checked_rem(self, rhs: Self) -> Option<Self> {
+                let r = self.unsigned_abs().checked_rem(rhs.unsigned_abs())?;
+                Self::from_sign_abs(self.is_negative(), Some(r))
+            }
+
+            /// Compute self * mul / div without intermediate overflow (rounded toward zero)
+            pub fn mul_div(self, mul: Self, div: Self) -> Option<Self> {
+                let res = self
+                    .unsigned_abs()
+                    .mul_div(mul.unsigned_abs(), div.unsigned_abs());
+                let negative =
+                    self.is_negative() ^ mul.is_negative() ^ div.is_negative();
+                Self::from_sign_abs(negative, res)
+            }
+
+            fn from_sign_abs(negative: bool, abs: Option<$uint>) -> Option<Self> {
+                let abs = abs?;
+                if negative {
+                    if abs > Self::MIN.0 {
+                        None
+                    } else {
+                        Some(Self((!abs).wrapping_add($uint::ONE)))
+                    }
+                } else if abs > Self::MAX.0 {
+                    None
+                } else {
+                    Some(Self(abs))
+                }
+            }
+
+            /// Serialize as little-endian bytes (two's complement)
+            pub fn to_le_bytes(&self) -> [u8; $n_limbs * 8] {
+                self.0.to_le_bytes()
+            }
+
+            /// Deserialize from little-endian bytes (two's complement)
+            pub fn from_le_bytes(bytes: [u8; $n_limbs * 8]) -> Self {
+                Self($uint::from_le_bytes(bytes))
+            }
+        }
+
+        impl From<i64> for $name {
+            fn from(value: i64) -> Self {
+                Self::from_i64(value)
+            }
+        }
+
+        impl From<i128> for $name {
+            fn from(value: i128) -> Self {
+                Self::from_i128(value)
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                match (self.is_negative(), other.is_negative()) {
+                    (true, false) => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    // Same sign: two's complement order == unsigned order
+                    _ => self.0.cmp(&other.0),
+                }
+            }
+        }
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+            fn neg(self) -> Self {
+                self.checked_neg().expect("attempt to negate with overflow")
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                self.checked_add(rhs).expect("attempt to add with overflow")
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                self.checked_sub(rhs).expect("attempt to subtract with overflow")
+            }
+        }
+
+        impl Mul for $name {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                self.checked_mul(rhs).expect("attempt to multiply with overflow")
+            }
+        }
+
+        impl Div for $name {
+            type Output = Self;
+            fn div(self, rhs: Self) -> Self {
+                self.checked_div(rhs).expect("attempt to divide by zero or with overflow")
+            }
+        }
+
+        impl Rem for $name {
+            type Output = Self;
+            fn rem(self, rhs: Self) -> Self {
+                self.checked_rem(rhs)
+                    .expect("attempt to calculate the remainder with a divisor of zero")
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                if self.is_negative() {
+                    write!(f, "-{}", self.unsigned_abs())
+                } else {
+                    write!(f, "{}", self.0)
+                }
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(self, f)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseBigNumError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let (negative, digits) = match s.strip_prefix('-') {
+                    Some(digits) => (true, digits),
+                    None => (false, s.strip_prefix('+').unwrap_or(s)),
+                };
+                let abs = $uint::from_str(digits)?;
+                Self::from_sign_abs(negative, Some(abs)).ok_or(ParseBigNumError::Overflow)
+            }
+        }
+
+        /// Serialized as little-endian bytes (like Args.addI128 / Args.addI256 in massa-as-sdk)
+        impl Serializable for $name {
+            fn serialize(&self, buffer: &mut Vec<u8>) {
+                self.0.serialize(buffer);
+            }
+
+            fn deserialize(buffer: &[u8], offset: &mut usize) -> Result<Self, ArgsError> {
+                $uint::deserialize(buffer, offset).map(Self)
+            }
+        }
+    };
+}
+
+construct_uint!(
+    /// 128 bits unsigned integer (u128 in as-bignum)
+    U128,
+    2
+);
+construct_uint!(
+    /// 256 bits unsigned integer (u256 in as-bignum)
+    U256,
+    4
+);
+construct_int!(
+    /// 128 bits signed integer (i128 in as-bignum)
+    I128,
+    U128,
+    2
+);
+construct_int!(
+    /// 256 bits signed integer (i256 in as-bignum)
+    I256,
+    U256,
+    4
+);
+
+impl From<U128> for U256 {
+    fn from(value: U128) -> Self {
+        let [lo, hi] = value.0;
+        Self([lo, hi, 0, 0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Args;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_u256_arithmetic() {
+        let a = U256::from_u128(u128::MAX);
+        let b = U256::ONE;
+        let c = a + b;
+        assert_eq!(c.as_limbs(), &[0, 0, 1, 0]);
+        assert_eq!(c - b, a);
+        assert_eq!(U256::MAX.checked_add(b), None);
+        assert_eq!(U256::ZERO.checked_sub(b), None);
+        assert_eq!(U256::MAX.overflowing_add(b), (U256::ZERO, true));
+        assert_eq!(U256::ZERO.overflowing_sub(b), (U256::MAX, true));
+
+        // (2^128 - 1)^2 fits in 256 bits
+        let sq = a * a;
+        assert_eq!(sq / a, a);
+        assert_eq!(sq % a, U256::ZERO);
+        assert_eq!(U256::MAX.checked_mul(U256::from_u64(2)), None);
+        assert_eq!(a.checked_div(U256::ZERO), None);
+
+        let (q, r) = U256::from_u64(1000)
+            .checked_div_rem(U256::from_u64(7))
+            .unwrap();
+        assert_eq!((q, r), (U256::from_u64(142), U256::from_u64(6)));
+        assert_eq!(U256::from_u64(3).checked_pow(5), Some(U256::from_u64(243)));
+        assert_eq!(U256::from_u64(2).checked_pow(256), None);
+    }
+
+    #[test]
+    fn test_u256_mul_div_sqrt() {
+        // MAX * MAX / MAX does not overflow with mul_div
+        assert_eq!(U256::MAX.mul_div(U256::MAX, U256::MAX), Some(U256::MAX));
+        assert_eq!(U256::MAX.mul_div(U256::from_u64(2), U256::ONE), None);
+        assert_eq!(U256::ONE.mul_div(U256::ONE, U256::ZERO), None);
+        assert_eq!(
+            U256::from_u64(10).mul_div(U256::from_u64(10), U256::from_u64(3)),
+            Some(U256::from_u64(33))
+        );
+        assert_eq!(
+            U256::from_u64(10).mul_div_ceil(U256::from_u64(10), U256::from_u64(3)),
+            Some(U256::from_u64(34))
+        );
+
+        assert_eq!(U256::ZERO.sqrt(), U256::ZERO);
+        assert_eq!(U256::from_u64(15).sqrt(), U256::from_u64(3));
+        assert_eq!(U256::from_u64(16).sqrt(), U256::from_u64(4));
+        assert_eq!(U256::MAX.sqrt(), U256::from_u128(u128::MAX));
+    }
+
+    #[test]
+    fn test_u256_string() {
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(U256::MAX.to_string(), max);
+        assert_eq!(U256::from_str(max), Ok(U256::MAX));
+        assert_eq!(U256::ZERO.to_string(), "0");
+        assert_eq!(
+            U256::from_u64(10_000_000_000_000_000_000).to_string(),
+            "10000000000000000000"
+        );
+        assert_eq!(U128::MAX.to_string(), u128::MAX.to_string());
+        assert_eq!(
+            U256::from_str(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+            ),
+            Err(ParseBigNumError::Overflow)
+        );
+        assert_eq!(U256::from_str("12a"), Err(ParseBigNumError::InvalidDigit));
+        assert_eq!(U256::from_str(""), Err(ParseBigNumError::Empty));
+    }
+
+    #[test]
+    fn test_u256_as_bignum_encoding() {
+        // u256.fromU64(1).toBytes() (as-bignum)
+        let mut expected = [0u8; 32];
+        expected[0] = 1;
+        assert_eq!(U256::ONE.to_le_bytes(), expected);
+
+        // new u256(1, 2, 3, 4) (lo1, lo2, hi1, hi2)
+        let v = U256::from_limbs([1, 2, 3, 4]);
+        let bytes = v.to_le_bytes();
+        assert_eq!(&bytes[0..8], &1u64.to_le_bytes());
+        assert_eq!(&bytes[24..32], &4u64.to_le_bytes());
+        assert_eq!(U256::from_le_bytes(bytes), v);
+
+        let mut args = Args::new();
+        args.add(&v).add(&U128::from_u128(42));
+        assert_eq!(args.as_bytes().len(), 32 + 16);
+        assert_eq!(args.next_arg::<U256>(), Ok(v));
+        assert_eq!(args.next_arg::<U128>(), Ok(U128::from_u128(42)));
+    }
+
+    #[test]
+    fn test_i256() {
+        let a = I256::from_i64(-5);
+        let b = I256::from_i64(3);
+        assert_eq!(a + b, I256::from_i64(-2));
+        assert_eq!(a * b, I256::from_i64(-15));
+        assert_eq!(a / b, I256::from_i64(-1));
+        assert_eq!(a % b, I256::from_i64(-2));
+        assert!(a < b);
+        assert!(I256::MIN < a);
+        assert_eq!(I256::MAX.checked_add(I256::ONE), None);
+        assert_eq!(I256::MIN.checked_sub(I256::ONE), None);
+        assert_eq!(I256::MIN.checked_neg(), None);
+        assert_eq!(I256::MIN.checked_div(I256::from_i64(-1)), None);
+        assert_eq!(I256::MAX.overflowing_add(I256::ONE), (I256::MIN, true));
+        assert_eq!(
+            I256::MAX.mul_div(I256::from_i64(-2), I256::from_i64(2)),
+            Some(-I256::MAX)
+        );
+
+        assert_eq!(a.to_string(), "-5");
+        assert_eq!(I256::from_str("-5"), Ok(a));
+        assert_eq!(I128::MIN.to_string(), i128::MIN.to_string());
+        assert_eq!(I128::from_str(&i128::MIN.to_string()), Ok(I128::MIN));
+        assert_eq!(I128::from_i128(-1).to_le_bytes(), (-1i128).to_le_bytes());
+    }
+}
@@ -0,0 +1,16 @@
+//! Args encoding of the Massa smart contract function arguments (and of the returned values)
+//!
+//! Same encoding as the `Args` class of massa-as-sdk && massa-web3. Shared by massa_rust_sc (in a
+//! smart contract) and massa_rust_web3 (to build the parameter of a call or decode a returned
+//! value), both re-export these types.
+
+#![no_std]
+
+extern crate alloc;
+
+mod args;
+mod bignum;
+
+// export
+pub use args::{Args, ArgsError, Serializable};
+pub use bignum::{ParseBigNumError, I128, I256, U128, U256};
@@ -0,0 +1,44 @@
+//! A smart contract using the wasmv1 ABI of the Massa node (experimental)
+//!
+//! cargo build --release --target wasm32-unknown-unknown -p massa_rust_sc --example hello_wasmv1 --features wasmv1
+//! massa_wasm_tools post target/wasm32-unknown-unknown/release/examples/hello_wasmv1.wasm
+//!
+//! Note: post-processing prefixes the bytecode with 0x01 (required to deploy a wasmv1 contract)
+
+#![no_std]
+
+extern crate alloc;
+// rust crates
+use alloc::format;
+use alloc::string::String;
+// internal
+use massa_rust_sc::{event, export, revert, wasmv1};
+
+// constants
+
+const KEY: &[u8] = b"greeting_key";
+const VALUE: &[u8] = b"hello";
+
+// end constants
+
+#[export]
+fn constructor() {
+    // Use a structured event (formatted as: "Greeting:hello there,42")
+    event!("Greeting", "hello there", 42);
+
+    // Storage set
+    // Note: wasmv1 host functions return an error instead of aborting the execution
+    wasmv1::set_data(KEY, VALUE).unwrap_or_else(|e| revert(&format!("{e}")));
+}
+
+/// Read only function with typed arguments & returned value (Args encoded)
+#[export]
+fn greet(name: String) -> String {
+    format!("Hello, {name}!")
+}
+
+#[panic_handler]
+fn panic(_panic: &core::panic::PanicInfo<'_>) -> ! {
+    // emit a wasm unreachable instruction if a panic occurs in our code
+    core::arch::wasm32::unreachable()
+}
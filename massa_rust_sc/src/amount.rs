@@ -0,0 +1,261 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+// internal
+use crate::{ArgsError, Serializable};
+
+/// Number of decimals of a Massa coin amount
+pub const AMOUNT_DECIMALS: u32 = 9;
+const AMOUNT_DECIMAL_FACTOR: u64 = 10u64.pow(AMOUNT_DECIMALS);
+
+/// Error returned when an [Amount] cannot be created
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmountError {
+    /// Not a decimal number (e.g. "1.5")
+    InvalidFormat,
+    /// More than [AMOUNT_DECIMALS] decimals
+    TooManyDecimals,
+    /// Value does not fit in an Amount
+    Overflow,
+}
+
+impl fmt::Display for AmountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AmountError::InvalidFormat => write!(f, "invalid amount format"),
+            AmountError::TooManyDecimals => {
+                write!(f, "amount has more than {AMOUNT_DECIMALS} decimals")
+            }
+            AmountError::Overflow => write!(f, "amount overflow"),
+        }
+    }
+}
+
+/// A fixed-point amount of Massa coins
+///
+/// Internally stored as a number of nanoMAS (10^-9 MAS) in an u64, like Amount in massa_models,
+/// so "1.5" (MAS) == 1_500_000_000 (raw value).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Amount(u64);
+
+impl Amount {
+    /// Minimum amount: 0
+    pub const MIN: Amount = Amount(0);
+    /// Maximum amount: u64::MAX nanoMAS
+    pub const MAX: Amount = Amount(u64::MAX);
+
+    /// Create an Amount from a number of nanoMAS
+    pub const fn from_raw(raw: u64) -> Self {
+        Self(raw)
+    }
+
+    /// Return the amount as a number of nanoMAS
+    pub const fn to_raw(self) -> u64 {
+        self.0
+    }
+
+    pub const fn zero() -> Self {
+        Self(0)
+    }
+
+    pub const fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Create an Amount from a mantissa and a scale (number of decimals)
+    ///
+    /// `Amount::from_mantissa_scale(15, 1)` == 1.5 MAS
+    pub const fn from_mantissa_scale(mantissa: u64, scale: u32) -> Result<Self, AmountError> {
+        if scale > AMOUNT_DECIMALS {
+            return Err(AmountError::TooManyDecimals);
+        }
+        match mantissa.checked_mul(10u64.pow(AMOUNT_DECIMALS - scale)) {
+            Some(raw) => Ok(Self(raw)),
+            None => Err(AmountError::Overflow),
+        }
+    }
+
+    pub const fn checked_add(self, amount: Self) -> Option<Self> {
+        match self.0.checked_add(amount.0) {
+            Some(raw) => Some(Self(raw)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_sub(self, amount: Self) -> Option<Self> {
+        match self.0.checked_sub(amount.0) {
+            Some(raw) => Some(Self(raw)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_mul_u64(self, factor: u64) -> Option<Self> {
+        match self.0.checked_mul(factor) {
+            Some(raw) => Some(Self(raw)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_div_u64(self, divisor: u64) -> Option<Self> {
+        match self.0.checked_div(divisor) {
+            Some(raw) => Some(Self(raw)),
+            None => None,
+        }
+    }
+
+    /// Divide two amounts, returns how many times amount fits in self
+    pub const fn checked_div(self, amount: Self) -> Option<u64> {
+        self.0.checked_div(amount.0)
+    }
+
+    pub const fn checked_rem(self, amount: Self) -> Option<Self> {
+        match self.0.checked_rem(amount.0) {
+            Some(raw) => Some(Self(raw)),
+            None => None,
+        }
+    }
+
+    pub const fn saturating_add(self, amount: Self) -> Self {
+        Self(self.0.saturating_add(amount.0))
+    }
+
+    pub const fn saturating_sub(self, amount: Self) -> Self {
+        Self(self.0.saturating_sub(amount.0))
+    }
+
+    pub const fn saturating_mul_u64(self, factor: u64) -> Self {
+        Self(self.0.saturating_mul(factor))
+    }
+}
+
+impl fmt::Display for Amount {
+    /// Display the amount in MAS, without trailing zeros (e.g. "1.5")
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let integer = self.0 / AMOUNT_DECIMAL_FACTOR;
+        let mut decimals = self.0 % AMOUNT_DECIMAL_FACTOR;
+        if decimals == 0 {
+            return write!(f, "{integer}");
+        }
+
+        let mut width = AMOUNT_DECIMALS as usize;
+        while decimals % 10 == 0 {
+            decimals /= 10;
+            width -= 1;
+        }
+        write!(f, "{integer}.{decimals:0width$}")
+    }
+}
+
+impl FromStr for Amount {
+    type Err = AmountError;
+
+    /// Parse an amount in MAS (e.g. "1.5", "42", "0.000000001")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (integer, decimals) = s.split_once('.').unwrap_or((s, ""));
+
+        if (integer.is_empty() && decimals.is_empty())
+            || !integer.bytes().all(|b| b.is_ascii_digit())
+            || !decimals.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(AmountError::InvalidFormat);
+        }
+        if decimals.len() > AMOUNT_DECIMALS as usize {
+            return Err(AmountError::TooManyDecimals);
+        }
+
+        let integer = if integer.is_empty() {
+            0
+        } else {
+            integer.parse::<u64>().map_err(|_| AmountError::Overflow)?
+        };
+        // Right pad decimals with 0 so "5" (in "1.5") becomes 500_000_000
+        let decimals = decimals
+            .bytes()
+            .chain(core::iter::repeat(b'0'))
+            .take(AMOUNT_DECIMALS as usize)
+            .fold(0u64, |acc, b| acc * 10 + u64::from(b - b'0'));
+
+        integer
+            .checked_mul(AMOUNT_DECIMAL_FACTOR)
+            .and_then(|raw| raw.checked_add(decimals))
+            .map(Self)
+            .ok_or(AmountError::Overflow)
+    }
+}
+
+/// Serialized as an u64 (number of nanoMAS), like Args.addU64 for coins in massa-as-sdk
+impl Serializable for Amount {
+    fn serialize(&self, buffer: &mut Vec<u8>) {
+        self.0.serialize(buffer);
+    }
+
+    fn deserialize(buffer: &[u8], offset: &mut usize) -> Result<Self, ArgsError> {
+        u64::deserialize(buffer, offset).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Args;
+    use alloc::string::ToString;
+    use wasm_test::*;
+
+    #[wasm_test]
+    fn test_amount_from_str() {
+        assert_eq!(Amount::from_str("1.5"), Ok(Amount::from_raw(1_500_000_000)));
+        assert_eq!(Amount::from_str("42"), Ok(Amount::from_raw(42_000_000_000)));
+        assert_eq!(Amount::from_str("0.000000001"), Ok(Amount::from_raw(1)));
+        assert_eq!(Amount::from_str(".01"), Ok(Amount::from_raw(10_000_000)));
+        assert_eq!(Amount::from_str("2."), Ok(Amount::from_raw(2_000_000_000)));
+
+        assert_eq!(Amount::from_str(""), Err(AmountError::InvalidFormat));
+        assert_eq!(Amount::from_str("."), Err(AmountError::InvalidFormat));
+        assert_eq!(Amount::from_str("-1"), Err(AmountError::InvalidFormat));
+        assert_eq!(Amount::from_str("1.2.3"), Err(AmountError::InvalidFormat));
+        assert_eq!(
+            Amount::from_str("0.0000000001"),
+            Err(AmountError::TooManyDecimals)
+        );
+        assert_eq!(Amount::from_str("18446744074"), Err(AmountError::Overflow));
+    }
+
+    #[wasm_test]
+    fn test_amount_to_string() {
+        assert_eq!(Amount::from_raw(1_500_000_000).to_string(), "1.5");
+        assert_eq!(Amount::from_raw(42_000_000_000).to_string(), "42");
+        assert_eq!(Amount::from_raw(1).to_string(), "0.000000001");
+        assert_eq!(Amount::from_raw(10_000_000).to_string(), "0.01");
+        assert_eq!(Amount::zero().to_string(), "0");
+        assert_eq!(Amount::MAX.to_string(), "18446744073.709551615");
+    }
+
+    #[wasm_test]
+    fn test_amount_checked_ops() {
+        let a = Amount::from_mantissa_scale(15, 1).unwrap();
+        let b = Amount::from_str("0.5").unwrap();
+
+        assert_eq!(a.checked_add(b), Amount::from_str("2").ok());
+        assert_eq!(a.checked_sub(b), Amount::from_str("1").ok());
+        assert_eq!(b.checked_sub(a), None);
+        assert_eq!(a.checked_mul_u64(3), Amount::from_str("4.5").ok());
+        assert_eq!(Amount::MAX.checked_mul_u64(2), None);
+        assert_eq!(a.checked_div_u64(2), Amount::from_str("0.75").ok());
+        assert_eq!(a.checked_div_u64(0), None);
+        assert_eq!(a.checked_div(b), Some(3));
+        assert_eq!(a.checked_div(Amount::zero()), None);
+        assert_eq!(
+            Amount::from_mantissa_scale(1, 10),
+            Err(AmountError::TooManyDecimals)
+        );
+    }
+
+    #[wasm_test]
+    fn test_amount_args() {
+        let mut args = Args::new();
+        args.add(&Amount::from_str("0.01").unwrap());
+        // Same as: new Args().addU64(10000000n)
+        assert_eq!(args.as_bytes(), &[128, 150, 152, 0, 0, 0, 0, 0]);
+        assert_eq!(args.next_arg::<Amount>(), Ok(Amount::from_raw(10_000_000)));
+    }
+}
@@ -0,0 +1,281 @@
+//! AssemblyScript ABI of the Massa node (`assembly_script_*` functions)
+//!
+//! Not available with the `wasmv1` feature: the node rejects a wasmv1 smart contract importing
+//! these functions (see the wasmv1 module).
+
+// internal
+use crate::memory::AsMemoryModel;
+use crate::Amount;
+
+#[link(wasm_import_module = "massa")]
+extern "C" {
+
+    // External function signatures
+
+    /// Generate an event in the blockchain
+    ///
+    /// * event: a pointer to an utf-16 string (prefixed with array size, see [string_to_as_array!](string_to_as_array!))
+    #[link_name = "assembly_script_generate_event"]
+    pub fn assembly_script_generate_event(event: i32) -> ();
+
+    /// Store a value in smart contract storage
+    ///
+    /// * key: a pointer to a byte slice (prefixed with array size)
+    /// * value: a pointer to a byte slice (prefixed with array size)
+    #[link_name = "assembly_script_set_data"]
+    pub fn assembly_script_set_data(key: i32, value: i32) -> ();
+
+    /// Get a value stored in smart contract storage
+    ///
+    /// * key: a pointer to a byte slice (prefixed with array size)
+    ///
+    /// Return: a pointer to a byte slice (prefixed with array size)
+    #[link_name = "assembly_script_get_data"]
+    pub fn assembly_script_get_data(key: i32) -> i32;
+
+    /// Check if a value is stored in smart contract storage
+    ///
+    /// * key: a pointer to a byte slice (prefixed with array size)
+    ///
+    /// Return: a boolean value
+    #[link_name = "assembly_script_has_data"]
+    pub fn assembly_script_has_data(key: i32) -> bool;
+
+    /// Return true if the caller has write access to the contract
+    #[link_name = "assembly_script_caller_has_write_access"]
+    pub fn assembly_script_caller_has_write_access() -> bool;
+
+    /// Returns the addresses in the call stack, from the bottom to the top.
+    ///
+    /// Return: a string of the addresses (utf16 string in json format)
+    #[link_name = "assembly_script_get_call_stack"]
+    pub fn assembly_script_get_call_stack() -> i32;
+
+    /// Returns the addresses of the smart contracts owned by the current account
+    ///
+    /// Return: a string of the addresses (utf16 string in json format)
+    #[link_name = "assembly_script_get_owned_addresses"]
+    pub fn assembly_script_get_owned_addresses() -> i32;
+
+    #[link_name = "assembly_script_get_balance"]
+    pub fn assembly_script_get_balance() -> u64;
+
+    #[link_name = "assembly_script_get_call_coins"]
+    pub fn assembly_script_get_call_coins() -> u64;
+
+    #[link_name = "assembly_script_chain_id"]
+    pub fn assembly_script_chain_id() -> u64;
+
+    /// Return the timestamp of the current slot (in ms)
+    #[link_name = "assembly_script_get_time"]
+    pub fn assembly_script_get_time() -> u64;
+
+    #[link_name = "assembly_script_get_current_period"]
+    pub fn assembly_script_get_current_period() -> u64;
+
+    #[link_name = "assembly_script_get_current_thread"]
+    pub fn assembly_script_get_current_thread() -> u32;
+
+    #[link_name = "assembly_script_get_remaining_gas"]
+    pub fn assembly_script_get_remaining_gas() -> u64;
+
+    /// Return a pseudo random number (NOT cryptographically secure, see [unsafe_random])
+    #[link_name = "assembly_script_unsafe_random"]
+    pub fn assembly_script_unsafe_random() -> i64;
+
+    /// Return a pseudo random number in [0, 1) (NOT cryptographically secure, see [unsafe_random])
+    #[link_name = "assembly_script_unsafe_random_f64"]
+    pub fn assembly_script_unsafe_random_f64() -> f64;
+
+    /// Return the id of the operation that initiated the execution
+    ///
+    /// Return: a pointer to an utf16 string (empty string if there is no origin operation,
+    /// e.g. in a read only execution)
+    #[link_name = "assembly_script_get_origin_operation_id"]
+    pub fn assembly_script_get_origin_operation_id() -> i32;
+
+    /// Return true if the address is a user address (Externally Owned Account)
+    ///
+    /// * address: a pointer to an utf16 string
+    #[link_name = "assembly_script_is_address_eoa"]
+    pub fn assembly_script_is_address_eoa(address: i32) -> bool;
+
+    /// Return true if the address is valid
+    ///
+    /// * address: a pointer to an utf16 string
+    #[link_name = "assembly_script_validate_address"]
+    pub fn assembly_script_validate_address(address: i32) -> bool;
+
+    /// Call a function of a smart contract in the context of the caller
+    ///
+    /// * address: a pointer to an utf16 string
+    /// * function: a pointer to an utf16 string
+    /// * param: a pointer to a byte slice (prefixed with array size)
+    ///
+    /// Return: a pointer to a byte slice (prefixed with array size)
+    #[link_name = "assembly_script_local_call"]
+    pub fn assembly_script_local_call(address: i32, function: i32, param: i32) -> i32;
+
+    /// Call a function of a smart contract (in the context of the called smart contract)
+    ///
+    /// * address: a pointer to an utf16 string
+    /// * function: a pointer to an utf16 string
+    /// * param: a pointer to a byte slice (prefixed with array size)
+    /// * call_coins: coins (in nanoMAS) transferred to the called smart contract
+    ///
+    /// Return: a pointer to a byte slice (prefixed with array size)
+    #[link_name = "assembly_script_call"]
+    pub fn assembly_script_call(address: i32, function: i32, param: i32, call_coins: i64) -> i32;
+
+    /// Create a new smart contract (owned by the current smart contract)
+    ///
+    /// * bytecode: a pointer to a byte slice (prefixed with array size)
+    ///
+    /// Return: the address of the new smart contract (a pointer to an utf16 string)
+    #[link_name = "assembly_script_create_sc"]
+    pub fn assembly_script_create_sc(bytecode: i32) -> i32;
+
+    /// Execute a function of a bytecode in the context of the caller
+    ///
+    /// * bytecode: a pointer to a byte slice (prefixed with array size)
+    /// * function: a pointer to an utf16 string
+    /// * param: a pointer to a byte slice (prefixed with array size)
+    ///
+    /// Return: a pointer to a byte slice (prefixed with array size)
+    #[link_name = "assembly_script_local_execution"]
+    pub fn assembly_script_local_execution(bytecode: i32, function: i32, param: i32) -> i32;
+
+    /// Get the bytecode of the current smart contract
+    ///
+    /// Return: a pointer to a byte slice (prefixed with array size)
+    #[link_name = "assembly_script_get_bytecode"]
+    pub fn assembly_script_get_bytecode() -> i32;
+
+    /// Get the bytecode of a smart contract
+    ///
+    /// * address: a pointer to an utf16 string
+    ///
+    /// Return: a pointer to a byte slice (prefixed with array size)
+    #[link_name = "assembly_script_get_bytecode_for"]
+    pub fn assembly_script_get_bytecode_for(address: i32) -> i32;
+}
+
+#[link(wasm_import_module = "env")]
+extern "C" {
+    /// Abort the execution (AssemblyScript runtime function, see [revert])
+    ///
+    /// * message: a pointer to an utf16 string
+    /// * file_name: a pointer to an utf16 string
+    /// * line & column: position in the file (informative only)
+    #[link_name = "abort"]
+    pub fn assembly_script_abort(message: i32, file_name: i32, line: i32, column: i32) -> ();
+}
+
+// AssemblyScript runtime functions (required by the Massa node to allocate memory in the AS ABI)
+
+#[no_mangle]
+extern "C" fn __new(size: usize, _id: i32) -> *mut u8 {
+    // https://www.assemblyscript.org/runtime.html#interface
+    // function __new(size: usize, id: u32): usize
+    // https://github.com/AssemblyScript/assemblyscript/blob/main/std/assembly/rt/itcms.ts#L260
+    // Note: id is defined as u32 in doc but as i32 in source code
+
+    const HEADER_SIZE: usize = 20;
+    let mut v = alloc::vec![0; HEADER_SIZE + size];
+    v[12..16].copy_from_slice(&[1, 0, 0, 0]);
+    v[16..HEADER_SIZE].copy_from_slice(&size.to_le_bytes());
+
+    unsafe { v.leak().as_mut_ptr().add(HEADER_SIZE) }
+}
+
+#[no_mangle]
+extern "C" fn __pin(ptr: usize) -> usize {
+    // https://www.assemblyscript.org/runtime.html#interface
+    // function __pin(ptr: usize): usize
+    // https://github.com/AssemblyScript/assemblyscript/blob/main/std/assembly/rt/itcms.ts#L334
+    ptr
+}
+
+pub fn generate_event<T: AsMemoryModel>(event: T) {
+    unsafe {
+        assembly_script_generate_event(event.as_ptr_data());
+    }
+}
+
+pub fn set_data<T: AsMemoryModel, U: AsMemoryModel>(key: T, value: U) {
+    unsafe {
+        assembly_script_set_data(key.as_ptr_data(), value.as_ptr_data());
+    }
+}
+
+pub fn get_data<T: AsMemoryModel>(key: T) -> i32 {
+    unsafe { assembly_script_get_data(key.as_ptr_data()) }
+}
+
+pub fn has_data<T: AsMemoryModel>(key: T) -> bool {
+    unsafe { assembly_script_has_data(key.as_ptr_data()) }
+}
+
+/// Return true if the caller has write access to the contract
+pub fn caller_has_write_access() -> bool {
+    unsafe { assembly_script_caller_has_write_access() }
+}
+
+/// Return the balance of the current account
+pub fn get_balance() -> Amount {
+    unsafe { Amount::from_raw(assembly_script_get_balance()) }
+}
+
+/// Returns the amount transferred in the current call.
+///
+/// The returned value is related to the `coins` argument sent along the call.
+/// It is not related to the transferCoins or transferCoinsOf functions.
+pub fn get_call_coins() -> Amount {
+    unsafe { Amount::from_raw(assembly_script_get_call_coins()) }
+}
+
+/// Return the current chain id
+///
+/// The chain id is a unique identifier for MAINNET, BUILDNET, ...
+pub fn chain_id() -> u64 {
+    unsafe { assembly_script_chain_id() }
+}
+
+/// Return the timestamp of the current slot (in milliseconds since the Unix epoch)
+pub fn get_time() -> u64 {
+    unsafe { assembly_script_get_time() }
+}
+
+/// Return the current slot: (period, thread)
+pub fn get_current_slot() -> (u64, u8) {
+    unsafe {
+        (
+            assembly_script_get_current_period(),
+            assembly_script_get_current_thread() as u8,
+        )
+    }
+}
+
+/// Returns the remaining gas for the current smart contract execution.
+///
+/// Gas is a measure of the computational resources required to execute a transaction on the blockchain.
+/// When there is no more gas, the execution of the smart contract is interrupted and all the transactions are reversed.
+pub fn get_remaining_gas() -> u64 {
+    unsafe { assembly_script_get_remaining_gas() }
+}
+
+/// Return a pseudo random number
+///
+/// Warning: the value is NOT cryptographically secure and can be predicted (and influenced)
+/// by the block producer. Do not use it when something valuable depends on it (e.g. a lottery
+/// with a significant prize) unless the caveats are acceptable.
+pub fn unsafe_random() -> i64 {
+    unsafe { assembly_script_unsafe_random() }
+}
+
+/// Return a pseudo random number in [0, 1)
+///
+/// Warning: NOT cryptographically secure (see [unsafe_random])
+pub fn unsafe_random_f64() -> f64 {
+    unsafe { assembly_script_unsafe_random_f64() }
+}
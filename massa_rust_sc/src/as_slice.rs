@@ -5,6 +5,7 @@ use core::slice;
 use bytemuck::Pod;
 // internal
 use crate::memory::AsMemoryModel;
+use crate::Args;
 
 pub struct AsArray<T, const N: usize>([T; N]);
 
@@ -17,6 +18,13 @@ impl<T, const N: usize> AsArray<T, N> {
 #[derive(Debug)]
 pub struct AsSlice<'a, T>(&'a [T]);
 
+impl<'a, T> AsSlice<'a, T> {
+    /// Return the underlying slice (with the lifetime of the AsSlice)
+    pub const fn into_slice(self) -> &'a [T] {
+        self.0
+    }
+}
+
 impl<T> Deref for AsSlice<'_, T> {
     type Target = [T];
     fn deref(&self) -> &Self::Target {
@@ -71,6 +79,12 @@ impl From<*const u8> for AsSlice<'_, u16> {
     }
 }
 
+impl From<AsSlice<'_, u8>> for Args {
+    fn from(data: AsSlice<'_, u8>) -> Self {
+        Self::from(&*data)
+    }
+}
+
 pub const fn to_as_array<const N: usize>(v: &[u8]) -> AsArray<u8, N> {
     let mut dst: [u8; N] = [0u8; N];
     let (a1, a2) = dst.split_at_mut(4);
@@ -88,3 +102,103 @@ macro_rules! to_as_slice {
         to_as_array::<{ N__ + 4 }>(K_U8__).as_slice()
     }};
 }
+
+/// Total length of the given segments (see [as_key!](as_key!))
+pub const fn segments_len(segments: &[&[u8]]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < segments.len() {
+        len += segments[i].len();
+        i += 1;
+    }
+    len
+}
+
+/// Same as [to_as_array] but concatenate several segments
+///
+/// N must be equal to: 4 + [segments_len(segments)](segments_len)
+pub const fn concat_to_as_array<const N: usize>(segments: &[&[u8]]) -> AsArray<u8, N> {
+    let mut dst: [u8; N] = [0u8; N];
+    let (a1, a2) = dst.split_at_mut(4);
+    a1.copy_from_slice((segments_len(segments) as u32).to_le_bytes().as_slice());
+
+    let mut offset = 0;
+    let mut i = 0;
+    while i < segments.len() {
+        let segment = segments[i];
+        let (_, a) = a2.split_at_mut(offset);
+        let (a, _) = a.split_at_mut(segment.len());
+        a.copy_from_slice(segment);
+        offset += segment.len();
+        i += 1;
+    }
+    AsArray(dst)
+}
+
+/// Build a const AsSlice<u8> from a byte slice (no utf16 encoding)
+///
+/// ```ignore
+/// const KEY: AsSlice<u8> = as_bytes!(b"greeting_key");
+/// ```
+#[macro_export]
+macro_rules! as_bytes {
+    ($bytes:expr) => {{
+        const B__: &[u8] = $bytes;
+        const N__: usize = B__.len();
+        $crate::to_as_array::<{ N__ + 4 }>(B__).as_slice()
+    }};
+}
+
+/// Build a const AsSlice<u8> from a string, encoded as utf8 (not utf16 like [to_as_slice!](to_as_slice!))
+///
+/// ```ignore
+/// const KEY: AsSlice<u8> = as_utf8!("greeting_key");
+/// ```
+#[macro_export]
+macro_rules! as_utf8 {
+    ($s:expr) => {{
+        const S__: &str = $s;
+        $crate::as_bytes!(S__.as_bytes())
+    }};
+}
+
+/// Build a const AsSlice<u8> by concatenating byte segments (e.g. a prefix and an id)
+///
+/// ```ignore
+/// const KEY: AsSlice<u8> = as_key!(b"balance_", &42u64.to_le_bytes());
+/// ```
+#[macro_export]
+macro_rules! as_key {
+    ($($segment:expr),+ $(,)?) => {{
+        const S__: &[&[u8]] = &[$($segment),+];
+        const N__: usize = $crate::segments_len(S__);
+        $crate::concat_to_as_array::<{ N__ + 4 }>(S__).as_slice()
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_test::*;
+
+    const ID: u64 = 42;
+    const BYTES: AsSlice<u8> = as_bytes!(b"key");
+    const UTF8: AsSlice<u8> = as_utf8!("key");
+    const KEY: AsSlice<u8> = as_key!(b"balance_", &ID.to_le_bytes());
+
+    #[wasm_test]
+    fn test_as_bytes() {
+        // header (data size as u32) then data
+        assert_eq!(&*BYTES, &[3, 0, 0, 0, b'k', b'e', b'y']);
+        assert_eq!(&*UTF8, &*BYTES);
+        assert_eq!(&*as_bytes!(&[]), &[0, 0, 0, 0]);
+    }
+
+    #[wasm_test]
+    fn test_as_key() {
+        assert_eq!(&KEY[..4], &16u32.to_le_bytes());
+        assert_eq!(&KEY[4..12], b"balance_");
+        assert_eq!(&KEY[12..], &42u64.to_le_bytes());
+        assert_eq!(&*as_key!(b"a", b"", b"bc"), &[3, 0, 0, 0, b'a', b'b', b'c']);
+    }
+}
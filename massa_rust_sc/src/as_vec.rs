@@ -172,7 +172,8 @@ impl<T: Pod> AsMemoryModel for AsVec<T> {
     }
 }
 
-#[cfg(test)]
+// Note: the tests use the AssemblyScript ABI (generate_event, set_data, ...)
+#[cfg(all(test, not(feature = "wasmv1")))]
 mod tests {
     use super::*;
     use crate::{generate_event, get_data, set_data, AsSlice};
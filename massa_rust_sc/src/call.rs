@@ -0,0 +1,267 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+// internal
+use crate::memory::AsMemoryModel;
+use crate::{
+    assembly_script_call, assembly_script_create_sc, assembly_script_get_bytecode,
+    assembly_script_get_bytecode_for, assembly_script_local_call, assembly_script_local_execution,
+    Amount, Args, AsSlice, AsVec,
+};
+
+/// Convert a string to an AssemblyScript string (utf16)
+pub(crate) fn to_as_string(s: &str) -> AsVec<u16> {
+    s.encode_utf16().collect()
+}
+
+fn to_as_bytes(bytes: &[u8]) -> AsVec<u8> {
+    bytes.iter().copied().collect()
+}
+
+/// Copy the byte array returned by the host
+fn from_as_bytes(ptr: i32) -> Vec<u8> {
+    AsSlice::<u8>::from(ptr as *const u8).to_vec()
+}
+
+/// Call a function of a smart contract
+///
+/// The function is executed in the context of the called smart contract (its storage, balance,
+/// ...), `coins` are transferred to it before the call. Return the value returned by the function
+/// (usually serialized with [Args]).
+///
+/// Note: any error (e.g. function not found) interrupts the execution, the changes made by the
+///       call are reverted
+pub fn call(address: &str, function: &str, args: &Args, coins: Amount) -> Vec<u8> {
+    let address = to_as_string(address);
+    let function = to_as_string(function);
+    let args = to_as_bytes(args.as_bytes());
+    let res = unsafe {
+        assembly_script_call(
+            address.as_ptr_data(),
+            function.as_ptr_data(),
+            args.as_ptr_data(),
+            coins.to_raw() as i64,
+        )
+    };
+    from_as_bytes(res)
+}
+
+/// Create a new smart contract with the given bytecode, return its address
+///
+/// The storage costs of the new smart contract are paid by the current smart contract, which
+/// gets write access to it (e.g. to initialize it with [local_call] or [call]).
+pub fn create_sc(bytecode: &[u8]) -> String {
+    let bytecode = to_as_bytes(bytecode);
+    let address = unsafe { assembly_script_create_sc(bytecode.as_ptr_data()) };
+    String::from_utf16_lossy(AsSlice::<u16>::from(address as *const u8).into_slice())
+}
+
+/// Call a function of a smart contract in the context of the caller
+///
+/// The bytecode of the smart contract at `address` is executed but the storage, balance,
+/// ... used are the ones of the current smart contract (e.g. when a script executed by an
+/// ExecuteSC operation uses local_call, the callee acts on behalf of the user).
+/// Return the value returned by the function (usually serialized with [Args]).
+///
+/// Note: any error (e.g. function not found) interrupts the execution
+pub fn local_call(address: &str, function: &str, args: &Args) -> Vec<u8> {
+    let address = to_as_string(address);
+    let function = to_as_string(function);
+    let args = to_as_bytes(args.as_bytes());
+    let res = unsafe {
+        assembly_script_local_call(
+            address.as_ptr_data(),
+            function.as_ptr_data(),
+            args.as_ptr_data(),
+        )
+    };
+    from_as_bytes(res)
+}
+
+/// Execute a function of the given bytecode in the context of the caller
+///
+/// Same as [local_call] but with a bytecode (e.g. retrieved with [get_bytecode_for])
+/// instead of a smart contract address.
+///
+/// Note: any error (e.g. invalid bytecode) interrupts the execution
+pub fn local_execution(bytecode: &[u8], function: &str, args: &Args) -> Vec<u8> {
+    let bytecode = to_as_bytes(bytecode);
+    let function = to_as_string(function);
+    let args = to_as_bytes(args.as_bytes());
+    let res = unsafe {
+        assembly_script_local_execution(
+            bytecode.as_ptr_data(),
+            function.as_ptr_data(),
+            args.as_ptr_data(),
+        )
+    };
+    from_as_bytes(res)
+}
+
+/// Return the bytecode of the current smart contract
+pub fn get_bytecode() -> Vec<u8> {
+    from_as_bytes(unsafe { assembly_script_get_bytecode() })
+}
+
+/// Return the bytecode of the smart contract at the given address
+pub fn get_bytecode_for(address: &str) -> Vec<u8> {
+    let address = to_as_string(address);
+    from_as_bytes(unsafe { assembly_script_get_bytecode_for(address.as_ptr_data()) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{export, get_balance, get_data, has_data, is_address_eoa, revert, set_data};
+    use wasm_test::*;
+
+    /// Coins sent to the child smart contract (to pay its storage costs)
+    const COINS: Amount = Amount::from_raw(100_000_000);
+
+    /// Key of the value stored by the child smart contract
+    fn value_key() -> AsVec<u8> {
+        to_as_bytes(b"call_value")
+    }
+
+    // Functions of the child smart contract (deployed with the unit tests bytecode)
+
+    #[export]
+    fn call_test_set_value(value: u64) {
+        set_data(value_key(), to_as_bytes(&value.to_le_bytes()));
+    }
+
+    #[export]
+    fn call_test_get_value() -> u64 {
+        let value = AsSlice::<u8>::from(get_data(value_key()) as *const u8).to_vec();
+        // Unwrap safe: stored by call_test_set_value
+        u64::from_le_bytes(value.try_into().unwrap())
+    }
+
+    #[export]
+    fn call_test_has_value() -> bool {
+        has_data(value_key())
+    }
+
+    #[export]
+    fn call_test_balance() -> u64 {
+        get_balance().to_raw()
+    }
+
+    #[export]
+    fn call_test_fail(value: u64) {
+        call_test_set_value(value);
+        crate::emit_event("call_test_fail");
+        revert("call_test_fail: reverted");
+    }
+
+    fn child_value(child: &str) -> u64 {
+        let res = call(child, "call_test_get_value", &Args::new(), Amount::zero());
+        Args::from(res).next_arg().unwrap()
+    }
+
+    fn child_balance(child: &str) -> u64 {
+        let res = call(child, "call_test_balance", &Args::new(), Amount::zero());
+        Args::from(res).next_arg().unwrap()
+    }
+
+    #[wasm_test]
+    fn test_create_sc_and_call() {
+        let child = create_sc(&get_bytecode());
+        assert!(child.starts_with("AS"));
+        assert!(!is_address_eoa(&child));
+
+        let balance = get_balance();
+        call(
+            &child,
+            "call_test_set_value",
+            Args::new().add(&42u64),
+            COINS,
+        );
+        // The write is done in the datastore of the child
+        assert_eq!(child_value(&child), 42);
+        assert!(!has_data(value_key()));
+        // The coins are transferred to the child (which paid its storage costs)
+        assert_eq!(get_balance().checked_add(COINS), Some(balance));
+        let child_balance = child_balance(&child);
+        assert!(child_balance > 0 && child_balance < COINS.to_raw());
+    }
+
+    /// Address of the smart contract executing the unit test
+    fn current_address() -> String {
+        String::from_utf16_lossy(crate::get_call_stack().unwrap().callee())
+    }
+
+    #[wasm_test]
+    fn test_get_bytecode() {
+        // The unit tests bytecode is deployed at the smart contract address
+        let bytecode = get_bytecode();
+        assert!(bytecode.starts_with(b"\0asm"));
+        assert_eq!(get_bytecode_for(&current_address()), bytecode);
+
+        let child = create_sc(&bytecode);
+        assert_eq!(get_bytecode_for(&child), bytecode);
+    }
+
+    #[wasm_test]
+    fn test_local_call() {
+        // Executed in the context of the current smart contract (its datastore)
+        local_call(
+            &current_address(),
+            "call_test_set_value",
+            Args::new().add(&7u64),
+        );
+        assert_eq!(call_test_get_value(), 7);
+
+        let child = create_sc(&get_bytecode());
+        local_call(&child, "call_test_set_value", Args::new().add(&8u64));
+        assert_eq!(call_test_get_value(), 8);
+        // Returned value
+        let res = local_call(&child, "call_test_get_value", &Args::new());
+        assert_eq!(Args::from(res).next_arg::<u64>(), Ok(8));
+        // Nothing written in the child datastore
+        let res = local_call(&child, "call_test_has_value", &Args::new());
+        assert_eq!(Args::from(res).next_arg::<bool>(), Ok(true));
+        let res = call(&child, "call_test_has_value", &Args::new(), Amount::zero());
+        assert_eq!(Args::from(res).next_arg::<bool>(), Ok(false));
+    }
+
+    #[wasm_test]
+    fn test_local_execution() {
+        let bytecode = get_bytecode();
+        local_execution(&bytecode, "call_test_set_value", Args::new().add(&9u64));
+        assert_eq!(call_test_get_value(), 9);
+        let res = local_execution(&bytecode, "call_test_get_value", &Args::new());
+        assert_eq!(Args::from(res).next_arg::<u64>(), Ok(9));
+    }
+
+    #[cfg(feature = "testing")]
+    #[wasm_test]
+    fn test_failed_call_is_reverted() {
+        use crate::testing::{event_emitted, try_call};
+
+        let child = create_sc(&get_bytecode());
+        call(&child, "call_test_set_value", Args::new().add(&1u64), COINS);
+        let balance = get_balance();
+        let balance_of_child = child_balance(&child);
+
+        let res = try_call(&child, "call_test_fail", Args::new().add(&2u64), COINS);
+        assert!(res.unwrap_err().contains("call_test_fail: reverted"));
+        // Coins, datastore & events of the failed call are reverted
+        assert_eq!(get_balance(), balance);
+        assert_eq!(child_balance(&child), balance_of_child);
+        assert_eq!(child_value(&child), 1);
+        assert!(!event_emitted("call_test_fail"));
+
+        // A successful call is not reverted
+        let res = try_call(&child, "call_test_set_value", Args::new().add(&3u64), COINS);
+        assert_eq!(res, Ok(Vec::new()));
+        assert_eq!(child_value(&child), 3);
+    }
+
+    #[cfg(feature = "testing")]
+    #[wasm_test]
+    fn test_failed_call_reverts() {
+        let child = create_sc(&get_bytecode());
+        crate::testing::expect_revert("call_test_fail: reverted");
+        call(&child, "call_test_fail", Args::new().add(&2u64), COINS);
+    }
+}
@@ -1,95 +1,249 @@
-use crate::{assembly_script_get_call_stack, caller_has_write_access, AsSlice};
+use alloc::vec::Vec;
+use core::fmt;
+// internal
+use crate::call::to_as_string;
+use crate::json::{parse_string_array, JsonError};
+use crate::memory::AsMemoryModel;
+use crate::{
+    assembly_script_get_call_stack, assembly_script_get_origin_operation_id,
+    assembly_script_get_owned_addresses, assembly_script_is_address_eoa,
+    assembly_script_validate_address, caller_has_write_access, AsSlice,
+};
 
-// https://doc.rust-lang.org/std/ascii/enum.Char.html#variant.Comma
-const COMMA_CHAR: u16 = 44; // == ','
+/// Error returned when the call stack cannot be retrieved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallStackError {
+    /// Call stack string returned by the host is not a json array of strings
+    Json(JsonError),
+    /// Call stack does not contain any address
+    Empty,
+}
 
-/// Return true if the smart contract is currently being deployed
+impl fmt::Display for CallStackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CallStackError::Json(e) => write!(f, "invalid call stack: {e}"),
+            CallStackError::Empty => write!(f, "empty call stack"),
+        }
+    }
+}
+
+impl From<JsonError> for CallStackError {
+    fn from(e: JsonError) -> Self {
+        CallStackError::Json(e)
+    }
+}
+
+/// The addresses in the call stack, from the bottom (origin) to the top (callee)
 ///
-/// This function is typically used in the constructor to ensure a one-time deployment and
-/// initialization, usually by the creator of the contract.
-/// Under the hood, this method verifies that the account calling this function (either the user
-/// creating the operation or an upper contract) has write access to the data of the current account
-pub fn is_deploying_contract() -> bool {
-    // massa-as-sdk code: function isDeployingContract
-    // https://github.com/massalabs/massa-as-sdk/blob/main/assembly/std/context.ts
+/// Addresses are utf16 strings (e.g. "AU1Yvq49utdezr496dHbRj3TMjqsCh2awggjfGraHoddE7XfEkpY")
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallStack<'a> {
+    // Invariant: never empty
+    addresses: Vec<&'a [u16]>,
+}
 
-    unsafe {
-        if caller_has_write_access() {
-            // in isDeployingContract, there are 2 calls to addressStack (one for the caller, one for the callee)
-            // Here we try to call it only once
-            let call_stack = assembly_script_get_call_stack();
-            // Note: assembly_script_get_call_stack return something like:
-            //       let s1 = r#"[\"AU1Yvq49utdezr496dHbRj3TMjqsCh2awggjfGraHoddE7XfEkpY\",\"AS12mb3TqNpeers7FRDpYR9XDaFHFxXaG9SuQ1yU778QdjZUa8eQ7\"]"#;
-            // but encoded as utf16 string (see as-ffi-bindings - string_ptr.rs file for details)
-            let call_stack = AsSlice::<u16>::from(call_stack as *const u8);
-
-            let mut call_stack_split = call_stack.as_ref()[1..call_stack.len() - 1] // remove '[' && ']' characters
-                .rsplitn(2, |c| *c == COMMA_CHAR);
-
-            // Unwrap safe: assume call_stack string is formatted as expected (see the above comment)
-            let callee = call_stack_split.next().unwrap();
-            // Note: call stack len can be < 2. In this case, in massa-as-sdk code, callee is returned as caller
-            let caller = call_stack_split.next().unwrap_or(callee);
-
-            // in isDeployingContract, checks is done with Address objects,
-            // and an Address object is just a wrapper of a string (utf16 string)
-            // https://github.com/massalabs/massa-as-sdk/blob/main/assembly/std/address.ts
-            // Note: slicing with 2..len - 2 is to remove the following characters: \"
-            callee[2..callee.len() - 2] != caller[2..caller.len() - 2]
-        } else {
-            false
+impl<'a> CallStack<'a> {
+    /// Parse a call stack string (utf16 string in json format)
+    ///
+    /// e.g. ["AU1Yvq49utdezr496dHbRj3TMjqsCh2awggjfGraHoddE7XfEkpY","AS12mb3TqNpeers7FRDpYR9XDaFHFxXaG9SuQ1yU778QdjZUa8eQ7"]
+    pub fn parse(call_stack: &'a [u16]) -> Result<Self, CallStackError> {
+        let addresses = parse_string_array(call_stack)?;
+        if addresses.is_empty() {
+            return Err(CallStackError::Empty);
         }
+        Ok(Self { addresses })
+    }
+
+    /// Addresses from the bottom to the top of the call stack
+    pub fn addresses(&self) -> &[&'a [u16]] {
+        self.addresses.as_slice()
+    }
+
+    /// Number of addresses in the call stack
+    pub fn depth(&self) -> usize {
+        self.addresses.len()
+    }
+
+    /// Returns the address of the initial transaction creator (originator).
+    pub fn origin(&self) -> &'a [u16] {
+        self.addresses[0]
+    }
+
+    /// Returns the `address` of the `caller` of the currently executing smart contract.
+    ///
+    /// The caller is the person or the smart contract that directly called
+    /// the pending function.
+    /// Note: if the call stack has only one address, the callee is returned (like in massa-as-sdk)
+    pub fn caller(&self) -> &'a [u16] {
+        let depth = self.depth();
+        self.addresses[depth.saturating_sub(2)]
+    }
+
+    /// Returns the address of the currently executing smart contract.
+    ///
+    /// The "callee" refers to the contract that is currently being executed.
+    pub fn callee(&self) -> &'a [u16] {
+        self.addresses[self.depth() - 1]
     }
 }
 
-pub fn get_call_stack<'a>() -> AsSlice<'a, u16> {
-    unsafe {
+/// Returns the addresses in the call stack, from the bottom to the top.
+pub fn get_call_stack<'a>() -> Result<CallStack<'a>, CallStackError> {
+    let call_stack = unsafe {
         let call_stack = assembly_script_get_call_stack();
         // Note: assembly_script_get_call_stack return something like:
-        //       let s1 = r#"[\"AU1Yvq49utdezr496dHbRj3TMjqsCh2awggjfGraHoddE7XfEkpY\",\"AS12mb3TqNpeers7FRDpYR9XDaFHFxXaG9SuQ1yU778QdjZUa8eQ7\"]"#;
+        //       ["AU1Yvq49utdezr496dHbRj3TMjqsCh2awggjfGraHoddE7XfEkpY","AS12mb3TqNpeers7FRDpYR9XDaFHFxXaG9SuQ1yU778QdjZUa8eQ7"]
         // but encoded as utf16 string (see as-ffi-bindings - string_ptr.rs file for details)
-        let call_stack = AsSlice::<u16>::from(call_stack as *const u8);
-        call_stack
-    }
+        AsSlice::<u16>::from(call_stack as *const u8)
+    };
+    CallStack::parse(call_stack.into_slice())
 }
 
-/// Returns the address of the currently executing smart contract.
+/// Returns the addresses (utf16 strings) of the smart contracts owned by the current account
+pub fn get_owned_addresses<'a>() -> Result<Vec<&'a [u16]>, JsonError> {
+    let owned_addresses = unsafe {
+        let owned_addresses = assembly_script_get_owned_addresses();
+        // Note: same format as assembly_script_get_call_stack (utf16 string in json format)
+        AsSlice::<u16>::from(owned_addresses as *const u8)
+    };
+    parse_string_array(owned_addresses.into_slice())
+}
+
+/// Returns the id (utf16 string) of the operation that initiated the current execution
 ///
-/// The "callee" refers to the contract that is currently being executed.
-pub fn callee<'a>(call_stack: &'a AsSlice<'a, u16>) -> &'a [u16] {
-    let mut call_stack_split = call_stack.as_ref()[1..call_stack.len() - 1] // remove '[' && ']' characters
-        // https://rust-lang.github.io/rust-clippy/master/index.html#suspicious_splitn
-        .rsplitn(1 + 1, |c| *c == COMMA_CHAR);
-    // Unwrap safe: assume call_stack string is formatted as expected (see the above comment)
-    let callee = call_stack_split.next().unwrap();
-    &callee[2..callee.len() - 2]
+/// Returns None if there is no such operation (e.g. in a read only execution).
+/// The id can be used to detect that an operation has already been processed.
+pub fn get_origin_operation_id<'a>() -> Option<&'a [u16]> {
+    let operation_id = unsafe {
+        let operation_id = assembly_script_get_origin_operation_id();
+        AsSlice::<u16>::from(operation_id as *const u8)
+    };
+    Some(operation_id.into_slice()).filter(|operation_id| !operation_id.is_empty())
 }
 
-/// Returns the `address` of the `caller` of the currently executing smart contract.
+/// Returns true if the address is a user address (an Externally Owned Account)
 ///
-/// The caller is the person or the smart contract that directly called
-/// the pending function.
-pub fn caller<'a>(call_stack: &'a AsSlice<'a, u16>) -> &'a [u16] {
-    let mut call_stack_split = call_stack.as_ref()[1..call_stack.len() - 1] // remove '[' && ']' characters
-        // https://rust-lang.github.io/rust-clippy/master/index.html#suspicious_splitn
-        .rsplitn(1 + 1, |c| *c == COMMA_CHAR);
-    let callee = call_stack_split.next().unwrap();
-    // Note: call stack len can be < 2. In this case, in massa-as-sdk code, callee is returned as caller
-    let caller = call_stack_split.next();
-
-    if caller.is_none() {
-        &callee[2..callee.len() - 2]
-    } else {
-        let caller = caller.unwrap();
-        &caller[2..caller.len() - 2]
-    }
+/// Can be used to reject calls from smart contracts, e.g.:
+///
+/// ```ignore
+/// let call_stack = get_call_stack().unwrap();
+/// let caller = String::from_utf16_lossy(call_stack.caller());
+/// assert!(is_address_eoa(&caller));
+/// ```
+pub fn is_address_eoa(address: &str) -> bool {
+    let address = to_as_string(address);
+    unsafe { assembly_script_is_address_eoa(address.as_ptr_data()) }
 }
 
-/// Returns the address of the initial transaction creator (originator).
-pub fn transaction_creator<'a>(call_stack: &'a AsSlice<'a, u16>) -> &'a [u16] {
-    let mut call_stack_split = call_stack.as_ref()[1..call_stack.len() - 1] // remove '[' && ']' characters
-        // https://rust-lang.github.io/rust-clippy/master/index.html#suspicious_splitn
-        .splitn(1 + 1, |c| *c == COMMA_CHAR);
-    let creator = call_stack_split.next().unwrap();
-    &creator[2..creator.len() - 2]
+/// Returns true if the address is a valid Massa address
+pub fn validate_address(address: &str) -> bool {
+    let address = to_as_string(address);
+    unsafe { assembly_script_validate_address(address.as_ptr_data()) }
+}
+
+/// Return true if the smart contract is currently being deployed
+///
+/// This function is typically used in the constructor to ensure a one-time deployment and
+/// initialization, usually by the creator of the contract.
+/// Under the hood, this method verifies that the account calling this function (either the user
+/// creating the operation or an upper contract) has write access to the data of the current account
+///
+/// Note: returns false if the call stack cannot be parsed
+pub fn is_deploying_contract() -> bool {
+    // massa-as-sdk code: function isDeployingContract
+    // https://github.com/massalabs/massa-as-sdk/blob/main/assembly/std/context.ts
+
+    // in isDeployingContract, there are 2 calls to addressStack (one for the caller, one for the callee)
+    // Here we parse the call stack only once
+    caller_has_write_access()
+        && get_call_stack()
+            .map(|call_stack| call_stack.callee() != call_stack.caller())
+            .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec;
+    use utf16_lit::utf16;
+    use wasm_test::*;
+
+    const AU: &[u16] = &utf16!("AU1Yvq49utdezr496dHbRj3TMjqsCh2awggjfGraHoddE7XfEkpY");
+    const AS1: &[u16] = &utf16!("AS12mb3TqNpeers7FRDpYR9XDaFHFxXaG9SuQ1yU778QdjZUa8eQ7");
+    const AS2: &[u16] = &utf16!("AS1GFocKuZKiSr2Gcu8y69fraPEqs7xYWvEFikTKcThYC38sBFBH");
+
+    #[wasm_test]
+    fn test_call_stack() {
+        let s = utf16!(
+            r#"["AU1Yvq49utdezr496dHbRj3TMjqsCh2awggjfGraHoddE7XfEkpY","AS12mb3TqNpeers7FRDpYR9XDaFHFxXaG9SuQ1yU778QdjZUa8eQ7","AS1GFocKuZKiSr2Gcu8y69fraPEqs7xYWvEFikTKcThYC38sBFBH"]"#
+        );
+        let call_stack = CallStack::parse(&s).unwrap();
+        assert_eq!(call_stack.depth(), 3);
+        assert_eq!(call_stack.addresses(), [AU, AS1, AS2]);
+        assert_eq!(call_stack.origin(), AU);
+        assert_eq!(call_stack.caller(), AS1);
+        assert_eq!(call_stack.callee(), AS2);
+    }
+
+    #[wasm_test]
+    fn test_call_stack_single() {
+        let s = utf16!(r#"["AU1Yvq49utdezr496dHbRj3TMjqsCh2awggjfGraHoddE7XfEkpY"]"#);
+        let call_stack = CallStack::parse(&s).unwrap();
+        assert_eq!(call_stack.origin(), AU);
+        assert_eq!(call_stack.caller(), AU);
+        assert_eq!(call_stack.callee(), AU);
+
+        assert_eq!(CallStack::parse(&utf16!("[]")), Err(CallStackError::Empty));
+        assert_eq!(
+            CallStack::parse(&utf16!(r#"["AU1", "#)),
+            Err(CallStackError::Json(JsonError::UnexpectedEnd))
+        );
+    }
+
+    #[wasm_test]
+    fn test_address_checks() {
+        // Addresses of the caller & the smart contract in massa_sc_runner
+        let caller = String::from_utf16_lossy(AU);
+        let contract = String::from_utf16_lossy(AS2);
+        assert!(validate_address(&caller));
+        assert!(validate_address(&contract));
+        assert!(is_address_eoa(&caller));
+        assert!(!is_address_eoa(&contract));
+
+        // Invalid checksum, prefix or length
+        assert!(!validate_address(&caller.replace("AU1Y", "AU1Z")));
+        assert!(!validate_address(&contract.replace("AS", "AX")));
+        assert!(!validate_address("AU12"));
+        assert!(!validate_address(""));
+
+        let call_stack = get_call_stack().unwrap();
+        assert!(is_address_eoa(&String::from_utf16_lossy(
+            call_stack.caller()
+        )));
+        assert!(!is_address_eoa(&String::from_utf16_lossy(
+            call_stack.callee()
+        )));
+    }
+
+    #[wasm_test]
+    fn test_execution_context() {
+        // massa_sc_runner: no origin operation, the smart contract only owns its own address
+        assert_eq!(get_origin_operation_id(), None);
+        let call_stack = get_call_stack().unwrap();
+        assert_eq!(get_owned_addresses(), Ok(vec![call_stack.callee()]));
+    }
+
+    #[wasm_test]
+    fn test_unsafe_random() {
+        // Seeded in massa_sc_runner (see --random-seed): not a constant value
+        let numbers: Vec<i64> = (0..10).map(|_| crate::unsafe_random()).collect();
+        assert!(numbers.windows(2).all(|w| w[0] != w[1]));
+
+        for _ in 0..100 {
+            let n = crate::unsafe_random_f64();
+            assert!((0.0..1.0).contains(&n));
+        }
+    }
 }
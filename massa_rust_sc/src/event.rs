@@ -0,0 +1,91 @@
+use alloc::string::String;
+use core::fmt::{self, Write};
+// internal
+#[cfg(not(feature = "wasmv1"))]
+use crate::{generate_event, AsVec};
+
+/// Separator between the event name and its arguments
+pub const EVENT_NAME_SEPARATOR: char = ':';
+/// Separator between the event arguments
+pub const EVENT_ARGS_SEPARATOR: char = ',';
+
+/// Format a structured event: "EventName:arg1,arg2"
+///
+/// This is the format used by createEvent in massa-standards (and decoded by
+/// massa_rust_web3::ContractEvent). Note that no escaping is done: the event name
+/// should not contain ':' and the arguments should not contain ','.
+pub fn create_event(name: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut event = String::from(name);
+    event.push(EVENT_NAME_SEPARATOR);
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            event.push(EVENT_ARGS_SEPARATOR);
+        }
+        // Unwrap safe: writing to a String cannot fail
+        write!(event, "{arg}").unwrap();
+    }
+    event
+}
+
+/// Emit an event (the string is converted to utf16 as required by generateEvent)
+#[cfg(not(feature = "wasmv1"))]
+pub fn emit_event(event: &str) {
+    generate_event(event.encode_utf16().collect::<AsVec<u16>>());
+}
+
+/// Emit an event (utf-8 string, see [wasmv1::generate_event](crate::wasmv1::generate_event))
+#[cfg(feature = "wasmv1")]
+pub fn emit_event(event: &str) {
+    if let Err(e) = crate::wasmv1::generate_event(event) {
+        crate::revert(&alloc::format!("{e}"));
+    }
+}
+
+/// A structured event (usually implemented with `#[derive(MassaEvent)]`)
+pub trait MassaEvent {
+    /// Event name
+    const NAME: &'static str;
+
+    /// Format the event: "EventName:arg1,arg2" (see [create_event])
+    fn to_event_string(&self) -> String;
+
+    /// Emit the event
+    fn emit(&self) {
+        emit_event(&self.to_event_string());
+    }
+}
+
+/// Emit a structured event: "EventName:arg1,arg2" (see [create_event](crate::create_event))
+///
+/// Arguments can be any value implementing Display.
+///
+/// ```ignore
+/// event!("Transfer", from, to, amount);
+/// ```
+#[macro_export]
+macro_rules! event {
+    ($name:expr $(, $arg:expr)* $(,)?) => {
+        $crate::emit_event(&$crate::create_event(
+            $name,
+            &[$(&$arg as &dyn ::core::fmt::Display),*],
+        ))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Amount;
+    use wasm_test::*;
+
+    #[wasm_test]
+    fn test_create_event() {
+        let amount = Amount::from_raw(1_500_000_000);
+        assert_eq!(
+            create_event("Transfer", &[&"AU12", &"AU13", &amount]),
+            "Transfer:AU12,AU13,1.5"
+        );
+        assert_eq!(create_event("Paused", &[]), "Paused:");
+        assert_eq!(create_event("Count", &[&42u64]), "Count:42");
+    }
+}
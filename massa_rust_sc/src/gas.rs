@@ -0,0 +1,89 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+// internal
+use crate::{create_event, emit_event, get_remaining_gas};
+
+/// Event name of the gas checkpoints (see [gas_checkpoint!](gas_checkpoint!))
+pub const GAS_CHECKPOINT_EVENT: &str = "GasCheckpoint";
+
+// Remaining gas at the previous checkpoint (0: no checkpoint yet)
+static LAST_CHECKPOINT: AtomicU64 = AtomicU64::new(0);
+
+/// Run f and return its result along with the gas it consumed
+///
+/// Note: the gas used to read the remaining gas (after f) is included
+///
+/// ```ignore
+/// let (total, gas) = measure_gas(|| compute_rewards(&stakers));
+/// ```
+pub fn measure_gas<R>(f: impl FnOnce() -> R) -> (R, u64) {
+    let start = get_remaining_gas();
+    let res = f();
+    let end = get_remaining_gas();
+    (res, start.saturating_sub(end))
+}
+
+/// Emit a "GasCheckpoint:label,delta,remaining" event
+///
+/// delta is the gas consumed since the previous checkpoint (0 for the first one).
+/// Prefer [gas_checkpoint!](gas_checkpoint!) which is removed in release builds.
+pub fn emit_gas_checkpoint(label: &str) {
+    let remaining = get_remaining_gas();
+    let delta = match LAST_CHECKPOINT.swap(remaining, Ordering::Relaxed) {
+        0 => 0,
+        last => last.saturating_sub(remaining),
+    };
+    emit_event(&create_event(
+        GAS_CHECKPOINT_EVENT,
+        &[&label, &delta, &remaining],
+    ));
+}
+
+/// Emit the gas consumed since the previous checkpoint as an event (debug builds only)
+///
+/// In release builds (without debug assertions), the macro expands to nothing.
+///
+/// ```ignore
+/// gas_checkpoint!("start");
+/// let total = compute_rewards(&stakers);
+/// gas_checkpoint!("rewards"); // event: GasCheckpoint:rewards,<gas used>,<remaining gas>
+/// ```
+#[macro_export]
+macro_rules! gas_checkpoint {
+    ($label:expr) => {{
+        #[cfg(debug_assertions)]
+        $crate::emit_gas_checkpoint($label);
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_test::*;
+
+    #[wasm_test]
+    fn test_measure_gas() {
+        let remaining = get_remaining_gas();
+        let (res, gas) = measure_gas(|| (0..100u64).map(core::hint::black_box).sum::<u64>());
+        assert_eq!(res, 4950);
+        assert!(gas > 0);
+        assert!(gas <= remaining);
+
+        // 100 times more iterations
+        let (res, heavier_gas) =
+            measure_gas(|| (0..10_000u64).map(core::hint::black_box).sum::<u64>());
+        assert_eq!(res, 49_995_000);
+        assert!(heavier_gas > gas);
+    }
+
+    // The checkpoints are removed in release builds
+    #[cfg(debug_assertions)]
+    #[wasm_test]
+    fn test_gas_checkpoint() {
+        crate::gas_checkpoint!("start");
+        let first = LAST_CHECKPOINT.load(Ordering::Relaxed);
+        assert_ne!(first, 0);
+
+        crate::gas_checkpoint!("end");
+        assert!(LAST_CHECKPOINT.load(Ordering::Relaxed) <= first);
+    }
+}
@@ -0,0 +1,173 @@
+// Minimal (no_std) json parser for the strings returned by some Massa host functions
+// (e.g. the call stack: ["AU1...", "AS1..."]), encoded as utf16 strings
+
+use alloc::vec::Vec;
+use core::fmt;
+
+const OPEN_BRACKET: u16 = b'[' as u16;
+const CLOSE_BRACKET: u16 = b']' as u16;
+const COMMA: u16 = b',' as u16;
+const QUOTE: u16 = b'"' as u16;
+const BACKSLASH: u16 = b'\\' as u16;
+
+/// Error returned when a json string cannot be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonError {
+    /// Input ended before the end of the json array
+    UnexpectedEnd,
+    /// Unexpected character at the given offset (in utf16 code units)
+    UnexpectedChar(usize),
+    /// Escape sequence in a string (not supported) at the given offset
+    UnsupportedEscape(usize),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::UnexpectedEnd => write!(f, "unexpected end of json input"),
+            JsonError::UnexpectedChar(offset) => {
+                write!(f, "unexpected character in json input at offset {offset}")
+            }
+            JsonError::UnsupportedEscape(offset) => {
+                write!(
+                    f,
+                    "unsupported escape sequence in json input at offset {offset}"
+                )
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a [u16],
+    offset: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u16> {
+        self.input.get(self.offset).copied()
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') =
+            self.peek().and_then(|c| u8::try_from(c).ok())
+        {
+            self.offset += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: u16) -> Result<(), JsonError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.offset += 1;
+                Ok(())
+            }
+            Some(_) => Err(JsonError::UnexpectedChar(self.offset)),
+            None => Err(JsonError::UnexpectedEnd),
+        }
+    }
+
+    /// Parse a string and return its content (without the quotes)
+    ///
+    /// Note: quotes can be escaped (e.g. [\"AU1...\"]) when the host returns an escaped json
+    ///       string, in this case the string must end with an escaped quote too
+    fn string(&mut self) -> Result<&'a [u16], JsonError> {
+        let escaped = self.peek() == Some(BACKSLASH);
+        if escaped {
+            self.offset += 1;
+        }
+        self.expect(QUOTE)?;
+
+        let start = self.offset;
+        loop {
+            match self.peek() {
+                None => return Err(JsonError::UnexpectedEnd),
+                Some(QUOTE) if !escaped => {
+                    self.offset += 1;
+                    return Ok(&self.input[start..self.offset - 1]);
+                }
+                Some(BACKSLASH) => {
+                    if escaped && self.input.get(self.offset + 1) == Some(&QUOTE) {
+                        self.offset += 2;
+                        return Ok(&self.input[start..self.offset - 2]);
+                    }
+                    return Err(JsonError::UnsupportedEscape(self.offset));
+                }
+                Some(_) => self.offset += 1,
+            }
+        }
+    }
+}
+
+/// Parse a json array of strings (e.g. ["AU1...","AS1..."])
+///
+/// Return the string slices (borrowed from input, without the quotes)
+pub(crate) fn parse_string_array(input: &[u16]) -> Result<Vec<&[u16]>, JsonError> {
+    let mut parser = Parser { input, offset: 0 };
+    let mut res = Vec::new();
+
+    parser.skip_whitespaces();
+    parser.expect(OPEN_BRACKET)?;
+    parser.skip_whitespaces();
+    if parser.peek() == Some(CLOSE_BRACKET) {
+        parser.offset += 1;
+    } else {
+        loop {
+            parser.skip_whitespaces();
+            res.push(parser.string()?);
+            parser.skip_whitespaces();
+            match parser.peek() {
+                Some(COMMA) => parser.offset += 1,
+                Some(CLOSE_BRACKET) => {
+                    parser.offset += 1;
+                    break;
+                }
+                Some(_) => return Err(JsonError::UnexpectedChar(parser.offset)),
+                None => return Err(JsonError::UnexpectedEnd),
+            }
+        }
+    }
+
+    parser.skip_whitespaces();
+    if parser.offset != input.len() {
+        return Err(JsonError::UnexpectedChar(parser.offset));
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utf16_lit::utf16;
+    use wasm_test::*;
+
+    #[wasm_test]
+    fn test_parse_string_array() {
+        let res = parse_string_array(&utf16!(r#"["AU1", "AS12"]"#)).unwrap();
+        assert_eq!(res, [&utf16!("AU1")[..], &utf16!("AS12")[..]]);
+        let res = parse_string_array(&utf16!(r#" [\"AU1\",\"AS12\"] "#)).unwrap();
+        assert_eq!(res, [&utf16!("AU1")[..], &utf16!("AS12")[..]]);
+        assert!(parse_string_array(&utf16!("[ ]")).unwrap().is_empty());
+    }
+
+    #[wasm_test]
+    fn test_parse_string_array_malformed() {
+        assert_eq!(parse_string_array(&[]), Err(JsonError::UnexpectedEnd));
+        assert_eq!(
+            parse_string_array(&utf16!(r#"["AU1""#)),
+            Err(JsonError::UnexpectedEnd)
+        );
+        assert_eq!(
+            parse_string_array(&utf16!(r#"["AU1",]"#)),
+            Err(JsonError::UnexpectedChar(7))
+        );
+        assert_eq!(
+            parse_string_array(&utf16!(r#"["AU1"]x"#)),
+            Err(JsonError::UnexpectedChar(7))
+        );
+        assert_eq!(
+            parse_string_array(&utf16!(r#"["A\nU1"]"#)),
+            Err(JsonError::UnsupportedEscape(3))
+        );
+    }
+}
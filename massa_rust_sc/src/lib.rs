@@ -8,153 +8,163 @@
 // https://github.com/rust-lang/rust/issues/128475
 // https://github.com/rust-lang/rust/pull/128511
 
+mod amount;
+#[cfg(not(feature = "wasmv1"))]
+mod as_abi;
 mod as_slice;
 mod as_vec;
+#[cfg(not(feature = "wasmv1"))]
+mod call;
+#[cfg(not(feature = "wasmv1"))]
 mod context;
+mod event;
+#[cfg(not(feature = "wasmv1"))]
+mod gas;
+#[cfg(not(feature = "wasmv1"))]
+mod json;
+// Note: the memory model of AsSlice & AsVec is only used by the AssemblyScript ABI
+#[cfg_attr(feature = "wasmv1", allow(dead_code))]
 mod memory;
+#[cfg(not(feature = "wasmv1"))]
+mod pausable;
+#[cfg(not(feature = "wasmv1"))]
+mod reentrancy_guard;
+mod revert;
+mod safe_math;
+#[cfg(all(feature = "testing", not(feature = "wasmv1")))]
+pub mod testing;
+#[cfg(feature = "wasmv1")]
+pub mod wasmv1;
 
 use lol_alloc::LeakingPageAllocator;
 #[global_allocator]
 static ALLOCATOR: LeakingPageAllocator = LeakingPageAllocator;
 
 extern crate alloc;
-use crate::memory::AsMemoryModel;
-use alloc::vec;
+// The code generated by the macros uses ::massa_rust_sc paths (e.g. #[export] in the unit tests)
+#[cfg(test)]
+extern crate self as massa_rust_sc;
 
 // export
-pub use as_slice::{to_as_array, AsArray, AsSlice};
+pub use amount::{Amount, AmountError, AMOUNT_DECIMALS};
+pub use as_slice::{concat_to_as_array, segments_len, to_as_array, AsArray, AsSlice};
 pub use as_vec::AsVec;
-pub use context::is_deploying_contract;
-
-#[link(wasm_import_module = "massa")]
-extern "C" {
-
-    // External function signatures
-
-    /// Generate an event in the blockchain
-    ///
-    /// * event: a pointer to an utf-16 string (prefixed with array size, see [string_to_as_array!](string_to_as_array!))
-    #[link_name = "assembly_script_generate_event"]
-    pub fn assembly_script_generate_event(event: i32) -> ();
-
-    /// Store a value in smart contract storage
-    ///
-    /// * key: a pointer to a byte slice (prefixed with array size)
-    /// * value: a pointer to a byte slice (prefixed with array size)
-    #[link_name = "assembly_script_set_data"]
-    pub fn assembly_script_set_data(key: i32, value: i32) -> ();
-
-    /// Get a value stored in smart contract storage
-    ///
-    /// * key: a pointer to a byte slice (prefixed with array size)
-    ///
-    /// Return: a pointer to a byte slice (prefixed with array size)
-    #[link_name = "assembly_script_get_data"]
-    pub fn assembly_script_get_data(key: i32) -> i32;
-
-    /// Check if a value is stored in smart contract storage
-    ///
-    /// * key: a pointer to a byte slice (prefixed with array size)
-    ///
-    /// Return: a boolean value
-    #[link_name = "assembly_script_has_data"]
-    pub fn assembly_script_has_data(key: i32) -> bool;
-
-    /// Return true if the caller has write access to the contract
-    #[link_name = "assembly_script_caller_has_write_access"]
-    pub fn assembly_script_caller_has_write_access() -> bool;
-
-    /// Returns the addresses in the call stack, from the bottom to the top.
-    ///
-    /// Return: a string of the addresses (utf16 string in json format)
-    #[link_name = "assembly_script_get_call_stack"]
-    pub fn assembly_script_get_call_stack() -> i32;
-
-    #[link_name = "assembly_script_get_balance"]
-    pub fn assembly_script_get_balance() -> u64;
-
-    #[link_name = "assembly_script_get_call_coins"]
-    pub fn assembly_script_get_call_coins() -> u64;
-
-    #[link_name = "assembly_script_chain_id"]
-    pub fn assembly_script_chain_id() -> u64;
-
-    #[link_name = "assembly_script_get_remaining_gas"]
-    pub fn assembly_script_get_remaining_gas() -> u64;
-}
-
-#[no_mangle]
-extern "C" fn __new(size: usize, _id: i32) -> *mut u8 {
-    // https://www.assemblyscript.org/runtime.html#interface
-    // function __new(size: usize, id: u32): usize
-    // https://github.com/AssemblyScript/assemblyscript/blob/main/std/assembly/rt/itcms.ts#L260
-    // Note: id is defined as u32 in doc but as i32 in source code
-
-    const HEADER_SIZE: usize = 20;
-    let mut v = vec![0; HEADER_SIZE + size];
-    v[12..16].copy_from_slice(&[1, 0, 0, 0]);
-    v[16..HEADER_SIZE].copy_from_slice(&size.to_le_bytes());
-
-    unsafe { v.leak().as_mut_ptr().add(HEADER_SIZE) }
-}
-
-#[no_mangle]
-extern "C" fn __pin(ptr: usize) -> usize {
-    // https://www.assemblyscript.org/runtime.html#interface
-    // function __pin(ptr: usize): usize
-    // https://github.com/AssemblyScript/assemblyscript/blob/main/std/assembly/rt/itcms.ts#L334
-    ptr
-}
-
-pub fn generate_event<T: AsMemoryModel>(event: T) {
-    unsafe {
-        assembly_script_generate_event(event.as_ptr_data());
+pub use event::{create_event, emit_event, MassaEvent, EVENT_ARGS_SEPARATOR, EVENT_NAME_SEPARATOR};
+pub use massa_args::{Args, ArgsError, ParseBigNumError, Serializable, I128, I256, U128, U256};
+pub use massa_rust_sc_macros::{export, ignore, should_panic, MassaEvent};
+pub use revert::{require, revert};
+pub use safe_math::SafeMath;
+
+// AssemblyScript ABI (the default), the wasmv1 ABI is in the wasmv1 module
+#[cfg(not(feature = "wasmv1"))]
+pub use as_abi::*;
+#[cfg(not(feature = "wasmv1"))]
+pub use call::{call, create_sc, get_bytecode, get_bytecode_for, local_call, local_execution};
+#[cfg(not(feature = "wasmv1"))]
+pub use context::{
+    get_call_stack, get_origin_operation_id, get_owned_addresses, is_address_eoa,
+    is_deploying_contract, validate_address, CallStack, CallStackError,
+};
+#[cfg(not(feature = "wasmv1"))]
+pub use gas::{emit_gas_checkpoint, measure_gas, GAS_CHECKPOINT_EVENT};
+#[cfg(not(feature = "wasmv1"))]
+pub use json::JsonError;
+#[cfg(not(feature = "wasmv1"))]
+pub use pausable::{is_paused, pause, unpause, when_not_paused, when_paused, PAUSED_KEY};
+#[cfg(not(feature = "wasmv1"))]
+pub use reentrancy_guard::{non_reentrant, REENTRANCY_GUARD_KEY};
+
+// Used by the code generated by the massa_rust_sc_macros crate
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::format;
+    pub use alloc::string::String;
+    // internal
+    use crate::{Args, Serializable};
+
+    /// Build an Args from the parameter of an exported function
+    #[cfg(not(feature = "wasmv1"))]
+    pub fn args_from_ptr(ptr: i32) -> Args {
+        Args::from(crate::AsSlice::<u8>::from(ptr as *const u8))
     }
-}
 
-pub fn set_data<T: AsMemoryModel, U: AsMemoryModel>(key: T, value: U) {
-    unsafe {
-        assembly_script_set_data(key.as_ptr_data(), value.as_ptr_data());
+    /// Build an Args from the parameter of an exported function (a wasmv1 buffer)
+    #[cfg(feature = "wasmv1")]
+    pub fn args_from_ptr(ptr: i32) -> Args {
+        // Safety: the host gives a buffer allocated with __alloc
+        Args::from(unsafe { crate::wasmv1::read_buffer(ptr) })
     }
-}
-
-pub fn get_data<T: AsMemoryModel>(key: T) -> i32 {
-    unsafe { assembly_script_get_data(key.as_ptr_data()) }
-}
-
-pub fn has_data<T: AsMemoryModel>(key: T) -> bool {
-    unsafe { assembly_script_has_data(key.as_ptr_data()) }
-}
 
-/// Return true if the caller has write access to the contract
-pub fn caller_has_write_access() -> bool {
-    unsafe { assembly_script_caller_has_write_access() }
-}
+    /// Serialize the value returned by an exported function (as Args) and return a pointer to it
+    #[cfg(not(feature = "wasmv1"))]
+    pub fn return_value<T: Serializable>(value: &T) -> i32 {
+        use crate::memory::AsMemoryModel;
+
+        let mut args = Args::new();
+        args.add(value);
+        let res: crate::AsVec<u8> = args.into_bytes().into_iter().collect();
+        let ptr = res.as_ptr_data();
+        // The host reads the value after the function returns
+        core::mem::forget(res);
+        ptr
+    }
 
-/// Return the balance of the current account
-pub fn get_balance() -> u64 {
-    unsafe { assembly_script_get_balance() }
-}
+    /// Serialize the value returned by an exported function (as Args) and return a pointer to it
+    /// (a wasmv1 buffer)
+    #[cfg(feature = "wasmv1")]
+    pub fn return_value<T: Serializable>(value: &T) -> i32 {
+        let mut args = Args::new();
+        args.add(value);
+        // The host reads the value after the function returns
+        crate::wasmv1::leak_buffer(args.as_bytes())
+    }
 
-/// Returns the amount transferred in the current call.
-///
-/// The returned value is related to the `coins` argument sent along the call.
-/// It is not related to the transferCoins or transferCoinsOf functions.
-pub fn get_call_coins() -> u64 {
-    unsafe { assembly_script_get_call_coins() }
+    /// Return a raw buffer from an exported function (e.g. an empty one for no returned value)
+    #[cfg(feature = "wasmv1")]
+    pub fn return_buffer(bytes: &[u8]) -> i32 {
+        crate::wasmv1::leak_buffer(bytes)
+    }
 }
 
-/// Return the current chain id
-///
-/// The chain id is a unique identifier for MAINNET, BUILDNET, ...
-pub fn chain_id() -> u64 {
-    unsafe { assembly_script_chain_id() }
+/// Define the exported wrapper of an #[export] function (used by massa_rust_sc_macros)
+#[cfg(not(feature = "wasmv1"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_wrapper {
+    ($export_name:literal, $wrapper:ident, [$($args:ident)?], $body:block) => {
+        #[doc(hidden)]
+        #[export_name = $export_name]
+        extern "C" fn $wrapper($($args: i32)?) $body
+    };
+    ($export_name:literal, $wrapper:ident, [$($args:ident)?], -> $body:block) => {
+        #[doc(hidden)]
+        #[export_name = $export_name]
+        extern "C" fn $wrapper($($args: i32)?) -> i32 $body
+    };
 }
 
-/// Returns the remaining gas for the current smart contract execution.
+/// Define the exported wrapper of an #[export] function (used by massa_rust_sc_macros)
 ///
-/// Gas is a measure of the computational resources required to execute a transaction on the blockchain.
-/// When there is no more gas, the execution of the smart contract is interrupted and all the transactions are reversed.
-pub fn get_remaining_gas() -> u64 {
-    unsafe { assembly_script_get_remaining_gas() }
+/// A wasmv1 function always takes a buffer (the arguments) and returns a buffer (the result)
+#[cfg(feature = "wasmv1")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_wrapper {
+    ($export_name:literal, $wrapper:ident, [$($args:ident)?], $body:block) => {
+        #[doc(hidden)]
+        #[export_name = $export_name]
+        extern "C" fn $wrapper(_massa_args: i32) -> i32 {
+            $(let $args = _massa_args;)?
+            $body;
+            $crate::__private::return_buffer(&[])
+        }
+    };
+    ($export_name:literal, $wrapper:ident, [$($args:ident)?], -> $body:block) => {
+        #[doc(hidden)]
+        #[export_name = $export_name]
+        extern "C" fn $wrapper(_massa_args: i32) -> i32 {
+            $(let $args = _massa_args;)?
+            $body
+        }
+    };
 }
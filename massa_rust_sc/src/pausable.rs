@@ -0,0 +1,102 @@
+// internal
+use crate::revert::revert;
+use crate::{get_data, has_data, set_data, AsSlice};
+
+/// Storage key of the paused flag (utf8 encoded)
+pub const PAUSED_KEY: AsSlice<u8> = crate::as_utf8!("PAUSED");
+
+const PAUSED: AsSlice<u8> = crate::as_bytes!(&[1]);
+const NOT_PAUSED: AsSlice<u8> = crate::as_bytes!(&[0]);
+
+/// Return true if the contract is paused
+///
+/// Note: a contract is not paused until [pause] is called
+pub fn is_paused() -> bool {
+    if !has_data(PAUSED_KEY) {
+        return false;
+    }
+    let value = AsSlice::<u8>::from(get_data(PAUSED_KEY) as *const u8);
+    *value == *PAUSED
+}
+
+/// Pause the contract and emit a "Paused" event (structured event, see [event!](crate::event!))
+///
+/// Reverts if the contract is already paused. Access control (e.g. only the owner can pause)
+/// is left to the caller.
+pub fn pause() {
+    when_not_paused();
+    set_data(PAUSED_KEY, PAUSED);
+    crate::event!("Paused");
+}
+
+/// Unpause the contract and emit an "Unpaused" event (structured event)
+///
+/// Reverts if the contract is not paused. Access control is left to the caller.
+pub fn unpause() {
+    when_paused();
+    set_data(PAUSED_KEY, NOT_PAUSED);
+    crate::event!("Unpaused");
+}
+
+/// Revert if the contract is paused (call it at the start of the functions to disable)
+pub fn when_not_paused() {
+    if is_paused() {
+        revert("Pausable: paused");
+    }
+}
+
+/// Revert if the contract is not paused
+pub fn when_paused() {
+    if !is_paused() {
+        revert("Pausable: not paused");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_test::*;
+
+    #[wasm_test]
+    fn test_pausable() {
+        assert!(!is_paused());
+        when_not_paused();
+
+        pause();
+        assert!(is_paused());
+        when_paused();
+        assert_eq!(*AsSlice::<u8>::from(get_data(PAUSED_KEY) as *const u8), [1]);
+
+        unpause();
+        assert!(!is_paused());
+        when_not_paused();
+    }
+
+    #[cfg(feature = "testing")]
+    #[wasm_test]
+    fn test_pausable_events() {
+        use crate::testing::{clear_events, events};
+
+        pause();
+        crate::assert_event_emitted!("Paused");
+        assert_eq!(events(), ["Paused:"]);
+
+        clear_events();
+        unpause();
+        crate::assert_event_emitted!("Unpaused");
+        assert_eq!(events(), ["Unpaused:"]);
+    }
+
+    #[crate::should_panic(expected = "Pausable: paused")]
+    #[wasm_test]
+    fn test_pause_when_paused() {
+        pause();
+        pause();
+    }
+
+    #[crate::should_panic(expected = "Pausable: not paused")]
+    #[wasm_test]
+    fn test_unpause_when_not_paused() {
+        unpause();
+    }
+}
@@ -0,0 +1,95 @@
+// internal
+use crate::revert::revert;
+use crate::{get_data, has_data, set_data, AsSlice};
+
+/// Storage key of the reentrancy guard status (utf8 encoded)
+pub const REENTRANCY_GUARD_KEY: AsSlice<u8> = crate::as_utf8!("ReentrancyGuard::STATUS");
+
+// Note: the status is overwritten instead of deleted (a storage write is cheaper than a
+// delete followed by a new entry)
+const NOT_ENTERED: AsSlice<u8> = crate::as_bytes!(&[1]);
+const ENTERED: AsSlice<u8> = crate::as_bytes!(&[2]);
+
+fn is_entered() -> bool {
+    if !has_data(REENTRANCY_GUARD_KEY) {
+        return false;
+    }
+    let value = AsSlice::<u8>::from(get_data(REENTRANCY_GUARD_KEY) as *const u8);
+    *value == *ENTERED
+}
+
+/// Run f, reverting if the contract is re-entered while f is running
+///
+/// Use it around functions that call other smart contracts (which could call back the current
+/// contract before the state is updated):
+///
+/// ```ignore
+/// #[export]
+/// fn withdraw(amount: Amount) {
+///     non_reentrant(|| {
+///         // update the balance, then transfer the coins / call the other contract
+///     })
+/// }
+/// ```
+///
+/// Note: if f reverts, the whole execution is reverted (including the guard status)
+pub fn non_reentrant<R>(f: impl FnOnce() -> R) -> R {
+    if is_entered() {
+        revert("ReentrancyGuard: reentrant call");
+    }
+    set_data(REENTRANCY_GUARD_KEY, ENTERED);
+    let res = f();
+    set_data(REENTRANCY_GUARD_KEY, NOT_ENTERED);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_test::*;
+
+    #[wasm_test]
+    fn test_non_reentrant() {
+        assert!(!is_entered());
+        let res = non_reentrant(|| {
+            assert!(is_entered());
+            42
+        });
+        assert_eq!(res, 42);
+        assert!(!is_entered());
+
+        // Can be entered again once f has returned
+        non_reentrant(|| assert!(is_entered()));
+        assert_eq!(
+            *AsSlice::<u8>::from(get_data(REENTRANCY_GUARD_KEY) as *const u8),
+            [1]
+        );
+    }
+
+    #[crate::should_panic(expected = "ReentrancyGuard: reentrant call")]
+    #[wasm_test]
+    fn test_nested_non_reentrant() {
+        non_reentrant(|| non_reentrant(|| ()));
+    }
+
+    /// Call back the current smart contract while the guard is entered
+    #[crate::export]
+    fn reentrancy_guard_test_reenter() {
+        non_reentrant(|| {
+            let call_stack = crate::get_call_stack().unwrap();
+            let address = alloc::string::String::from_utf16_lossy(call_stack.callee());
+            crate::call(
+                &address,
+                "reentrancy_guard_test_reenter",
+                &crate::Args::new(),
+                crate::Amount::zero(),
+            );
+        });
+    }
+
+    #[crate::should_panic(expected = "ReentrancyGuard: reentrant call")]
+    #[wasm_test]
+    fn test_reentrant_call() {
+        reentrancy_guard_test_reenter();
+    }
+}
@@ -0,0 +1,54 @@
+// internal
+#[cfg(not(feature = "wasmv1"))]
+use crate::{assembly_script_abort, memory::AsMemoryModel, AsVec};
+
+/// File name reported by the node along with the revert message
+#[cfg(not(feature = "wasmv1"))]
+const FILE_NAME: &str = "massa_rust_sc";
+
+/// Abort the execution with a message: all the changes (storage, coins, events) are reverted
+///
+/// The node reports the message as the execution error (e.g. in the result of a read only call)
+#[cfg(not(feature = "wasmv1"))]
+pub fn revert(message: &str) -> ! {
+    let message: AsVec<u16> = message.encode_utf16().collect();
+    let file_name: AsVec<u16> = FILE_NAME.encode_utf16().collect();
+    unsafe {
+        assembly_script_abort(message.as_ptr_data(), file_name.as_ptr_data(), 0, 0);
+    }
+    // abort never returns (the node stops the execution)
+    core::arch::wasm32::unreachable()
+}
+
+/// Abort the execution with a message: all the changes (storage, coins, events) are reverted
+///
+/// The node reports the message as the execution error (e.g. in the result of a read only call)
+#[cfg(feature = "wasmv1")]
+pub fn revert(message: &str) -> ! {
+    crate::wasmv1::abort(message)
+}
+
+/// Revert the execution with the given message if the condition is false
+pub fn require(condition: bool, message: &str) {
+    if !condition {
+        revert(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_test::*;
+
+    #[wasm_test]
+    fn test_require() {
+        require(true, "should not revert");
+        require(1 + 1 == 2, "should not revert");
+    }
+
+    #[crate::should_panic(expected = "require failed")]
+    #[wasm_test]
+    fn test_require_false() {
+        require(false, "require failed");
+    }
+}
@@ -0,0 +1,107 @@
+// internal
+use crate::revert::revert;
+use crate::{I128, I256, U128, U256};
+
+const ADDITION_OVERFLOW: &str = "SafeMath: addition overflow";
+const SUBTRACTION_OVERFLOW: &str = "SafeMath: subtraction overflow";
+const MULTIPLICATION_OVERFLOW: &str = "SafeMath: multiplication overflow";
+const DIVISION_BY_ZERO: &str = "SafeMath: division by zero";
+const DIVISION_OVERFLOW: &str = "SafeMath: division overflow";
+const MODULO_BY_ZERO: &str = "SafeMath: modulo by zero";
+
+/// Arithmetic operations that revert the execution (with a message) instead of overflowing
+///
+/// ```ignore
+/// let total = balance.safe_add(amount); // reverts with: SafeMath: addition overflow
+/// ```
+pub trait SafeMath: Sized {
+    fn safe_add(self, rhs: Self) -> Self;
+    fn safe_sub(self, rhs: Self) -> Self;
+    fn safe_mul(self, rhs: Self) -> Self;
+    /// Reverts on division by zero (and on MIN / -1 for signed integers)
+    fn safe_div(self, rhs: Self) -> Self;
+    fn safe_rem(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_safe_math {
+    ($($t:ty),+) => {
+        $(
+            impl SafeMath for $t {
+                fn safe_add(self, rhs: Self) -> Self {
+                    self.checked_add(rhs)
+                        .unwrap_or_else(|| revert(ADDITION_OVERFLOW))
+                }
+
+                fn safe_sub(self, rhs: Self) -> Self {
+                    self.checked_sub(rhs)
+                        .unwrap_or_else(|| revert(SUBTRACTION_OVERFLOW))
+                }
+
+                fn safe_mul(self, rhs: Self) -> Self {
+                    self.checked_mul(rhs)
+                        .unwrap_or_else(|| revert(MULTIPLICATION_OVERFLOW))
+                }
+
+                fn safe_div(self, rhs: Self) -> Self {
+                    if rhs == <$t>::default() {
+                        revert(DIVISION_BY_ZERO);
+                    }
+                    self.checked_div(rhs)
+                        .unwrap_or_else(|| revert(DIVISION_OVERFLOW))
+                }
+
+                fn safe_rem(self, rhs: Self) -> Self {
+                    if rhs == <$t>::default() {
+                        revert(MODULO_BY_ZERO);
+                    }
+                    // Note: MIN % -1 is 0 but checked_rem returns None (overflow of MIN / -1)
+                    self.checked_rem(rhs)
+                        .unwrap_or_else(|| revert(DIVISION_OVERFLOW))
+                }
+            }
+        )+
+    };
+}
+
+impl_safe_math!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, U128, U256, I128, I256);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_test::*;
+
+    #[wasm_test]
+    fn test_safe_math() {
+        assert_eq!(250u8.safe_add(5), 255);
+        assert_eq!(5u64.safe_sub(5), 0);
+        assert_eq!((-3i32).safe_sub(4), -7);
+        assert_eq!(u64::MAX.safe_div(2).safe_mul(2), u64::MAX - 1);
+        assert_eq!(7i64.safe_rem(-2), 1);
+        assert_eq!(i128::MIN.safe_div(1), i128::MIN);
+    }
+
+    #[wasm_test]
+    fn test_safe_math_bignum() {
+        let a = U256::from_u64(u64::MAX);
+        let b = a.safe_mul(a).safe_add(a);
+        assert_eq!(b.safe_div(a), U256::from_u64(u64::MAX).safe_add(U256::ONE));
+        assert_eq!(b.safe_rem(a), U256::ZERO);
+        assert_eq!(b.safe_sub(b), U256::ZERO);
+
+        let c = I256::from_i64(-10);
+        assert_eq!(c.safe_div(I256::from_i64(3)), I256::from_i64(-3));
+        assert_eq!(c.safe_rem(I256::from_i64(3)), I256::from_i64(-1));
+    }
+
+    #[crate::should_panic(expected = "SafeMath: addition overflow")]
+    #[wasm_test]
+    fn test_safe_add_overflow() {
+        u8::MAX.safe_add(1);
+    }
+
+    #[crate::should_panic(expected = "SafeMath: division by zero")]
+    #[wasm_test]
+    fn test_safe_div_by_zero() {
+        U256::ONE.safe_div(U256::ZERO);
+    }
+}
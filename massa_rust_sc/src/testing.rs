@@ -0,0 +1,271 @@
+//! Cheatcodes for the unit tests run by massa_sc_runner (enabled with the `testing` feature)
+//!
+//! Change the context of a unit test (caller, balances, time, ...) to test access control or
+//! time dependent code, e.g.:
+//!
+//! ```ignore
+//! #[wasm_test]
+//! fn test_only_owner() {
+//!     testing::set_caller("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x");
+//!     testing::expect_revert("Unauthorized");
+//!     withdraw();
+//! }
+//! ```
+//!
+//! Enable it for the tests only:
+//! `massa_rust_sc = { path = "...", features = ["testing"] }` in `[dev-dependencies]`.
+//!
+//! Note: the cheatcodes are imported from the `massa_test` module (not provided by a Massa node),
+//! a smart contract built with this feature cannot be deployed
+
+use alloc::string::String;
+use alloc::vec::Vec;
+// internal
+use crate::memory::AsMemoryModel;
+use crate::{Amount, Args, AsSlice, AsVec};
+
+/// Prefix of the cheatcode names (see massa_sc_runner cheatcodes)
+const CHEATCODE_PREFIX: &str = "massa_test:";
+
+#[link(wasm_import_module = "massa_test")]
+extern "C" {
+    /// Run a cheatcode of massa_sc_runner
+    ///
+    /// * name: a pointer to an utf16 string
+    /// * args: a pointer to a byte slice (prefixed with array size), serialized with [Args]
+    ///
+    /// Return: a pointer to a byte slice (prefixed with array size)
+    #[link_name = "cheatcode"]
+    fn massa_test_cheatcode(name: i32, args: i32) -> i32;
+}
+
+fn cheatcode(name: &str, args: &Args) -> Vec<u8> {
+    let name: AsVec<u16> = CHEATCODE_PREFIX
+        .encode_utf16()
+        .chain(name.encode_utf16())
+        .collect();
+    let args: AsVec<u8> = args.as_bytes().iter().copied().collect();
+    let res = unsafe { massa_test_cheatcode(name.as_ptr_data(), args.as_ptr_data()) };
+    AsSlice::<u8>::from(res as *const u8).to_vec()
+}
+
+/// Set the caller of the unit test (the bottom of the call stack)
+///
+/// Only the default caller has write access to the smart contract (see
+/// [caller_has_write_access](crate::caller_has_write_access))
+pub fn set_caller(address: &str) {
+    cheatcode("set_caller", Args::new().add_str(address));
+}
+
+/// Set the address of the smart contract executing the unit test
+///
+/// The smart contract (with its own balance & datastore) is created if needed
+pub fn set_callee(address: &str) {
+    cheatcode("set_callee", Args::new().add_str(address));
+}
+
+/// Set the coins returned by [get_call_coins](crate::get_call_coins) (the balances are not
+/// changed)
+pub fn set_call_coins(coins: Amount) {
+    cheatcode("set_call_coins", Args::new().add(&coins.to_raw()));
+}
+
+/// Set the balance of an address
+pub fn set_balance(address: &str, amount: Amount) {
+    cheatcode(
+        "set_balance",
+        Args::new().add_str(address).add(&amount.to_raw()),
+    );
+}
+
+/// Set the current time (in milliseconds since the Unix epoch), the current slot is the slot at
+/// this time
+pub fn set_time(time: u64) {
+    cheatcode("set_time", Args::new().add(&time));
+}
+
+/// Set the current slot, the current time is the timestamp of this slot
+pub fn warp_to_slot(period: u64, thread: u8) {
+    cheatcode("warp_to_slot", Args::new().add(&period).add(&thread));
+}
+
+/// Set the chain id returned by [chain_id](crate::chain_id)
+pub fn set_chain_id(chain_id: u64) {
+    cheatcode("set_chain_id", Args::new().add(&chain_id));
+}
+
+/// Expect the unit test to revert with a message containing `message` (any message if empty)
+///
+/// A revert cannot be caught so the call expected to revert must be the last one of the test:
+/// the test passes if it reverts as expected and fails otherwise.
+pub fn expect_revert(message: &str) {
+    cheatcode("expect_revert", Args::new().add_str(message));
+}
+
+/// Call a function of a smart contract (as [call](crate::call)) but return a failure (e.g. a
+/// revert) instead of interrupting the unit test, the changes of the failed call are reverted
+///
+/// Return the value returned by the function or the error message, e.g. to check that a failed
+/// call has no effect on the balances & the datastores:
+///
+/// ```ignore
+/// let balance = get_balance();
+/// let res = testing::try_call(&token, "transfer", &args, Amount::zero());
+/// assert!(res.unwrap_err().contains("insufficient balance"));
+/// assert_eq!(get_balance(), balance);
+/// ```
+///
+/// Note: the call has its own gas limit (the gas limit of the unit tests), the gas it uses is not
+///       charged to the unit test
+pub fn try_call(
+    address: &str,
+    function: &str,
+    args: &Args,
+    coins: Amount,
+) -> Result<Vec<u8>, String> {
+    let mut res = Args::from(cheatcode(
+        "try_call",
+        Args::new()
+            .add_str(address)
+            .add_str(function)
+            .add(&args.as_bytes().to_vec())
+            .add(&coins.to_raw()),
+    ));
+    // Unwrap safe: the runner returns the success followed by the value or the error message
+    if res.next_arg::<bool>().unwrap() {
+        Ok(res.next_arg().unwrap())
+    } else {
+        Err(res.next_arg().unwrap())
+    }
+}
+
+/// Events emitted by the unit test (since the last [clear_events]), in the emission order
+///
+/// The events emitted by a failed call (reverted) are not included.
+pub fn events() -> Vec<String> {
+    let mut res = Args::from(cheatcode("events", &Args::new()));
+    // Unwrap safe: the runner returns the number of events followed by the events
+    let count: u32 = res.next_arg().unwrap();
+    (0..count).map(|_| res.next_arg().unwrap()).collect()
+}
+
+/// Return true if the event was emitted by the unit test (since the last [clear_events])
+pub fn event_emitted(event: &str) -> bool {
+    events().iter().any(|e| e == event)
+}
+
+/// Forget the events emitted so far (e.g. the events emitted by the constructor)
+///
+/// Note: the events are still included in the test report of the runner
+pub fn clear_events() {
+    cheatcode("clear_events", &Args::new());
+}
+
+/// Assert that a structured event ("EventName:arg1,arg2", see [create_event](crate::create_event))
+/// was emitted by the unit test (since the last [clear_events])
+///
+/// ```ignore
+/// assert_event_emitted!("Transfer", from, to, amount);
+/// ```
+///
+/// Use [event_emitted] for a raw event (e.g. emitted with [emit_event](crate::emit_event)).
+#[macro_export]
+macro_rules! assert_event_emitted {
+    ($name:expr $(, $arg:expr)* $(,)?) => {{
+        let event = $crate::create_event($name, &[$(&$arg as &dyn ::core::fmt::Display),*]);
+        let events = $crate::testing::events();
+        // Note: revert (instead of assert!) so the message is reported by massa_sc_runner
+        if !events.iter().any(|e| *e == event) {
+            $crate::revert(&$crate::__private::format!(
+                "event {:?} was not emitted, emitted events: {:?}",
+                event,
+                events
+            ));
+        }
+    }};
+}
+
+/// Save the ledger (balances, bytecodes & datastores), return the snapshot id (see [revert_to])
+pub fn snapshot() -> u64 {
+    // Unwrap safe: the runner returns the snapshot id
+    Args::from(cheatcode("snapshot", &Args::new()))
+        .next_arg()
+        .unwrap()
+}
+
+/// Restore the ledger saved by [snapshot]
+pub fn revert_to(snapshot_id: u64) {
+    cheatcode("revert_to", Args::new().add(&snapshot_id));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        caller_has_write_access, chain_id, emit_event, event, get_balance, get_call_coins,
+        get_call_stack, get_current_slot, get_time, revert,
+    };
+    use wasm_test::*;
+
+    const USER: &str = "AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x";
+
+    #[wasm_test]
+    fn test_set_caller() {
+        assert!(caller_has_write_access());
+        set_caller(USER);
+        assert!(!caller_has_write_access());
+        let user: Vec<u16> = USER.encode_utf16().collect();
+        assert_eq!(get_call_stack().unwrap().caller(), user.as_slice());
+    }
+
+    #[wasm_test]
+    fn test_balance_and_call_coins() {
+        set_call_coins(Amount::from_raw(10));
+        assert_eq!(get_call_coins(), Amount::from_raw(10));
+
+        let snapshot_id = snapshot();
+        let callee: Vec<u16> = get_call_stack().unwrap().callee().to_vec();
+        let callee = String::from_utf16(&callee).unwrap();
+        set_balance(&callee, Amount::from_raw(42));
+        assert_eq!(get_balance(), Amount::from_raw(42));
+        revert_to(snapshot_id);
+        assert_ne!(get_balance(), Amount::from_raw(42));
+    }
+
+    #[wasm_test]
+    fn test_time_and_chain_id() {
+        warp_to_slot(10, 2);
+        assert_eq!(get_current_slot(), (10, 2));
+        set_time(get_time() + 16_000);
+        assert_eq!(get_current_slot(), (11, 2));
+        set_chain_id(77658366);
+        assert_eq!(chain_id(), 77658366);
+    }
+
+    #[wasm_test]
+    fn test_events() {
+        emit_event("Hello");
+        assert_eq!(events(), ["Hello"]);
+        assert!(event_emitted("Hello"));
+
+        clear_events();
+        assert!(events().is_empty());
+        let amount = Amount::from_raw(1_500_000_000);
+        event!("Transfer", "AU12", "AU13", amount);
+        assert_event_emitted!("Transfer", "AU12", "AU13", amount);
+        assert!(!event_emitted("Hello"));
+    }
+
+    #[crate::should_panic(expected = "event \"Transfer:AU12\" was not emitted")]
+    #[wasm_test]
+    fn test_assert_event_emitted_failure() {
+        event!("Transfer", "AU13");
+        assert_event_emitted!("Transfer", "AU12");
+    }
+
+    #[wasm_test]
+    fn test_expect_revert() {
+        expect_revert("Unauthorized");
+        revert("Unauthorized");
+    }
+}
@@ -0,0 +1,461 @@
+//! Bindings for the wasmv1 ABI of the Massa node (enabled with the `wasmv1` feature)
+//!
+//! Experimental: massa_sc_runner does not support the wasmv1 ABI yet, the unit tests of a wasmv1
+//! smart contract cannot be run.
+//!
+//! Unlike the AssemblyScript ABI (`assembly_script_*` functions), the wasmv1 ABI does not require
+//! to emulate the AssemblyScript memory layout: every host function takes a protobuf encoded
+//! request and returns a protobuf encoded response (see massa-proto: `massa/abi/v1/abi.proto`).
+//!
+//! Buffers exchanged with the host are: length (u32, little endian) + data, and the pointer
+//! given to (or returned by) the host points to the length. The host allocates its response
+//! buffers using the `__alloc` function exported by this module.
+//!
+//! Note: the Massa node only executes a bytecode with the wasmv1 ABI if the bytecode is
+//! prefixed with a 0x01 byte (a raw wasm file starts with 0x00: the wasm magic number). The
+//! prefix is added by `cargo massa build`, `massa_wasm_tools post` & deploy_smart_contract.
+//!
+//! The feature selects the ABI of the smart contract: the AssemblyScript API of this crate is not
+//! available with it (see examples/hello_wasmv1.rs).
+
+pub(crate) mod proto;
+mod types;
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+// internal
+use proto::{Decoder, Encoder, Message, Value};
+pub use types::{NativeAmount, NativeTime};
+
+/// Error returned by a wasmv1 ABI call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiError {
+    /// Error returned by the host (the `error` field of an AbiResponse)
+    Host(String),
+    /// Unable to decode the response of the host
+    Decode(&'static str),
+}
+
+impl AbiError {
+    pub(crate) const fn decode(reason: &'static str) -> Self {
+        AbiError::Decode(reason)
+    }
+}
+
+impl fmt::Display for AbiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbiError::Host(msg) => write!(f, "abi error: {msg}"),
+            AbiError::Decode(reason) => write!(f, "cannot decode abi response: {reason}"),
+        }
+    }
+}
+
+#[link(wasm_import_module = "massa")]
+extern "C" {
+    // All functions take a pointer to a request buffer and return a pointer to a response buffer
+
+    fn abi_abort(request: i32) -> i32;
+    fn abi_generate_event(request: i32) -> i32;
+    fn abi_set_ds_value(request: i32) -> i32;
+    fn abi_get_ds_value(request: i32) -> i32;
+    fn abi_append_ds_value(request: i32) -> i32;
+    fn abi_delete_ds_entry(request: i32) -> i32;
+    fn abi_ds_entry_exists(request: i32) -> i32;
+    fn abi_get_balance(request: i32) -> i32;
+    fn abi_get_call_coins(request: i32) -> i32;
+    fn abi_transfer_coins(request: i32) -> i32;
+    fn abi_get_call_stack(request: i32) -> i32;
+    fn abi_get_remaining_gas(request: i32) -> i32;
+    fn abi_get_native_time(request: i32) -> i32;
+    fn abi_native_amount_from_string(request: i32) -> i32;
+    fn abi_native_amount_to_string(request: i32) -> i32;
+    fn abi_add_native_amount(request: i32) -> i32;
+    fn abi_sub_native_amount(request: i32) -> i32;
+    fn abi_checked_add_native_time(request: i32) -> i32;
+    fn abi_checked_sub_native_time(request: i32) -> i32;
+}
+
+#[no_mangle]
+extern "C" fn __alloc(size: i32) -> i32 {
+    // Called by the host to allocate the buffer of a response
+    // buffer: length (4 bytes) + data (size bytes)
+    let size = size as usize;
+    let mut buffer = vec![0u8; 4 + size];
+    buffer[..4].copy_from_slice(&(size as u32).to_le_bytes());
+    buffer.leak().as_mut_ptr() as i32
+}
+
+/// Build a buffer exchanged with the host: length (u32, little endian) + data
+fn to_buffer(data: &[u8]) -> Vec<u8> {
+    let mut buffer = Vec::with_capacity(4 + data.len());
+    buffer.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(data);
+    buffer
+}
+
+/// Copy data to a buffer that is never freed, return a pointer to it (e.g. the value returned by
+/// an exported function, read by the host after the function returns)
+pub(crate) fn leak_buffer(data: &[u8]) -> i32 {
+    to_buffer(data).leak().as_ptr() as i32
+}
+
+/// Data of a buffer given by the host (e.g. the response of an ABI function)
+///
+/// # Safety
+///
+/// ptr must point to a buffer (length + data) that is never freed
+pub(crate) unsafe fn read_buffer<'a>(ptr: i32) -> &'a [u8] {
+    let ptr = ptr as *const u8;
+    let len = u32::from_le_bytes(*(ptr as *const [u8; 4]));
+    core::slice::from_raw_parts(ptr.add(4), len as usize)
+}
+
+/// Call an ABI function: encode the request, decode the AbiResponse and return the result bytes
+fn call_abi(abi_function: impl FnOnce(i32) -> i32, request: Encoder) -> Result<Vec<u8>, AbiError> {
+    let buffer = to_buffer(&request.finish());
+    let response_ptr = abi_function(buffer.as_ptr() as i32);
+    // Safety: the host allocates the response with __alloc (never freed)
+    let response = unsafe { read_buffer(response_ptr) };
+    decode_response(response)
+}
+
+/// Decode an AbiResponse: the result bytes or the error of the host
+fn decode_response(response: &[u8]) -> Result<Vec<u8>, AbiError> {
+    // AbiResponse { oneof resp { RespResult res = 1; Error error = 2; } }
+    // RespResult { bytes res = 1; }
+    // Error { string message = 1; }
+    let mut decoder = Decoder::new(response);
+    while let Some((field, value)) = decoder.next_field()? {
+        match field {
+            1 => {
+                let result = field_value(value.as_bytes()?, 1)?;
+                return result.map_or(Ok(Vec::new()), |v| v.as_bytes().map(<[u8]>::to_vec));
+            }
+            2 => {
+                let message = field_value(value.as_bytes()?, 1)?
+                    .map_or(Ok(String::new()), |v| v.as_string())?;
+                return Err(AbiError::Host(message));
+            }
+            _ => {}
+        }
+    }
+    Err(AbiError::decode("empty abi response"))
+}
+
+/// Return the value of a field in a message (last value if the field is present several times)
+fn field_value(message: &[u8], number: u32) -> Result<Option<Value<'_>>, AbiError> {
+    let mut res = None;
+    let mut decoder = Decoder::new(message);
+    while let Some((field, value)) = decoder.next_field()? {
+        if field == number {
+            res = Some(value);
+        }
+    }
+    Ok(res)
+}
+
+/// Return the embedded message of a field (or the default message if the field is not set)
+fn field_message<M: Message + Default>(message: &[u8], number: u32) -> Result<M, AbiError> {
+    field_value(message, number)?.map_or(Ok(M::default()), |v| v.as_message())
+}
+
+/// Abort the execution with a message (see [revert](crate::revert))
+pub(crate) fn abort(message: &str) -> ! {
+    // AbortRequest { string message = 1; }
+    let mut request = Encoder::new();
+    request.string(1, message);
+    // Note: the host never returns an AbiResponse (the execution stops)
+    let _ = call_abi(|r| unsafe { abi_abort(r) }, request);
+    core::arch::wasm32::unreachable()
+}
+
+/// Generate an event in the blockchain
+pub fn generate_event(event: impl AsRef<[u8]>) -> Result<(), AbiError> {
+    // GenerateEventRequest { bytes event = 1; }
+    let mut request = Encoder::new();
+    request.bytes(1, event.as_ref());
+    call_abi(|r| unsafe { abi_generate_event(r) }, request).map(|_| ())
+}
+
+/// Store a value in the storage of the current smart contract
+pub fn set_data(key: &[u8], value: &[u8]) -> Result<(), AbiError> {
+    set_data_for(None, key, value)
+}
+
+/// Store a value in the storage of a smart contract (None for the current smart contract)
+pub fn set_data_for(address: Option<&str>, key: &[u8], value: &[u8]) -> Result<(), AbiError> {
+    // SetDsValueRequest { bytes key = 1; bytes value = 2; optional string address = 3; }
+    let mut request = Encoder::new();
+    request
+        .bytes(1, key)
+        .bytes(2, value)
+        .optional_string(3, address);
+    call_abi(|r| unsafe { abi_set_ds_value(r) }, request).map(|_| ())
+}
+
+/// Get a value from the storage of the current smart contract
+pub fn get_data(key: &[u8]) -> Result<Vec<u8>, AbiError> {
+    get_data_for(None, key)
+}
+
+/// Get a value from the storage of a smart contract (None for the current smart contract)
+pub fn get_data_for(address: Option<&str>, key: &[u8]) -> Result<Vec<u8>, AbiError> {
+    // GetDsValueRequest { bytes key = 1; optional string address = 2; }
+    // GetDsValueResult { bytes value = 1; }
+    let mut request = Encoder::new();
+    request.bytes(1, key).optional_string(2, address);
+    let result = call_abi(|r| unsafe { abi_get_ds_value(r) }, request)?;
+    field_value(&result, 1)?.map_or(Ok(Vec::new()), |v| v.as_bytes().map(<[u8]>::to_vec))
+}
+
+/// Append a value to an existing value in the storage of a smart contract
+pub fn append_data_for(address: Option<&str>, key: &[u8], value: &[u8]) -> Result<(), AbiError> {
+    // AppendDsValueRequest { bytes key = 1; bytes value = 2; optional string address = 3; }
+    let mut request = Encoder::new();
+    request
+        .bytes(1, key)
+        .bytes(2, value)
+        .optional_string(3, address);
+    call_abi(|r| unsafe { abi_append_ds_value(r) }, request).map(|_| ())
+}
+
+/// Delete a value from the storage of a smart contract (None for the current smart contract)
+pub fn delete_data_for(address: Option<&str>, key: &[u8]) -> Result<(), AbiError> {
+    // DeleteDsEntryRequest { bytes key = 1; optional string address = 2; }
+    let mut request = Encoder::new();
+    request.bytes(1, key).optional_string(2, address);
+    call_abi(|r| unsafe { abi_delete_ds_entry(r) }, request).map(|_| ())
+}
+
+/// Check if a key exists in the storage of the current smart contract
+pub fn has_data(key: &[u8]) -> Result<bool, AbiError> {
+    has_data_for(None, key)
+}
+
+/// Check if a key exists in the storage of a smart contract (None for the current smart contract)
+pub fn has_data_for(address: Option<&str>, key: &[u8]) -> Result<bool, AbiError> {
+    // DsEntryExistsRequest { bytes key = 1; optional string address = 2; }
+    // DsEntryExistsResult { bool has_data = 1; }
+    let mut request = Encoder::new();
+    request.bytes(1, key).optional_string(2, address);
+    let result = call_abi(|r| unsafe { abi_ds_entry_exists(r) }, request)?;
+    field_value(&result, 1)?.map_or(Ok(false), |v| v.as_bool())
+}
+
+/// Return the balance of an address (None for the current smart contract)
+pub fn get_balance(address: Option<&str>) -> Result<NativeAmount, AbiError> {
+    // GetBalanceRequest { optional string address = 1; }
+    // GetBalanceResult { NativeAmount balance = 1; }
+    let mut request = Encoder::new();
+    request.optional_string(1, address);
+    let result = call_abi(|r| unsafe { abi_get_balance(r) }, request)?;
+    field_message(&result, 1)
+}
+
+/// Returns the amount transferred in the current call
+pub fn get_call_coins() -> Result<NativeAmount, AbiError> {
+    // GetCallCoinsResult { NativeAmount coins = 1; }
+    let result = call_abi(|r| unsafe { abi_get_call_coins(r) }, Encoder::new())?;
+    field_message(&result, 1)
+}
+
+/// Transfer coins from the current smart contract (or from sender if provided) to an address
+pub fn transfer_coins(
+    to_address: &str,
+    amount: NativeAmount,
+    sender: Option<&str>,
+) -> Result<(), AbiError> {
+    // TransferCoinsRequest {
+    //     string target_address = 1; NativeAmount amount_to_transfer = 2;
+    //     optional string sender_address = 3;
+    // }
+    let mut request = Encoder::new();
+    request
+        .string(1, to_address)
+        .message(2, &amount)
+        .optional_string(3, sender);
+    call_abi(|r| unsafe { abi_transfer_coins(r) }, request).map(|_| ())
+}
+
+/// Returns the addresses in the call stack, from the bottom to the top
+pub fn get_call_stack() -> Result<Vec<String>, AbiError> {
+    // GetCallStackResult { repeated string calls = 1; }
+    let result = call_abi(|r| unsafe { abi_get_call_stack(r) }, Encoder::new())?;
+    let mut calls = Vec::new();
+    let mut decoder = Decoder::new(&result);
+    while let Some((field, value)) = decoder.next_field()? {
+        if field == 1 {
+            calls.push(value.as_string()?);
+        }
+    }
+    Ok(calls)
+}
+
+/// Returns the remaining gas for the current smart contract execution
+pub fn get_remaining_gas() -> Result<u64, AbiError> {
+    // GetRemainingGasResult { uint64 remaining_gas = 1; }
+    let result = call_abi(|r| unsafe { abi_get_remaining_gas(r) }, Encoder::new())?;
+    field_value(&result, 1)?.map_or(Ok(0), |v| v.as_u64())
+}
+
+/// Return the current timestamp (of the slot being executed)
+pub fn get_native_time() -> Result<NativeTime, AbiError> {
+    // GetNativeTimeResult { NativeTime time = 1; }
+    let result = call_abi(|r| unsafe { abi_get_native_time(r) }, Encoder::new())?;
+    field_message(&result, 1)
+}
+
+/// Parse an amount (e.g. "1.5") using the host implementation
+pub fn native_amount_from_str(amount: &str) -> Result<NativeAmount, AbiError> {
+    // NativeAmountFromStringRequest { string to_convert = 1; }
+    // NativeAmountFromStringResult { NativeAmount converted_amount = 1; }
+    let mut request = Encoder::new();
+    request.string(1, amount);
+    let result = call_abi(|r| unsafe { abi_native_amount_from_string(r) }, request)?;
+    field_message(&result, 1)
+}
+
+/// Format an amount (e.g. "1.5") using the host implementation
+pub fn native_amount_to_string(amount: &NativeAmount) -> Result<String, AbiError> {
+    // NativeAmountToStringRequest { NativeAmount to_convert = 1; }
+    // NativeAmountToStringResult { string converted_amount = 1; }
+    let mut request = Encoder::new();
+    request.message(1, amount);
+    let result = call_abi(|r| unsafe { abi_native_amount_to_string(r) }, request)?;
+    field_value(&result, 1)?.map_or(Ok(String::new()), |v| v.as_string())
+}
+
+/// Add 2 amounts (error on overflow)
+pub fn add_native_amount(
+    amount1: &NativeAmount,
+    amount2: &NativeAmount,
+) -> Result<NativeAmount, AbiError> {
+    // AddNativeAmountRequest { NativeAmount amount1 = 1; NativeAmount amount2 = 2; }
+    // AddNativeAmountResult { NativeAmount sum = 1; }
+    let mut request = Encoder::new();
+    request.message(1, amount1).message(2, amount2);
+    let result = call_abi(|r| unsafe { abi_add_native_amount(r) }, request)?;
+    field_message(&result, 1)
+}
+
+/// Subtract 2 amounts (error on underflow)
+pub fn sub_native_amount(
+    left: &NativeAmount,
+    right: &NativeAmount,
+) -> Result<NativeAmount, AbiError> {
+    // SubNativeAmountRequest { NativeAmount left = 1; NativeAmount right = 2; }
+    // SubNativeAmountResult { NativeAmount difference = 1; }
+    let mut request = Encoder::new();
+    request.message(1, left).message(2, right);
+    let result = call_abi(|r| unsafe { abi_sub_native_amount(r) }, request)?;
+    field_message(&result, 1)
+}
+
+/// Add 2 times (error on overflow)
+pub fn checked_add_native_time(
+    left: &NativeTime,
+    right: &NativeTime,
+) -> Result<NativeTime, AbiError> {
+    // CheckedAddNativeTimeRequest { NativeTime left = 1; NativeTime right = 2; }
+    // CheckedAddNativeTimeResult { NativeTime sum = 1; }
+    let mut request = Encoder::new();
+    request.message(1, left).message(2, right);
+    let result = call_abi(|r| unsafe { abi_checked_add_native_time(r) }, request)?;
+    field_message(&result, 1)
+}
+
+/// Subtract 2 times (error on underflow)
+pub fn checked_sub_native_time(
+    left: &NativeTime,
+    right: &NativeTime,
+) -> Result<NativeTime, AbiError> {
+    // CheckedSubNativeTimeRequest { NativeTime left = 1; NativeTime right = 2; }
+    // CheckedSubNativeTimeResult { NativeTime difference = 1; }
+    let mut request = Encoder::new();
+    request.message(1, left).message(2, right);
+    let result = call_abi(|r| unsafe { abi_checked_sub_native_time(r) }, request)?;
+    field_message(&result, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use wasm_test::*;
+
+    /// AbiResponse with a result (RespResult { bytes res = 1; })
+    fn result_response(result: &[u8]) -> Vec<u8> {
+        let mut resp_result = Encoder::new();
+        resp_result.bytes(1, result);
+        let mut response = Encoder::new();
+        response.bytes(1, &resp_result.finish());
+        response.finish()
+    }
+
+    /// AbiResponse with an error (Error { string message = 1; })
+    fn error_response(message: &str) -> Vec<u8> {
+        let mut error = Encoder::new();
+        error.string(1, message);
+        let mut response = Encoder::new();
+        response.bytes(2, &error.finish());
+        response.finish()
+    }
+
+    #[wasm_test]
+    fn test_buffer_roundtrip() {
+        let ptr = leak_buffer(b"massa");
+        assert_eq!(unsafe { read_buffer(ptr) }, b"massa");
+        assert_eq!(to_buffer(b"ab"), [2, 0, 0, 0, b'a', b'b']);
+    }
+
+    #[wasm_test]
+    fn test_call_abi() {
+        // The request is given to the host as a buffer
+        let mut request = Encoder::new();
+        request.string(1, "key");
+        let response = leak_buffer(&result_response(b"value"));
+        let result = call_abi(
+            |r| {
+                let mut expected = Encoder::new();
+                expected.string(1, "key");
+                assert_eq!(unsafe { read_buffer(r) }, expected.finish().as_slice());
+                response
+            },
+            request,
+        );
+        assert_eq!(result, Ok(b"value".to_vec()));
+    }
+
+    #[wasm_test]
+    fn test_decode_response() {
+        assert_eq!(decode_response(&result_response(b"")), Ok(vec![]));
+        // GetBalanceResult { NativeAmount balance = 1; }
+        let mut balance = Encoder::new();
+        balance.message(1, &NativeAmount::new(15, 1));
+        let result = decode_response(&result_response(&balance.finish())).unwrap();
+        assert_eq!(field_message(&result, 1), Ok(NativeAmount::new(15, 1)));
+        assert_eq!(field_message(&[], 1), Ok(NativeAmount::default()));
+
+        // Errors
+        assert_eq!(
+            decode_response(&error_response("insufficient balance")),
+            Err(AbiError::Host("insufficient balance".into()))
+        );
+        assert_eq!(
+            decode_response(&[]),
+            Err(AbiError::decode("empty abi response"))
+        );
+        // Result field with a wrong wire type (varint)
+        assert_eq!(
+            decode_response(&[0x08, 0x01]),
+            Err(AbiError::decode("expected a length-delimited field"))
+        );
+        assert_eq!(
+            AbiError::Host("not found".into()).to_string(),
+            "abi error: not found"
+        );
+    }
+}
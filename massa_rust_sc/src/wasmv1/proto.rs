@@ -0,0 +1,222 @@
+// Minimal protobuf encoding / decoding (only what is needed by the wasmv1 ABI messages)
+// See: https://protobuf.dev/programming-guides/encoding/
+
+use alloc::string::String;
+use alloc::vec::Vec;
+// internal
+use crate::wasmv1::AbiError;
+
+const WIRE_TYPE_VARINT: u8 = 0;
+const WIRE_TYPE_I64: u8 = 1;
+const WIRE_TYPE_LEN: u8 = 2;
+const WIRE_TYPE_I32: u8 = 5;
+
+/// Protobuf message encoder
+#[derive(Debug, Default)]
+pub(crate) struct Encoder(Vec<u8>);
+
+impl Encoder {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+
+    fn tag(&mut self, field: u32, wire_type: u8) {
+        self.varint(((field as u64) << 3) | wire_type as u64);
+    }
+
+    pub(crate) fn uint64(&mut self, field: u32, value: u64) -> &mut Self {
+        self.tag(field, WIRE_TYPE_VARINT);
+        self.varint(value);
+        self
+    }
+
+    pub(crate) fn uint32(&mut self, field: u32, value: u32) -> &mut Self {
+        self.uint64(field, value as u64)
+    }
+
+    pub(crate) fn bytes(&mut self, field: u32, value: &[u8]) -> &mut Self {
+        self.tag(field, WIRE_TYPE_LEN);
+        self.varint(value.len() as u64);
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    pub(crate) fn string(&mut self, field: u32, value: &str) -> &mut Self {
+        self.bytes(field, value.as_bytes())
+    }
+
+    /// Encode an optional field (only if value is Some)
+    pub(crate) fn optional_string(&mut self, field: u32, value: Option<&str>) -> &mut Self {
+        if let Some(value) = value {
+            self.string(field, value);
+        }
+        self
+    }
+
+    /// Encode an embedded message
+    pub(crate) fn message(&mut self, field: u32, message: &impl Message) -> &mut Self {
+        let mut encoder = Encoder::new();
+        message.encode(&mut encoder);
+        self.bytes(field, encoder.0.as_slice())
+    }
+
+    pub(crate) fn finish(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+/// A decoded field value
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Value<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed64(u64),
+    Fixed32(u32),
+}
+
+impl<'a> Value<'a> {
+    pub(crate) fn as_u64(&self) -> Result<u64, AbiError> {
+        match self {
+            Value::Varint(v) | Value::Fixed64(v) => Ok(*v),
+            Value::Fixed32(v) => Ok(*v as u64),
+            Value::Bytes(_) => Err(AbiError::decode("expected an integer")),
+        }
+    }
+
+    pub(crate) fn as_bool(&self) -> Result<bool, AbiError> {
+        self.as_u64().map(|v| v != 0)
+    }
+
+    pub(crate) fn as_bytes(&self) -> Result<&'a [u8], AbiError> {
+        match self {
+            Value::Bytes(b) => Ok(b),
+            _ => Err(AbiError::decode("expected a length-delimited field")),
+        }
+    }
+
+    pub(crate) fn as_string(&self) -> Result<String, AbiError> {
+        core::str::from_utf8(self.as_bytes()?)
+            .map(String::from)
+            .map_err(|_| AbiError::decode("invalid utf-8 string"))
+    }
+
+    pub(crate) fn as_message<M: Message>(&self) -> Result<M, AbiError> {
+        M::decode(self.as_bytes()?)
+    }
+}
+
+/// Protobuf message decoder (iterate over the fields of a message)
+pub(crate) struct Decoder<'a> {
+    buffer: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub(crate) fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer, offset: 0 }
+    }
+
+    fn varint(&mut self) -> Result<u64, AbiError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self
+                .buffer
+                .get(self.offset)
+                .ok_or(AbiError::decode("truncated varint"))?;
+            self.offset += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(AbiError::decode("varint too long"))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], AbiError> {
+        let bytes = self
+            .offset
+            .checked_add(len)
+            .and_then(|end| self.buffer.get(self.offset..end))
+            .ok_or(AbiError::decode("truncated field"))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    /// Return the next (field number, value) or None at the end of the message
+    pub(crate) fn next_field(&mut self) -> Result<Option<(u32, Value<'a>)>, AbiError> {
+        if self.offset >= self.buffer.len() {
+            return Ok(None);
+        }
+        let tag = self.varint()?;
+        let field = (tag >> 3) as u32;
+        let value = match (tag & 0x7) as u8 {
+            WIRE_TYPE_VARINT => Value::Varint(self.varint()?),
+            WIRE_TYPE_I64 => {
+                // Unwrap safe: take returns exactly 8 bytes
+                Value::Fixed64(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+            }
+            WIRE_TYPE_LEN => {
+                let len = self.varint()? as usize;
+                Value::Bytes(self.take(len)?)
+            }
+            WIRE_TYPE_I32 => {
+                // Unwrap safe: take returns exactly 4 bytes
+                Value::Fixed32(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+            }
+            _ => return Err(AbiError::decode("unsupported wire type")),
+        };
+        Ok(Some((field, value)))
+    }
+}
+
+/// A protobuf message (subset of the massa-proto wasmv1 ABI messages)
+pub(crate) trait Message: Sized {
+    fn encode(&self, encoder: &mut Encoder);
+
+    fn decode(buffer: &[u8]) -> Result<Self, AbiError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use wasm_test::*;
+
+    #[wasm_test]
+    fn test_proto_roundtrip() {
+        let mut encoder = Encoder::new();
+        encoder
+            .uint64(1, 300)
+            .string(2, "massa")
+            .optional_string(3, None)
+            .uint32(4, 9);
+        let buffer = encoder.finish();
+        // field 1, varint 300 == [0xac, 0x02]
+        assert_eq!(&buffer[0..3], &[0x08, 0xac, 0x02]);
+
+        let mut decoder = Decoder::new(buffer.as_slice());
+        let (field, value) = decoder.next_field().unwrap().unwrap();
+        assert_eq!((field, value.as_u64().unwrap()), (1, 300));
+        let (field, value) = decoder.next_field().unwrap().unwrap();
+        assert_eq!((field, value.as_string().unwrap().as_str()), (2, "massa"));
+        let (field, value) = decoder.next_field().unwrap().unwrap();
+        assert_eq!((field, value.as_u64().unwrap()), (4, 9));
+        assert!(decoder.next_field().unwrap().is_none());
+    }
+
+    #[wasm_test]
+    fn test_proto_truncated() {
+        // field 1, length-delimited, len == 5 but only 1 byte
+        let buffer = vec![0x0a, 0x05, 0x01];
+        let mut decoder = Decoder::new(buffer.as_slice());
+        assert!(decoder.next_field().is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use core::fmt;
+// internal
+use crate::wasmv1::proto::{Decoder, Encoder, Message};
+use crate::wasmv1::AbiError;
+use crate::{Amount, AMOUNT_DECIMALS};
+
+/// An amount of Massa coins, as used by the wasmv1 ABI
+///
+/// value == mantissa * 10^-scale (e.g. mantissa: 15, scale: 1 == 1.5 MAS)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NativeAmount {
+    pub mantissa: u64,
+    pub scale: u32,
+}
+
+impl NativeAmount {
+    pub const fn new(mantissa: u64, scale: u32) -> Self {
+        Self { mantissa, scale }
+    }
+
+    /// Convert to an [Amount] (None if the value cannot be represented with 9 decimals)
+    pub fn to_amount(&self) -> Option<Amount> {
+        if self.scale <= AMOUNT_DECIMALS {
+            Amount::from_mantissa_scale(self.mantissa, self.scale).ok()
+        } else {
+            let divisor = 10u64.checked_pow(self.scale - AMOUNT_DECIMALS)?;
+            if self.mantissa % divisor != 0 {
+                return None;
+            }
+            Some(Amount::from_raw(self.mantissa / divisor))
+        }
+    }
+}
+
+impl From<Amount> for NativeAmount {
+    fn from(amount: Amount) -> Self {
+        Self::new(amount.to_raw(), AMOUNT_DECIMALS)
+    }
+}
+
+impl Message for NativeAmount {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.uint64(1, self.mantissa).uint32(2, self.scale);
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, AbiError> {
+        let mut res = Self::default();
+        let mut decoder = Decoder::new(buffer);
+        while let Some((field, value)) = decoder.next_field()? {
+            match field {
+                1 => res.mantissa = value.as_u64()?,
+                2 => res.scale = value.as_u64()? as u32,
+                _ => {}
+            }
+        }
+        Ok(res)
+    }
+}
+
+impl fmt::Display for NativeAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_amount() {
+            Some(amount) => write!(f, "{amount}"),
+            None => write!(f, "{}e-{}", self.mantissa, self.scale),
+        }
+    }
+}
+
+/// A duration or a timestamp (in milliseconds), as used by the wasmv1 ABI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct NativeTime {
+    pub milliseconds: u64,
+}
+
+impl NativeTime {
+    pub const fn from_millis(milliseconds: u64) -> Self {
+        Self { milliseconds }
+    }
+}
+
+impl Message for NativeTime {
+    fn encode(&self, encoder: &mut Encoder) {
+        encoder.uint64(1, self.milliseconds);
+    }
+
+    fn decode(buffer: &[u8]) -> Result<Self, AbiError> {
+        let mut res = Self::default();
+        let mut decoder = Decoder::new(buffer);
+        while let Some((field, value)) = decoder.next_field()? {
+            if field == 1 {
+                res.milliseconds = value.as_u64()?;
+            }
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+    use wasm_test::*;
+
+    #[wasm_test]
+    fn test_native_amount_to_amount() {
+        let amount = Amount::from_str("1.5").unwrap();
+        assert_eq!(NativeAmount::new(15, 1).to_amount(), Some(amount));
+        assert_eq!(NativeAmount::from(amount).to_amount(), Some(amount));
+        assert_eq!(
+            NativeAmount::new(15_000_000_000, 10).to_amount(),
+            Some(amount)
+        );
+        assert_eq!(NativeAmount::new(1, 10).to_amount(), None);
+    }
+
+    #[wasm_test]
+    fn test_native_amount_proto() {
+        let amount = NativeAmount::new(300, 2);
+        let mut encoder = Encoder::new();
+        amount.encode(&mut encoder);
+        let buffer = encoder.finish();
+        assert_eq!(buffer, &[0x08, 0xac, 0x02, 0x10, 0x02]);
+        assert_eq!(NativeAmount::decode(buffer.as_slice()), Ok(amount));
+    }
+}
@@ -0,0 +1,131 @@
+// std
+use std::fmt::Write;
+// third-party
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+use syn::Type;
+
+/// Name of the wasm custom section where the ABI entries are stored
+///
+/// Each entry is a json object followed by '\n' (the linker concatenates all the entries)
+pub(crate) const ABI_SECTION: &str = "massa_abi";
+
+/// A named parameter (function input or event field)
+pub(crate) struct AbiParam {
+    pub(crate) name: String,
+    pub(crate) ty: String,
+}
+
+/// Return the ABI type name of a Rust type (e.g. String -> "string", Vec<u8> -> "bytes")
+///
+/// Unknown types are returned as written in the source code (without whitespaces)
+pub(crate) fn abi_type(ty: &Type) -> String {
+    let ty = quote!(#ty).to_string().replace(' ', "");
+    // Only keep the last path segment (e.g. massa_rust_sc::Amount -> Amount)
+    let name = match ty.rsplit_once("::") {
+        Some((_, name)) if !ty.contains('<') => name.to_string(),
+        _ => ty.clone(),
+    };
+    match name.as_str() {
+        "u8" | "u16" | "u32" | "u64" | "i8" | "i16" | "i32" | "i64" | "f32" | "f64" | "bool" => {
+            name
+        }
+        "String" => "string".to_string(),
+        "Vec<u8>" => "bytes".to_string(),
+        "Amount" => "amount".to_string(),
+        "U128" | "U256" | "I128" | "I256" => name.to_lowercase(),
+        _ => ty,
+    }
+}
+
+fn params_to_json(params: &[AbiParam]) -> String {
+    let params: Vec<String> = params
+        .iter()
+        .map(|p| format!(r#"{{"name":"{}","type":"{}"}}"#, p.name, p.ty))
+        .collect();
+    format!("[{}]", params.join(","))
+}
+
+/// Json ABI entry of an exported function
+pub(crate) fn function_entry(name: &str, inputs: &[AbiParam], output: Option<String>) -> String {
+    let mut entry = format!(
+        r#"{{"type":"function","name":"{}","inputs":{}"#,
+        name,
+        params_to_json(inputs)
+    );
+    match output {
+        // Unwrap safe: writing to a String cannot fail
+        Some(output) => write!(entry, r#","outputs":[{{"type":"{output}"}}]}}"#).unwrap(),
+        None => entry.push_str(r#","outputs":[]}"#),
+    }
+    entry
+}
+
+/// Json ABI entry of an event
+pub(crate) fn event_entry(name: &str, fields: &[AbiParam]) -> String {
+    format!(
+        r#"{{"type":"event","name":"{}","inputs":{}}}"#,
+        name,
+        params_to_json(fields)
+    )
+}
+
+/// Generate a static stored in the ABI wasm custom section
+pub(crate) fn abi_static(kind: &str, name: &str, entry: &str) -> TokenStream {
+    let ident = format_ident!("__MASSA_ABI_{}_{}", kind, name.to_uppercase());
+    let bytes = format!("{entry}\n").into_bytes();
+    let len = bytes.len();
+    let literal = Literal::byte_string(&bytes);
+    let section = ABI_SECTION;
+    // Note: the static is never referenced, #[used] so the linker always keeps the custom section
+    //       (the entry is then also stored in the data section)
+    quote! {
+        #[doc(hidden)]
+        #[used]
+        #[cfg_attr(target_arch = "wasm32", link_section = #section)]
+        static #ident: [u8; #len] = *#literal;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_abi_type() {
+        assert_eq!(abi_type(&parse_quote!(u64)), "u64");
+        assert_eq!(abi_type(&parse_quote!(String)), "string");
+        assert_eq!(abi_type(&parse_quote!(alloc::string::String)), "string");
+        assert_eq!(abi_type(&parse_quote!(Vec<u8>)), "bytes");
+        assert_eq!(abi_type(&parse_quote!(massa_rust_sc::Amount)), "amount");
+        assert_eq!(abi_type(&parse_quote!(U256)), "u256");
+        assert_eq!(abi_type(&parse_quote!(Vec<u64>)), "Vec<u64>");
+    }
+
+    #[test]
+    fn test_entries() {
+        let inputs = [
+            AbiParam {
+                name: "to".to_string(),
+                ty: "string".to_string(),
+            },
+            AbiParam {
+                name: "amount".to_string(),
+                ty: "amount".to_string(),
+            },
+        ];
+        assert_eq!(
+            function_entry("transfer", &inputs, Some("bool".to_string())),
+            r#"{"type":"function","name":"transfer","inputs":[{"name":"to","type":"string"},{"name":"amount","type":"amount"}],"outputs":[{"type":"bool"}]}"#
+        );
+        assert_eq!(
+            function_entry("constructor", &[], None),
+            r#"{"type":"function","name":"constructor","inputs":[],"outputs":[]}"#
+        );
+        assert_eq!(
+            event_entry("Paused", &[]),
+            r#"{"type":"event","name":"Paused","inputs":[]}"#
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! Procedural macros for massa_rust_sc (re-exported by massa_rust_sc)
+//!
+//! #[export] & #[derive(MassaEvent)] also emit a json description (ABI) of the function / event
+//! in the `massa_abi` wasm custom section (see massa_rust_web3::ContractAbi to read it).
+
+mod abi;
+mod test_attrs;
+
+// third-party
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Expr, ExprLit, Fields, FnArg, ItemFn, Lit,
+    MetaNameValue, Pat, ReturnType, Type,
+};
+// internal
+use crate::abi::{abi_static, abi_type, event_entry, function_entry, AbiParam};
+use crate::test_attrs::{ignore_entry, should_panic_entry, test_attr_static};
+
+/// Export a function of a smart contract
+///
+/// Function arguments are deserialized from the call parameter (serialized with Args) and
+/// the returned value (if any) is serialized with Args. Arguments and returned value must
+/// implement massa_rust_sc::Serializable.
+///
+/// ```ignore
+/// #[export]
+/// fn transfer(to: String, amount: Amount) -> bool {
+///     // ...
+/// }
+/// ```
+///
+/// Note: the function is left untouched (so it can be called in unit tests), the exported
+/// wrapper is named `__massa_export_{name}`
+#[proc_macro_attribute]
+pub fn export(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return Error::new(
+            proc_macro2::Span::call_site(),
+            "#[export] does not take any argument",
+        )
+        .into_compile_error()
+        .into();
+    }
+    let func = parse_macro_input!(item as ItemFn);
+    match expand_export(&func) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+fn expand_export(func: &ItemFn) -> Result<proc_macro2::TokenStream, Error> {
+    let sig = &func.sig;
+    if !sig.generics.params.is_empty() || sig.asyncness.is_some() {
+        return Err(Error::new_spanned(
+            sig,
+            "#[export] functions cannot be generic or async",
+        ));
+    }
+
+    let name = &sig.ident;
+    let export_name = name.to_string();
+    let wrapper = format_ident!("__massa_export_{}", name);
+
+    let mut inputs = vec![];
+    let mut arg_names = vec![];
+    let mut arg_types = vec![];
+    for input in sig.inputs.iter() {
+        let FnArg::Typed(pat_type) = input else {
+            return Err(Error::new_spanned(
+                input,
+                "#[export] functions cannot take self",
+            ));
+        };
+        let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+            return Err(Error::new_spanned(
+                &pat_type.pat,
+                "#[export] function arguments must be identifiers",
+            ));
+        };
+        inputs.push(AbiParam {
+            name: pat_ident.ident.to_string(),
+            ty: abi_type(&pat_type.ty),
+        });
+        arg_names.push(format_ident!("__massa_arg_{}", pat_ident.ident));
+        arg_types.push(pat_type.ty.as_ref().clone());
+    }
+
+    let output = match &sig.output {
+        ReturnType::Default => None,
+        ReturnType::Type(_, ty) => match ty.as_ref() {
+            Type::Tuple(t) if t.elems.is_empty() => None,
+            ty => Some(ty),
+        },
+    };
+
+    let entry = function_entry(&export_name, &inputs, output.map(abi_type));
+    let abi = abi_static("FN", &export_name, &entry);
+
+    // Only take a parameter if the function has arguments (e.g. constructor())
+    // Note: the wrapper signature depends on the ABI selected by massa_rust_sc (a wasmv1
+    // function always takes and returns a buffer), see massa_rust_sc::__export_wrapper
+    let (param, deserialize) = if arg_names.is_empty() {
+        (quote!(), quote!())
+    } else {
+        let messages = inputs
+            .iter()
+            .map(|p| format!("{}: invalid argument {}", export_name, p.name));
+        (
+            quote!(__massa_args),
+            quote! {
+                let mut __massa_args = ::massa_rust_sc::__private::args_from_ptr(__massa_args);
+                #(
+                    let #arg_names = __massa_args.next_arg::<#arg_types>().expect(#messages);
+                )*
+            },
+        )
+    };
+
+    let wrapper_fn = match output {
+        None => quote! {
+            ::massa_rust_sc::__export_wrapper!(#export_name, #wrapper, [#param], {
+                #deserialize
+                #name(#(#arg_names),*);
+            });
+        },
+        Some(ty) => quote! {
+            ::massa_rust_sc::__export_wrapper!(#export_name, #wrapper, [#param], -> {
+                #deserialize
+                let __massa_res: #ty = #name(#(#arg_names),*);
+                ::massa_rust_sc::__private::return_value(&__massa_res)
+            });
+        },
+    };
+
+    Ok(quote! {
+        #func
+        #wrapper_fn
+        #abi
+    })
+}
+
+/// Derive massa_rust_sc::MassaEvent for a struct
+///
+/// The event is emitted as "StructName:field1,field2" (fields must implement Display).
+///
+/// ```ignore
+/// #[derive(MassaEvent)]
+/// struct Transfer {
+///     from: String,
+///     to: String,
+///     amount: Amount,
+/// }
+///
+/// Transfer { from, to, amount }.emit();
+/// ```
+#[proc_macro_derive(MassaEvent)]
+pub fn derive_massa_event(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    match expand_massa_event(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+fn expand_massa_event(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "MassaEvent can only be derived for structs",
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "MassaEvent cannot be derived for generic structs",
+        ));
+    }
+
+    let name = &input.ident;
+    let event_name = name.to_string();
+    let (fields, accessors) = match &data.fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f| {
+                // Unwrap safe: named fields
+                let ident = f.ident.as_ref().unwrap();
+                (
+                    AbiParam {
+                        name: ident.to_string(),
+                        ty: abi_type(&f.ty),
+                    },
+                    quote!(&self.#ident),
+                )
+            })
+            .unzip(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                let index = syn::Index::from(i);
+                (
+                    AbiParam {
+                        name: i.to_string(),
+                        ty: abi_type(&f.ty),
+                    },
+                    quote!(&self.#index),
+                )
+            })
+            .unzip(),
+        Fields::Unit => (vec![], vec![]),
+    };
+
+    let entry = event_entry(&event_name, &fields);
+    let abi = abi_static("EVENT", &event_name, &entry);
+
+    Ok(quote! {
+        impl ::massa_rust_sc::MassaEvent for #name {
+            const NAME: &'static str = #event_name;
+
+            fn to_event_string(&self) -> ::massa_rust_sc::__private::String {
+                ::massa_rust_sc::create_event(
+                    Self::NAME,
+                    &[#(#accessors as &dyn ::core::fmt::Display),*],
+                )
+            }
+        }
+        #abi
+    })
+}
+
+/// Mark a unit test as ignored (same as #[ignore] for a native test)
+///
+/// Must be placed above #[wasm_test]. Ignored tests are run by massa_sc_runner with --ignored
+/// or --include-ignored.
+///
+/// ```ignore
+/// #[massa_rust_sc::ignore]
+/// #[wasm_test]
+/// fn test_slow() {
+///     // ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn ignore(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return Error::new(
+            proc_macro2::Span::call_site(),
+            "#[ignore] does not take any argument",
+        )
+        .into_compile_error()
+        .into();
+    }
+    let func = parse_macro_input!(item as ItemFn);
+    let attr = test_attr_static(&ignore_entry(&func.sig.ident.to_string()));
+    quote! {
+        #attr
+        #func
+    }
+    .into()
+}
+
+/// Mark a unit test as expected to fail (same as #[should_panic] for a native test)
+///
+/// The test passes if it traps or reverts (e.g. with massa_rust_sc::revert). With `expected`,
+/// the failure message must contain the given string. Must be placed above #[wasm_test].
+///
+/// ```ignore
+/// #[massa_rust_sc::should_panic(expected = "SafeMath: addition overflow")]
+/// #[wasm_test]
+/// fn test_overflow() {
+///     u64::MAX.safe_add(1);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn should_panic(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let expected = if attr.is_empty() {
+        None
+    } else {
+        let meta = parse_macro_input!(attr as MetaNameValue);
+        match expected_value(&meta) {
+            Ok(expected) => Some(expected),
+            Err(e) => return e.into_compile_error().into(),
+        }
+    };
+    let func = parse_macro_input!(item as ItemFn);
+    let entry = should_panic_entry(&func.sig.ident.to_string(), expected.as_deref());
+    let attr = test_attr_static(&entry);
+    quote! {
+        #attr
+        #func
+    }
+    .into()
+}
+
+fn expected_value(meta: &MetaNameValue) -> Result<String, Error> {
+    match &meta.value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) if meta.path.is_ident("expected") => Ok(s.value()),
+        _ => Err(Error::new_spanned(
+            meta,
+            r#"expected: #[should_panic] or #[should_panic(expected = "...")]"#,
+        )),
+    }
+}
@@ -0,0 +1,82 @@
+// third-party
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+
+/// Name of the wasm custom section where the unit test attributes are stored
+/// (read by massa_sc_runner)
+///
+/// Each entry is a json object followed by '\n' (the linker concatenates all the entries)
+pub(crate) const TEST_ATTRS_SECTION: &str = "massa_test";
+
+/// Escape a string for a json string value
+fn json_escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res
+}
+
+/// Json entry of #[ignore]
+pub(crate) fn ignore_entry(test: &str) -> String {
+    format!(r#"{{"test":"{}","ignore":true}}"#, json_escape(test))
+}
+
+/// Json entry of #[should_panic] (with an optional expected substring of the failure message)
+pub(crate) fn should_panic_entry(test: &str, expected: Option<&str>) -> String {
+    match expected {
+        Some(expected) => format!(
+            r#"{{"test":"{}","should_panic":true,"expected":"{}"}}"#,
+            json_escape(test),
+            json_escape(expected)
+        ),
+        None => format!(r#"{{"test":"{}","should_panic":true}}"#, json_escape(test)),
+    }
+}
+
+/// Generate a static stored in the test attributes wasm custom section
+///
+/// Note: the static is in an anonymous const (tests with the same name can exist in
+///       different modules)
+pub(crate) fn test_attr_static(entry: &str) -> TokenStream {
+    let bytes = format!("{entry}\n").into_bytes();
+    let len = bytes.len();
+    let literal = Literal::byte_string(&bytes);
+    let section = TEST_ATTRS_SECTION;
+    quote! {
+        const _: () = {
+            #[allow(dead_code)]
+            #[cfg_attr(target_arch = "wasm32", link_section = #section)]
+            static __MASSA_TEST_ATTR: [u8; #len] = *#literal;
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries() {
+        assert_eq!(
+            ignore_entry("test_slow"),
+            r#"{"test":"test_slow","ignore":true}"#
+        );
+        assert_eq!(
+            should_panic_entry("test_overflow", Some(r#"SafeMath: "addition" overflow"#)),
+            r#"{"test":"test_overflow","should_panic":true,"expected":"SafeMath: \"addition\" overflow"}"#
+        );
+        assert_eq!(
+            should_panic_entry("test_revert", None),
+            r#"{"test":"test_revert","should_panic":true}"#
+        );
+    }
+}
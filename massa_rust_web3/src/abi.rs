@@ -0,0 +1,231 @@
+// third-party
+use serde::{Deserialize, Serialize};
+// massa
+use massa_api_exports::address::AddressFilter;
+use massa_models::address::Address;
+// internal
+use crate::{MassaJsonRpc, MassaRpcClient};
+
+/// Name of the wasm custom section where a smart contract stores its ABI
+///
+/// The section contains one json entry per line (see massa_rust_sc::export & MassaEvent)
+pub const ABI_SECTION: &str = "massa_abi";
+
+const WASM_MAGIC: &[u8] = b"\0asm";
+
+#[derive(thiserror::Error, Debug)]
+pub enum AbiError {
+    #[error("Invalid wasm bytecode: {0}")]
+    InvalidBytecode(&'static str),
+    #[error("No {ABI_SECTION} custom section found in bytecode")]
+    NotFound,
+    #[error("Invalid ABI entry: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Client(#[from] <MassaRpcClient as MassaJsonRpc>::RpcError),
+    #[error("No bytecode found for address: {0}")]
+    BytecodeNotFound(Address),
+}
+
+/// A function input / output or an event field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiParam {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// ABI type (e.g. "u64", "string", "bytes", "amount", "u256", ...)
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiFunction {
+    pub name: String,
+    pub inputs: Vec<AbiParam>,
+    pub outputs: Vec<AbiParam>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiEvent {
+    pub name: String,
+    pub inputs: Vec<AbiParam>,
+}
+
+/// An entry of the massa_abi custom section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum AbiEntry {
+    Function(AbiFunction),
+    Event(AbiEvent),
+}
+
+/// Description of the exported functions and events of a smart contract
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractAbi {
+    pub functions: Vec<AbiFunction>,
+    pub events: Vec<AbiEvent>,
+}
+
+impl ContractAbi {
+    /// Parse the content of the massa_abi custom section (one json entry per line)
+    pub fn from_section(data: &[u8]) -> Result<Self, AbiError> {
+        let mut abi = Self::default();
+        for line in data.split(|b| *b == b'\n') {
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            match serde_json::from_slice(line)? {
+                AbiEntry::Function(f) => abi.functions.push(f),
+                AbiEntry::Event(e) => abi.events.push(e),
+            }
+        }
+        Ok(abi)
+    }
+
+    /// Read the ABI of a smart contract from its bytecode (e.g. a .wasm file)
+    pub fn from_bytecode(bytecode: &[u8]) -> Result<Self, AbiError> {
+        let section = custom_section(bytecode, ABI_SECTION)?.ok_or(AbiError::NotFound)?;
+        Self::from_section(section)
+    }
+
+    /// Read the ABI of a deployed smart contract (bytecode is fetched from the node)
+    pub async fn fetch(client: &MassaRpcClient, address: Address) -> Result<Self, AbiError> {
+        let filter = AddressFilter {
+            address,
+            is_final: true,
+        };
+        let bytecode = client
+            .get_addresses_bytecode(vec![filter])
+            .await?
+            .pop()
+            .filter(|bytecode| !bytecode.is_empty())
+            .ok_or(AbiError::BytecodeNotFound(address))?;
+        Self::from_bytecode(&bytecode)
+    }
+
+    pub fn function(&self, name: &str) -> Option<&AbiFunction> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+
+    pub fn event(&self, name: &str) -> Option<&AbiEvent> {
+        self.events.iter().find(|e| e.name == name)
+    }
+}
+
+fn read_leb128_u32(bytecode: &[u8], offset: &mut usize) -> Result<u32, AbiError> {
+    let mut value = 0u32;
+    for shift in (0..35).step_by(7) {
+        let byte = *bytecode
+            .get(*offset)
+            .ok_or(AbiError::InvalidBytecode("truncated leb128"))?;
+        *offset += 1;
+        value |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(AbiError::InvalidBytecode("leb128 too long"))
+}
+
+/// Return the content of the first custom section with the given name
+///
+/// Note: wasmv1 bytecode (prefixed with 0x01) is supported
+pub fn custom_section<'a>(bytecode: &'a [u8], name: &str) -> Result<Option<&'a [u8]>, AbiError> {
+    // wasmv1 bytecode starts with a version byte
+    let bytecode = match bytecode.first() {
+        Some(1) => &bytecode[1..],
+        _ => bytecode,
+    };
+    if !bytecode.starts_with(WASM_MAGIC) || bytecode.len() < 8 {
+        return Err(AbiError::InvalidBytecode("missing wasm header"));
+    }
+
+    let mut offset = 8; // magic + version
+    while offset < bytecode.len() {
+        let id = bytecode[offset];
+        offset += 1;
+        let size = read_leb128_u32(bytecode, &mut offset)? as usize;
+        let section = bytecode
+            .get(offset..offset + size)
+            .ok_or(AbiError::InvalidBytecode("truncated section"))?;
+        offset += size;
+
+        if id == 0 {
+            let mut name_offset = 0;
+            let name_len = read_leb128_u32(section, &mut name_offset)? as usize;
+            let section_name = section
+                .get(name_offset..name_offset + name_len)
+                .ok_or(AbiError::InvalidBytecode("truncated custom section name"))?;
+            if section_name == name.as_bytes() {
+                return Ok(Some(&section[name_offset + name_len..]));
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECTION: &str = concat!(
+        r#"{"type":"event","name":"Transfer","inputs":[{"name":"to","type":"string"},{"name":"amount","type":"amount"}]}"#,
+        "\n",
+        r#"{"type":"function","name":"transfer","inputs":[{"name":"to","type":"string"},{"name":"amount","type":"amount"}],"outputs":[{"type":"bool"}]}"#,
+        "\n",
+    );
+
+    fn wasm_with_custom_section(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut content = vec![name.len() as u8];
+        content.extend_from_slice(name.as_bytes());
+        content.extend_from_slice(data);
+
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        // an empty type section
+        wasm.extend_from_slice(&[1, 1, 0]);
+        wasm.push(0);
+        // section size (leb128)
+        let mut size = content.len();
+        loop {
+            let byte = (size & 0x7f) as u8;
+            size >>= 7;
+            if size == 0 {
+                wasm.push(byte);
+                break;
+            }
+            wasm.push(byte | 0x80);
+        }
+        wasm.extend(content);
+        wasm
+    }
+
+    #[test]
+    fn test_abi_from_bytecode() {
+        let wasm = wasm_with_custom_section(ABI_SECTION, SECTION.as_bytes());
+        let abi = ContractAbi::from_bytecode(&wasm).unwrap();
+        assert_eq!(abi.functions.len(), 1);
+        assert_eq!(abi.events.len(), 1);
+        let transfer = abi.function("transfer").unwrap();
+        assert_eq!(transfer.inputs[1].name, "amount");
+        assert_eq!(transfer.inputs[1].ty, "amount");
+        assert_eq!(transfer.outputs[0].ty, "bool");
+        assert_eq!(abi.event("Transfer").unwrap().inputs.len(), 2);
+
+        // wasmv1 bytecode
+        let mut wasmv1 = vec![1];
+        wasmv1.extend_from_slice(&wasm);
+        assert_eq!(ContractAbi::from_bytecode(&wasmv1).unwrap(), abi);
+    }
+
+    #[test]
+    fn test_abi_not_found() {
+        let wasm = wasm_with_custom_section("name", b"");
+        assert!(matches!(
+            ContractAbi::from_bytecode(&wasm),
+            Err(AbiError::NotFound)
+        ));
+        assert!(matches!(
+            ContractAbi::from_bytecode(b"hello"),
+            Err(AbiError::InvalidBytecode(_))
+        ));
+    }
+}
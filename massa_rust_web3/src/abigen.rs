@@ -0,0 +1,423 @@
+// std
+use std::collections::HashSet;
+use std::path::Path;
+// third-party
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+// internal
+use crate::abi::{AbiError, AbiFunction, AbiParam, ContractAbi};
+
+#[derive(thiserror::Error, Debug)]
+pub enum AbigenError {
+    #[error("Cannot read ABI file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Abi(#[from] AbiError),
+    #[error("Invalid ABI json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Function {function}: unsupported ABI type: {ty}")]
+    UnsupportedType { function: String, ty: String },
+    #[error("Invalid Rust identifier: {0:?}")]
+    InvalidName(String),
+    #[error("Function {function}: generated method {method} is already defined")]
+    MethodCollision { function: String, method: String },
+    #[error("Function {function}: parameter {param} is already defined (or reserved)")]
+    ParamCollision { function: String, param: String },
+    #[error("Invalid generated code: {0}")]
+    Syntax(#[from] syn::Error),
+}
+
+/// Keywords that cannot be escaped (raw identifiers)
+const NON_RAW_KEYWORDS: [&str; 5] = ["self", "Self", "super", "crate", "_"];
+
+/// Name without the raw identifier prefix (e.g. r#type -> type)
+fn unraw(name: &str) -> &str {
+    name.strip_prefix("r#").unwrap_or(name)
+}
+
+/// Return the Rust identifier of an ABI name (keywords are escaped, e.g. type -> r#type)
+fn ident(name: &str) -> Result<Ident, AbigenError> {
+    let name = unraw(name);
+    if NON_RAW_KEYWORDS.contains(&name) {
+        return Err(AbigenError::InvalidName(name.to_string()));
+    }
+    if let Ok(ident) = syn::parse_str::<Ident>(name) {
+        return Ok(ident);
+    }
+    syn::parse_str::<Ident>(&format!("r#{name}"))
+        .map_err(|_| AbigenError::InvalidName(name.to_string()))
+}
+
+/// Return the Rust type of an ABI type (see massa_rust_sc_macros for the ABI type names)
+fn rust_type(function: &AbiFunction, param: &AbiParam) -> Result<TokenStream, AbigenError> {
+    let ty = match param.ty.as_str() {
+        "u8" => quote!(u8),
+        "u16" => quote!(u16),
+        "u32" => quote!(u32),
+        "u64" => quote!(u64),
+        "i8" => quote!(i8),
+        "i16" => quote!(i16),
+        "i32" => quote!(i32),
+        "i64" => quote!(i64),
+        "f32" => quote!(f32),
+        "f64" => quote!(f64),
+        "bool" => quote!(bool),
+        "string" => quote!(String),
+        "bytes" => quote!(Vec<u8>),
+        "amount" => quote!(::massa_rust_web3::Amount),
+        "u128" => quote!(u128),
+        "i128" => quote!(i128),
+        "u256" => quote!(::massa_rust_web3::U256),
+        "i256" => quote!(::massa_rust_web3::I256),
+        _ => {
+            return Err(AbigenError::UnsupportedType {
+                function: function.name.clone(),
+                ty: param.ty.clone(),
+            });
+        }
+    };
+    Ok(ty)
+}
+
+// Note: Amount (massa_models) is serialized as its number of nanoMAS (u64), like
+//       massa_rust_sc::Amount
+
+/// Serialize a function argument (in __args)
+fn add_arg(param: &AbiParam, name: &Ident) -> TokenStream {
+    if param.ty == "amount" {
+        quote!(__args.add(&#name.to_raw());)
+    } else {
+        quote!(__args.add(&#name);)
+    }
+}
+
+/// Deserialize the returned value (from __res)
+fn next_arg(param: &AbiParam, ty: &TokenStream) -> TokenStream {
+    if param.ty == "amount" {
+        quote!(::massa_rust_web3::Amount::from_raw(
+            __res.next_arg::<u64>()?
+        ))
+    } else {
+        quote!(__res.next_arg::<#ty>()?)
+    }
+}
+
+/// Generate the methods for a function: `{name}` (read only call) and `call_{name}` (operation)
+fn generate_function(function: &AbiFunction) -> Result<TokenStream, AbigenError> {
+    let name = function.name.as_str();
+    let method = ident(name)?;
+    let call_method = format_ident!("call_{}", unraw(name));
+
+    let mut params = vec![];
+    let mut types = vec![];
+    let mut add_args = vec![];
+    let mut param_names = HashSet::new();
+    for input in function.inputs.iter() {
+        let param = ident(&input.name)?;
+        // Note: names starting with __ are used by the generated code (__args, __res, ...)
+        if unraw(&input.name).starts_with("__") || !param_names.insert(unraw(&input.name)) {
+            return Err(AbigenError::ParamCollision {
+                function: name.to_string(),
+                param: input.name.clone(),
+            });
+        }
+        types.push(rust_type(function, input)?);
+        add_args.push(add_arg(input, &param));
+        params.push(param);
+    }
+    // CallOptions parameter of call_{name} (renamed if a function parameter is named options)
+    let options = if param_names.contains("options") {
+        format_ident!("__options")
+    } else {
+        format_ident!("options")
+    };
+    let args_mut = if function.inputs.is_empty() {
+        quote!()
+    } else {
+        quote!(mut)
+    };
+
+    let inputs: Vec<String> = function
+        .inputs
+        .iter()
+        .map(|i| format!("{}: {}", i.name, i.ty))
+        .collect();
+    let output = function.outputs.first();
+    let signature = match output {
+        Some(output) => format!("{}({}) -> {}", name, inputs.join(", "), output.ty),
+        None => format!("{}({})", name, inputs.join(", ")),
+    };
+    let read_doc = format!(" {signature} (read only call)");
+    let call_doc = format!(" {signature} (CallSC operation)");
+
+    // Read only call
+    let read = match output {
+        Some(output) => {
+            let ty = rust_type(function, output)?;
+            let value = next_arg(output, &ty);
+            quote! {
+                #[doc = #read_doc]
+                pub async fn #method(&self, #(#params: #types),*) -> Result<#ty, ::massa_rust_web3::ContractError> {
+                    let #args_mut __args = ::massa_rust_web3::Args::new();
+                    #(#add_args)*
+                    let mut __res = self.contract.read(#name, __args).await?;
+                    Ok(#value)
+                }
+            }
+        }
+        None => quote! {
+            #[doc = #read_doc]
+            pub async fn #method(&self, #(#params: #types),*) -> Result<(), ::massa_rust_web3::ContractError> {
+                let #args_mut __args = ::massa_rust_web3::Args::new();
+                #(#add_args)*
+                self.contract.read(#name, __args).await?;
+                Ok(())
+            }
+        },
+    };
+
+    // State-changing call
+    Ok(quote! {
+        #read
+
+        #[doc = #call_doc]
+        pub async fn #call_method(
+            &self,
+            #(#params: #types,)*
+            #options: ::massa_rust_web3::CallOptions,
+        ) -> Result<::massa_rust_web3::OperationId, ::massa_rust_web3::ContractError> {
+            let #args_mut __args = ::massa_rust_web3::Args::new();
+            #(#add_args)*
+            self.contract.call(#name, __args, #options).await
+        }
+    })
+}
+
+/// Generate a typed client (Rust source code) for a smart contract
+///
+/// The generated struct wraps a [ContractClient](crate::ContractClient) and has 2 async methods
+/// per exported function (except the constructor):
+/// * `{function}`: read only call, returns the Args-decoded value
+/// * `call_{function}`: state-changing call (CallSC operation), returns the operation id
+///
+/// Names that are Rust keywords are escaped (e.g. `r#type`), an error is returned if a name
+/// cannot be used (e.g. `self`) or if 2 generated methods have the same name (e.g. functions
+/// `x` and `call_x`, or a function `new`).
+pub fn abigen(abi: &ContractAbi, struct_name: &str) -> Result<String, AbigenError> {
+    let struct_ident = ident(struct_name)?;
+
+    // Methods of the generated struct (new & contract + 2 methods per function)
+    let mut methods: HashSet<String> = HashSet::from(["new".to_string(), "contract".to_string()]);
+    let mut functions = vec![];
+    for function in abi.functions.iter() {
+        // Constructor is only called at deployment
+        if function.name == "constructor" {
+            continue;
+        }
+        let name = unraw(&function.name);
+        for method in [name.to_string(), format!("call_{name}")] {
+            if !methods.insert(method.clone()) {
+                return Err(AbigenError::MethodCollision {
+                    function: function.name.clone(),
+                    method,
+                });
+            }
+        }
+        functions.push(generate_function(function)?);
+    }
+
+    let code = quote! {
+        pub struct #struct_ident {
+            contract: ::massa_rust_web3::ContractClient,
+        }
+
+        #[allow(dead_code)]
+        impl #struct_ident {
+            pub fn new(contract: ::massa_rust_web3::ContractClient) -> Self {
+                Self { contract }
+            }
+
+            pub fn contract(&self) -> &::massa_rust_web3::ContractClient {
+                &self.contract
+            }
+
+            #(#functions)*
+        }
+    };
+    let file = syn::parse2::<syn::File>(code)?;
+    Ok(format!(
+        "// Generated by massa_rust_web3::abigen - do not edit\n\n{}",
+        prettyplease::unparse(&file)
+    ))
+}
+
+/// Same as [abigen] but read the ABI from a file
+///
+/// The file can be either a json file (a serialized [ContractAbi]) or a smart contract
+/// bytecode (.wasm file, ABI is read from the massa_abi custom section).
+/// Usually called from a build script.
+pub fn abigen_from_file(path: impl AsRef<Path>, struct_name: &str) -> Result<String, AbigenError> {
+    let path = path.as_ref();
+    let content = std::fs::read(path)?;
+    let abi = if path.extension().is_some_and(|ext| ext == "wasm") {
+        ContractAbi::from_bytecode(&content)?
+    } else {
+        serde_json::from_slice(&content)?
+    };
+    abigen(&abi, struct_name)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param(name: &str, ty: &str) -> AbiParam {
+        AbiParam {
+            name: name.to_string(),
+            ty: ty.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_abigen() {
+        let abi = ContractAbi {
+            functions: vec![
+                AbiFunction {
+                    name: "constructor".to_string(),
+                    inputs: vec![],
+                    outputs: vec![],
+                },
+                AbiFunction {
+                    name: "greet".to_string(),
+                    inputs: vec![param("name", "string")],
+                    outputs: vec![param("", "string")],
+                },
+                AbiFunction {
+                    name: "pause".to_string(),
+                    inputs: vec![],
+                    outputs: vec![],
+                },
+            ],
+            events: vec![],
+        };
+
+        let code = abigen(&abi, "HelloWorld").unwrap();
+        // Valid Rust code
+        syn::parse_file(&code).unwrap();
+        assert!(code.starts_with("// Generated by massa_rust_web3::abigen - do not edit"));
+        assert!(code.contains("pub struct HelloWorld {"));
+        assert!(!code.contains("fn constructor"));
+        assert!(code.contains(
+            "pub async fn greet(\n        &self,\n        name: String,\n    ) -> Result<String, ::massa_rust_web3::ContractError> {"
+        ));
+        assert!(code.contains("__args.add(&name);"));
+        assert!(code.contains("Ok(__res.next_arg::<String>()?)"));
+        assert!(code.contains(
+            "pub async fn call_greet(\n        &self,\n        name: String,\n        options: ::massa_rust_web3::CallOptions,"
+        ));
+        assert!(code.contains(
+            "pub async fn pause(&self) -> Result<(), ::massa_rust_web3::ContractError> {"
+        ));
+        assert!(code.contains("let __args = ::massa_rust_web3::Args::new();"));
+    }
+
+    #[test]
+    fn test_abigen_types_and_names() {
+        let abi = ContractAbi {
+            functions: vec![AbiFunction {
+                name: "transfer".to_string(),
+                inputs: vec![
+                    param("type", "u256"),
+                    param("options", "amount"),
+                    param("r#fn", "i256"),
+                ],
+                outputs: vec![param("", "amount")],
+            }],
+            events: vec![],
+        };
+
+        let code = abigen(&abi, "Token").unwrap();
+        syn::parse_file(&code).unwrap();
+        // Typed wide integers
+        assert!(code.contains("r#type: ::massa_rust_web3::U256,"));
+        assert!(code.contains("r#fn: ::massa_rust_web3::I256,"));
+        // Amount is serialized as its number of nanoMAS
+        assert!(code.contains("options: ::massa_rust_web3::Amount,"));
+        assert!(code.contains("__args.add(&options.to_raw());"));
+        assert!(code.contains("Ok(::massa_rust_web3::Amount::from_raw(__res.next_arg::<u64>()?))"));
+        // The CallOptions parameter is renamed (a function parameter is named options)
+        assert!(code.contains("__options: ::massa_rust_web3::CallOptions,"));
+        assert!(code.contains(r#"self.contract.call("transfer", __args, __options).await"#));
+    }
+
+    #[test]
+    fn test_abigen_invalid_names() {
+        let function = |name: &str, inputs: Vec<AbiParam>| AbiFunction {
+            name: name.to_string(),
+            inputs,
+            outputs: vec![],
+        };
+        let abigen_functions = |functions: Vec<AbiFunction>| {
+            let abi = ContractAbi {
+                functions,
+                events: vec![],
+            };
+            abigen(&abi, "Contract")
+        };
+
+        for name in ["self", "Self", "super", "crate", "_", "", "1x", "a-b"] {
+            assert!(matches!(
+                abigen_functions(vec![function("f", vec![param(name, "u64")])]),
+                Err(AbigenError::InvalidName(_))
+            ));
+            assert!(matches!(
+                abigen_functions(vec![function(name, vec![])]),
+                Err(AbigenError::InvalidName(_))
+            ));
+        }
+        assert!(matches!(
+            abigen(&ContractAbi::default(), "self"),
+            Err(AbigenError::InvalidName(_))
+        ));
+
+        // Generated methods with the same name
+        for functions in [
+            vec![function("new", vec![])],
+            vec![function("contract", vec![])],
+            vec![function("x", vec![]), function("call_x", vec![])],
+            vec![function("x", vec![]), function("x", vec![])],
+        ] {
+            assert!(matches!(
+                abigen_functions(functions),
+                Err(AbigenError::MethodCollision { .. })
+            ));
+        }
+
+        // Parameters with the same name (or reserved)
+        for inputs in [
+            vec![param("a", "u64"), param("a", "u8")],
+            vec![param("a", "u64"), param("r#a", "u8")],
+            vec![param("__args", "u64")],
+        ] {
+            assert!(matches!(
+                abigen_functions(vec![function("f", inputs)]),
+                Err(AbigenError::ParamCollision { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_abigen_unsupported_type() {
+        let abi = ContractAbi {
+            functions: vec![AbiFunction {
+                name: "sum".to_string(),
+                inputs: vec![param("values", "Vec<u64>")],
+                outputs: vec![],
+            }],
+            events: vec![],
+        };
+        assert!(matches!(
+            abigen(&abi, "Sum"),
+            Err(AbigenError::UnsupportedType { .. })
+        ));
+    }
+}
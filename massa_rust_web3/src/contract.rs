@@ -0,0 +1,161 @@
+// massa
+use massa_api_exports::execution::{ReadOnlyCall, ReadOnlyResult};
+use massa_args::{Args, ArgsError};
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::operation::{Operation, OperationId, OperationType};
+use massa_signature::KeyPair;
+// internal
+use crate::{MassaJsonRpc, MassaRpcClient};
+
+// From: node_modules/@massalabs/massa-web3/dist/cmd/smartContracts/constants.d.ts
+const MIN_GAS_CALL: u64 = 2100000;
+const MAX_GAS_CALL: u64 = 4294167295;
+const PERIOD_TO_LIVE_DEFAULT: u64 = 9;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ContractError {
+    #[error(transparent)]
+    Client(#[from] <MassaRpcClient as MassaJsonRpc>::RpcError),
+    #[error("Read only call of {0} failed: {1}")]
+    ReadOnly(String, String),
+    #[error("Empty response from node for function: {0}")]
+    EmptyResponse(String),
+    #[error("Unable to decode the returned value: {0}")]
+    Args(#[from] ArgsError),
+    #[error("A keypair is required to send an operation")]
+    MissingKeyPair,
+    #[error("Node status has no last slot (unable to compute the operation expire period)")]
+    MissingLastSlot,
+}
+
+/// Options for a state-changing call (CallSC operation)
+#[derive(Debug, Clone, Default)]
+pub struct CallOptions {
+    /// Coins sent along the call (Default to: 0)
+    pub coins: Option<Amount>,
+    /// Fee for the operation (If None, use the minimal fee fetched from the rpc)
+    pub fee: Option<Amount>,
+    /// Max gas (If None, gas will be estimated with a read only call)
+    pub max_gas: Option<u64>,
+}
+
+/// Call the functions of a deployed smart contract
+///
+/// Used by the code generated by [abigen](crate::abigen) but can also be used directly
+pub struct ContractClient {
+    client: MassaRpcClient,
+    address: Address,
+    caller: Option<Address>,
+    keypair: Option<KeyPair>,
+}
+
+impl ContractClient {
+    pub fn new(url: impl AsRef<str>, address: Address) -> Self {
+        Self {
+            client: MassaRpcClient::new(url),
+            address,
+            caller: None,
+            keypair: None,
+        }
+    }
+
+    /// Address used as the caller of read only calls
+    pub fn with_caller(mut self, caller: Address) -> Self {
+        self.caller = Some(caller);
+        self
+    }
+
+    /// Keypair used to sign the operations (and as the caller of read only calls)
+    pub fn with_keypair(mut self, keypair: KeyPair) -> Self {
+        self.caller = Some(Address::from_public_key(&keypair.get_public_key()));
+        self.keypair = Some(keypair);
+        self
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Call a function in read only mode (no operation is sent) and return the result
+    pub async fn read(&self, function: &str, args: Args) -> Result<Args, ContractError> {
+        let (result, _gas_cost) = self.read_only_call(function, args, None).await?;
+        Ok(Args::from(result))
+    }
+
+    async fn read_only_call(
+        &self,
+        function: &str,
+        args: Args,
+        coins: Option<Amount>,
+    ) -> Result<(Vec<u8>, u64), ContractError> {
+        let read_params = ReadOnlyCall {
+            max_gas: MAX_GAS_CALL,
+            target_address: self.address,
+            target_function: function.to_string(),
+            parameter: args.into_bytes(),
+            caller_address: self.caller,
+            coins,
+            // Note: should always be None otherwise got error
+            //       Runtime error: spending address AUXXXXX... not found
+            fee: None,
+        };
+
+        let response = self
+            .client
+            .execute_read_only_call(vec![read_params])
+            .await?
+            .pop()
+            .ok_or_else(|| ContractError::EmptyResponse(function.to_string()))?;
+
+        match response.result {
+            ReadOnlyResult::Ok(result) => Ok((result, response.gas_cost)),
+            ReadOnlyResult::Error(e) => Err(ContractError::ReadOnly(function.to_string(), e)),
+        }
+    }
+
+    /// Call a function with a CallSC operation (state-changing call)
+    ///
+    /// Return the operation id (the returned value of the function is not available)
+    pub async fn call(
+        &self,
+        function: &str,
+        args: Args,
+        options: CallOptions,
+    ) -> Result<OperationId, ContractError> {
+        let keypair = self.keypair.as_ref().ok_or(ContractError::MissingKeyPair)?;
+        let coins = options.coins.unwrap_or(Amount::zero());
+
+        let max_gas = match options.max_gas {
+            Some(max_gas) => max_gas,
+            None => {
+                let (_, gas_cost) = self
+                    .read_only_call(function, args.clone(), Some(coins))
+                    .await?;
+                // Like massa-web3, use a 20% margin for gas estimation
+                (gas_cost.saturating_mul(12) / 10).clamp(MIN_GAS_CALL, MAX_GAS_CALL)
+            }
+        };
+
+        let status = self.client.get_status().await?;
+        let last_slot = status.last_slot.ok_or(ContractError::MissingLastSlot)?;
+
+        let op = OperationType::CallSC {
+            target_addr: self.address,
+            target_func: function.to_string(),
+            param: args.into_bytes(),
+            max_gas,
+            coins,
+        };
+        let content = Operation {
+            fee: options.fee.unwrap_or(status.minimal_fees),
+            op,
+            expire_period: last_slot.period + PERIOD_TO_LIVE_DEFAULT,
+        };
+
+        let mut op_ids = self.client.send_operations(content, keypair).await?;
+        op_ids
+            .pop()
+            .ok_or_else(|| ContractError::EmptyResponse(function.to_string()))
+    }
+}
@@ -14,6 +14,7 @@ use massa_models::execution::EventFilter;
 use massa_models::operation::{Operation, OperationType};
 use massa_serialization::{SerializeError, Serializer};
 use massa_signature::KeyPair;
+use massa_wasm_tools::WasmToolsError;
 // internal
 use crate::deploy::DEPLOYER_BYTECODE;
 use crate::jsonrpc_common::ExecuteReadOnlyResponseLw;
@@ -35,6 +36,8 @@ pub enum DeployError {
     InvalidAddress(String),
     #[error("Unable to retrieve the address of the deployed smart contract from events")]
     AddressNotFound,
+    #[error("Smart contract cannot be deployed: {0}")]
+    InvalidSmartContract(#[from] WasmToolsError),
 }
 
 #[derive(Debug, Clone, Default)]
@@ -52,6 +55,17 @@ pub struct DeployerArgs {
     pub max_gas: Option<u64>,
 }
 
+/// Prepare a smart contract bytecode for deployment (done by [deploy_smart_contract])
+///
+/// A wasmv1 smart contract is prefixed with 0x01 (if not already done by the post processing,
+/// see massa_wasm_tools::add_wasmv1_prefix), then the bytecode is checked (wasm features,
+/// imports & size) before paying for the deployment.
+pub fn prepare_smart_contract(bytecode: Vec<u8>) -> Result<Vec<u8>, DeployError> {
+    let bytecode = massa_wasm_tools::add_wasmv1_prefix(bytecode)?;
+    massa_wasm_tools::validate(&bytecode)?;
+    Ok(bytecode)
+}
+
 #[allow(clippy::collapsible_if)]
 #[allow(clippy::manual_range_contains)]
 pub async fn deploy_smart_contract(
@@ -72,6 +86,7 @@ pub async fn deploy_smart_contract(
     let mut file_content = Vec::new();
     let mut fs = tokio::fs::File::open(smart_contract).await?;
     fs.read_to_end(&mut file_content).await?;
+    let file_content = prepare_smart_contract(file_content)?;
     let file_content_len = file_content.len();
 
     // Create client
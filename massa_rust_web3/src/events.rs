@@ -0,0 +1,147 @@
+// std
+use std::fmt;
+use std::str::FromStr;
+// massa
+use massa_models::output_event::SCOutputEvent;
+
+/// Separator between the event name and its arguments (see massa_rust_sc::create_event)
+pub const EVENT_NAME_SEPARATOR: char = ':';
+/// Separator between the event arguments
+pub const EVENT_ARGS_SEPARATOR: char = ',';
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum EventError {
+    #[error("Not a structured event (expected 'EventName:arg1,arg2'): {0}")]
+    InvalidFormat(String),
+    #[error("Event {name} has no argument at index {index}")]
+    MissingArg { name: String, index: usize },
+    #[error("Unable to parse argument {index} of event {name}: {value}")]
+    InvalidArg {
+        name: String,
+        index: usize,
+        value: String,
+    },
+}
+
+/// A structured event emitted by a smart contract
+///
+/// Format is: "EventName:arg1,arg2" (like createEvent in massa-standards or the event! macro
+/// in massa_rust_sc)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractEvent {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+impl ContractEvent {
+    /// Decode the data of an event (as returned by get_filtered_sc_output_event)
+    pub fn from_output_event(event: &SCOutputEvent) -> Result<Self, EventError> {
+        Self::from_str(&event.data)
+    }
+
+    /// Return the argument at the given index
+    pub fn arg(&self, index: usize) -> Result<&str, EventError> {
+        self.args
+            .get(index)
+            .map(String::as_str)
+            .ok_or_else(|| EventError::MissingArg {
+                name: self.name.clone(),
+                index,
+            })
+    }
+
+    /// Parse the argument at the given index (e.g. as an u64 or an Address)
+    pub fn parse_arg<T: FromStr>(&self, index: usize) -> Result<T, EventError> {
+        let value = self.arg(index)?;
+        value.parse().map_err(|_| EventError::InvalidArg {
+            name: self.name.clone(),
+            index,
+            value: value.to_string(),
+        })
+    }
+}
+
+impl FromStr for ContractEvent {
+    type Err = EventError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, args) = s
+            .split_once(EVENT_NAME_SEPARATOR)
+            .filter(|(name, _)| !name.is_empty())
+            .ok_or_else(|| EventError::InvalidFormat(s.to_string()))?;
+
+        let args = if args.is_empty() {
+            vec![]
+        } else {
+            args.split(EVENT_ARGS_SEPARATOR).map(String::from).collect()
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            args,
+        })
+    }
+}
+
+impl fmt::Display for ContractEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            self.name,
+            EVENT_NAME_SEPARATOR,
+            self.args.join(&EVENT_ARGS_SEPARATOR.to_string())
+        )
+    }
+}
+
+/// Decode all the structured events with the given name (other events are ignored)
+pub fn filter_events<'a>(
+    events: &'a [SCOutputEvent],
+    name: &'a str,
+) -> impl Iterator<Item = ContractEvent> + 'a {
+    events
+        .iter()
+        .filter_map(|event| ContractEvent::from_output_event(event).ok())
+        .filter(move |event| event.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contract_event_from_str() {
+        let event = ContractEvent::from_str("Transfer:AU12,AU13,1.5").unwrap();
+        assert_eq!(event.name, "Transfer");
+        assert_eq!(event.args, vec!["AU12", "AU13", "1.5"]);
+        assert_eq!(event.arg(1), Ok("AU13"));
+        assert_eq!(event.parse_arg::<f64>(2), Ok(1.5));
+        assert!(matches!(
+            event.parse_arg::<u64>(0),
+            Err(EventError::InvalidArg { index: 0, .. })
+        ));
+        assert!(matches!(
+            event.arg(3),
+            Err(EventError::MissingArg { index: 3, .. })
+        ));
+        assert_eq!(event.to_string(), "Transfer:AU12,AU13,1.5");
+
+        let event = ContractEvent::from_str("Paused:").unwrap();
+        assert_eq!(event.name, "Paused");
+        assert!(event.args.is_empty());
+        assert_eq!(event.to_string(), "Paused:");
+    }
+
+    #[test]
+    fn test_contract_event_invalid() {
+        assert!(matches!(
+            ContractEvent::from_str("hello there 42!!"),
+            Err(EventError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            ContractEvent::from_str(":42"),
+            Err(EventError::InvalidFormat(_))
+        ));
+    }
+}
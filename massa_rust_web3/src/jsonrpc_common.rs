@@ -4,7 +4,7 @@ use std::collections::VecDeque;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 // Massa
 use massa_api_exports::{
-    address::AddressInfo,
+    address::{AddressFilter, AddressInfo},
     execution::{ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult},
     node::NodeStatus,
     operation::{OperationInfo, OperationInput},
@@ -49,6 +49,15 @@ pub trait MassaJsonRpc {
         self.post("get_addresses", params).await
     }
 
+    /// Get the bytecode of the given addresses (empty if the address is not a smart contract)
+    async fn get_addresses_bytecode(
+        &self,
+        addresses: Vec<AddressFilter>,
+    ) -> Result<Vec<Vec<u8>>, Self::RpcError> {
+        let params = Self::prepare_params(addresses);
+        self.post("get_addresses_bytecode", params).await
+    }
+
     async fn get_filtered_sc_output_event(
         &self,
         event_filter: EventFilter,
@@ -1,5 +1,9 @@
+mod abi;
+mod abigen;
+mod contract;
 mod deploy;
 mod deploy_sc;
+mod events;
 #[cfg(feature = "jsonrpsee")]
 mod jsonrpc;
 mod jsonrpc_common;
@@ -7,19 +11,29 @@ mod jsonrpc_common;
 mod jsonrpc_reqwest;
 
 // exports
-pub use deploy_sc::{DeployerArgs, deploy_smart_contract};
+pub use abi::{
+    ABI_SECTION, AbiEntry, AbiError, AbiEvent, AbiFunction, AbiParam, ContractAbi, custom_section,
+};
+pub use abigen::{AbigenError, abigen, abigen_from_file};
+pub use contract::{CallOptions, ContractClient, ContractError};
+pub use deploy_sc::{DeployError, DeployerArgs, deploy_smart_contract, prepare_smart_contract};
+pub use events::{
+    ContractEvent, EVENT_ARGS_SEPARATOR, EVENT_NAME_SEPARATOR, EventError, filter_events,
+};
 #[cfg(feature = "jsonrpsee")]
 pub use jsonrpc::MassaRpcClient;
 pub use jsonrpc_common::MassaJsonRpc;
 #[cfg(feature = "reqwest")]
 pub use jsonrpc_reqwest::MassaRpcClient;
+// Args encoding (shared with massa_rust_sc)
+pub use massa_args::{Args, ArgsError, I128, I256, ParseBigNumError, Serializable, U128, U256};
 // massa re exports
 pub use massa_api_exports::{
     address::AddressInfo,
     execution::{ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult},
     node::NodeStatus,
 };
-pub use massa_models::{address::Address, operation::OperationId};
+pub use massa_models::{address::Address, amount::Amount, operation::OperationId};
 pub use massa_signature::KeyPair;
 
 pub const BUILDNET_URL: &str = "https://buildnet.massa.net/api/v2";
@@ -0,0 +1,241 @@
+// std
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+// third-party
+use massa_sc_runtime::{Compiler, CondomLimits, GasCosts, RuntimeModule};
+
+/// Extension of a cached (compiled) module
+const CACHE_EXTENSION: &str = "module";
+
+/// On disk cache of compiled modules (keyed by a hash of the bytecode)
+///
+/// The compiled module is instantiated for each unit test. A cached module is named after the wasm
+/// file of the unit tests (e.g. hello_world-1a2b3c), only the last one is kept for a wasm file.
+pub(crate) struct ModuleCache {
+    dir: PathBuf,
+    /// Name of the unit tests wasm file (without extension)
+    name: String,
+    /// See build_id
+    build_id: Option<String>,
+}
+
+impl ModuleCache {
+    pub(crate) fn new(dir: PathBuf, name: String) -> Self {
+        Self {
+            dir,
+            name,
+            build_id: build_id(),
+        }
+    }
+
+    /// Cache key: hash of the runner build, of the gas costs (gas metering is compiled in the
+    /// module) & of the bytecode
+    fn key(&self, bytecode: &[u8]) -> String {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        hasher.update(self.build_id.as_deref().unwrap_or_default().as_bytes());
+        hasher.update(crate::GAS_COSTS_FILE.as_bytes());
+        hasher.update(bytecode);
+        hasher.finalize().to_hex().to_string()
+    }
+
+    fn path(&self, bytecode: &[u8]) -> PathBuf {
+        self.dir
+            .join(format!("{}-{}", self.name, self.key(bytecode)))
+            .with_extension(CACHE_EXTENSION)
+    }
+
+    /// Load a compiled module from the cache, or compile it (and store it in the cache)
+    ///
+    /// Note: a cache error (e.g. read only directory or invalid artifact) is not fatal, the module
+    ///       is compiled again
+    pub(crate) fn load(
+        &self,
+        bytecode: &[u8],
+        gas_costs: &GasCosts,
+        exec_limits: &CondomLimits,
+    ) -> Result<RuntimeModule, String> {
+        // Note: without a build id, a module compiled by another runner build could be loaded
+        if self.build_id.is_none() {
+            return compile(bytecode, gas_costs, exec_limits);
+        }
+        let path = self.path(bytecode);
+        if let Ok(serialized) = std::fs::read(&path)
+            && let Ok(module) =
+                RuntimeModule::deserialize(&serialized, gas_costs.clone(), exec_limits.clone())
+        {
+            return Ok(module);
+        }
+
+        let module = compile(bytecode, gas_costs, exec_limits)?;
+        if let Ok(serialized) = module.serialize()
+            && store(&path, &serialized).is_ok()
+        {
+            self.prune(&path);
+        }
+        Ok(module)
+    }
+
+    /// Remove the other cached modules of the wasm file (previous builds of the unit tests)
+    fn prune(&self, keep: &Path) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        let prefix = format!("{}-", self.name);
+        for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+            let is_module = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(&prefix))
+                .and_then(|name| name.strip_suffix(CACHE_EXTENSION))
+                .and_then(|key| key.strip_suffix('.'))
+                .is_some_and(|key| key.len() == 64 && key.bytes().all(|b| b.is_ascii_hexdigit()));
+            if is_module && path != keep {
+                std::fs::remove_file(&path).ok();
+            }
+        }
+    }
+}
+
+/// Build id of the runner: size & modification time of its executable
+///
+/// The compiled modules can only be loaded by the massa-sc-runtime & wasmer versions that compiled
+/// them (and the package version is not updated with them)
+fn build_id() -> Option<String> {
+    let metadata = std::env::current_exe().and_then(std::fs::metadata).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    Some(format!("{}:{}", metadata.len(), modified.as_nanos()))
+}
+
+/// Compiled modules by bytecode hash: the unit tests module & the smart contracts deployed or
+/// called by the tests (shared by all the tests, see get_module)
+pub(crate) struct ModuleRegistry {
+    gas_costs: GasCosts,
+    exec_limits: CondomLimits,
+    modules: Mutex<HashMap<blake3::Hash, RuntimeModule>>,
+}
+
+impl ModuleRegistry {
+    pub(crate) fn new(gas_costs: GasCosts, exec_limits: CondomLimits) -> Self {
+        Self {
+            gas_costs,
+            exec_limits,
+            modules: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn gas_costs(&self) -> &GasCosts {
+        &self.gas_costs
+    }
+
+    pub(crate) fn exec_limits(&self) -> &CondomLimits {
+        &self.exec_limits
+    }
+
+    fn modules(&self) -> MutexGuard<'_, HashMap<blake3::Hash, RuntimeModule>> {
+        // Note: the map is never left in an inconsistent state (poisoned lock can be ignored)
+        self.modules.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Register an already compiled module
+    pub(crate) fn insert(&self, bytecode: &[u8], module: RuntimeModule) {
+        self.modules().insert(blake3::hash(bytecode), module);
+    }
+
+    /// Return the compiled module of a bytecode (compiled on first use)
+    pub(crate) fn get(&self, bytecode: &[u8]) -> Result<RuntimeModule, String> {
+        let hash = blake3::hash(bytecode);
+        if let Some(module) = self.modules().get(&hash) {
+            return Ok(module.clone());
+        }
+        // Note: the lock is not held while compiling (a module can be compiled twice)
+        let module = compile(bytecode, &self.gas_costs, &self.exec_limits)?;
+        self.modules().insert(hash, module.clone());
+        Ok(module)
+    }
+}
+
+/// Compile a module (without cache)
+///
+/// All the modules are compiled with Cranelift (as the modules cached by a Massa node), so a test
+/// behaves the same with or without the cache
+pub(crate) fn compile(
+    bytecode: &[u8],
+    gas_costs: &GasCosts,
+    exec_limits: &CondomLimits,
+) -> Result<RuntimeModule, String> {
+    RuntimeModule::new(
+        bytecode,
+        gas_costs.clone(),
+        Compiler::CL,
+        exec_limits.clone(),
+    )
+    .map_err(|e| format!("Cannot compile wasm module: {e}"))
+}
+
+/// Write a cached module (the file is renamed once written so concurrent runs never read a partial
+/// artifact)
+fn store(path: &Path, serialized: &[u8]) -> std::io::Result<()> {
+    // Unwrap safe: path is always built from the cache dir
+    let dir = path.parent().unwrap();
+    std::fs::create_dir_all(dir)?;
+    let temp_file = tempfile::NamedTempFile::new_in(dir)?;
+    std::fs::write(temp_file.path(), serialized)?;
+    temp_file.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_key() {
+        let cache = ModuleCache::new(PathBuf::from("cache"), "tests-1a2b".to_string());
+        assert!(cache.build_id.is_some());
+        let path = cache.path(b"\0asm");
+        assert_eq!(path.parent(), Some(Path::new("cache")));
+        assert_eq!(path.extension().unwrap(), CACHE_EXTENSION);
+        assert!(
+            path.file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("tests-1a2b-")
+        );
+        assert_eq!(path, cache.path(b"\0asm"));
+        assert_ne!(path, cache.path(b"\0asm\x01"));
+
+        // Another runner build
+        let other = ModuleCache {
+            build_id: Some("other".to_string()),
+            ..ModuleCache::new(PathBuf::from("cache"), "tests-1a2b".to_string())
+        };
+        assert_ne!(path, other.path(b"\0asm"));
+    }
+
+    #[test]
+    fn test_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ModuleCache::new(dir.path().to_path_buf(), "tests-1a2b".to_string());
+        let previous = cache.path(b"\0asm");
+        let other = ModuleCache::new(dir.path().to_path_buf(), "other-1a2b".to_string());
+        let other = other.path(b"\0asm");
+        let unrelated = dir.path().join("tests-1a2b-readme.txt");
+        for path in [&previous, &other, &unrelated] {
+            std::fs::write(path, b"module").unwrap();
+        }
+
+        let path = cache.path(b"\0asm\x01");
+        store(&path, b"module").unwrap();
+        cache.prune(&path);
+        assert!(path.exists());
+        assert!(!previous.exists());
+        assert!(other.exists());
+        assert!(unrelated.exists());
+    }
+}
@@ -0,0 +1,285 @@
+//! Cheatcodes: change the context of a unit test (caller, balances, time, ...), see the testing
+//! module of massa_rust_sc
+//!
+//! The unit tests import the cheatcode function from the massa_test module, massa-sc-runtime only
+//! provides the Massa ABI so this import is redirected to assembly_script_get_data_for (same
+//! signature) before compiling the unit tests module. A cheatcode is then a get_data_for call on
+//! the address CHEATCODE_PREFIX + cheatcode name, the key being the cheatcode arguments (only for
+//! the unit tests importing the cheatcode function, see imports_cheatcode).
+
+// third-party
+use massa_wasm_tools::{ImportRename, MASSA_MODULE, WasmToolsError, imported_functions};
+// internal
+use crate::context::{ExecutionContext, ExecutionError, check_address};
+
+/// Redirection of the cheatcode import (see rename_imports)
+pub(crate) const CHEATCODE_IMPORT: ImportRename = (
+    ("massa_test", "cheatcode"),
+    (MASSA_MODULE, "assembly_script_get_data_for"),
+);
+
+/// Return true if the unit tests import the cheatcode function (massa_rust_sc testing feature)
+///
+/// Note: the cheatcodes are only run for these unit tests, otherwise get_data_for behaves as in a
+///       Massa node
+pub(crate) fn imports_cheatcode(bytecode: &[u8]) -> Result<bool, WasmToolsError> {
+    let (module, name) = CHEATCODE_IMPORT.0;
+    Ok(imported_functions(bytecode)?
+        .iter()
+        .any(|import| import.0 == module && import.1 == name))
+}
+
+/// Prefix of the cheatcode names (not a valid address)
+pub(crate) const CHEATCODE_PREFIX: &str = "massa_test:";
+
+/// Cheatcode calling a smart contract function, run by the interface (see
+/// MassaScRunnerInterface::try_call) as it executes a module
+pub(crate) const TRY_CALL_CHEATCODE: &str = "try_call";
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CheatcodeError {
+    #[error("unknown cheatcode: {0}")]
+    Unknown(String),
+    #[error("invalid arguments for cheatcode: {0}")]
+    InvalidArgs(String),
+    #[error(transparent)]
+    Execution(#[from] ExecutionError),
+}
+
+/// Arguments of a cheatcode (serialized with massa_rust_sc::Args)
+struct Args<'a> {
+    buffer: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Args<'a> {
+    fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer, offset: 0 }
+    }
+
+    fn next_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .buffer
+            .get(self.offset..self.offset.checked_add(len)?)?;
+        self.offset += len;
+        Some(bytes)
+    }
+
+    fn next_u8(&mut self) -> Option<u8> {
+        self.next_bytes(1).map(|bytes| bytes[0])
+    }
+
+    fn next_u64(&mut self) -> Option<u64> {
+        // Unwrap safe: next_bytes returns exactly 8 bytes
+        self.next_bytes(8)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Length (u32) + bytes
+    fn next_byte_array(&mut self) -> Option<&'a [u8]> {
+        // Unwrap safe: next_bytes returns exactly 4 bytes
+        let len = u32::from_le_bytes(self.next_bytes(4)?.try_into().unwrap());
+        self.next_bytes(len as usize)
+    }
+
+    /// Length (u32) + utf-8 bytes
+    fn next_string(&mut self) -> Option<String> {
+        String::from_utf8(self.next_byte_array()?.to_vec()).ok()
+    }
+}
+
+/// Arguments of the try_call cheatcode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TryCall {
+    pub address: String,
+    pub function: String,
+    /// Arguments of the function
+    pub args: Vec<u8>,
+    pub coins: u64,
+}
+
+impl TryCall {
+    pub(crate) fn parse(args: &[u8]) -> Result<Self, CheatcodeError> {
+        let mut args = Args::new(args);
+        let mut parse = || {
+            Some(Self {
+                address: args.next_string()?,
+                function: args.next_string()?,
+                args: args.next_byte_array()?.to_vec(),
+                coins: args.next_u64()?,
+            })
+        };
+        parse().ok_or_else(|| CheatcodeError::InvalidArgs(TRY_CALL_CHEATCODE.to_string()))
+    }
+
+    /// Serialize the result of the call: success (bool) followed by the value returned by the
+    /// function (bytes) or the error message (string)
+    pub(crate) fn result(res: Result<Vec<u8>, String>) -> Vec<u8> {
+        let (success, bytes) = match res {
+            Ok(value) => (true, value),
+            Err(message) => (false, message.into_bytes()),
+        };
+        let mut res = vec![success as u8];
+        res.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        res.extend_from_slice(&bytes);
+        res
+    }
+}
+
+/// Run a cheatcode, return its (serialized) result
+pub(crate) fn run_cheatcode(
+    ctx: &mut ExecutionContext,
+    name: &str,
+    args: &[u8],
+) -> Result<Vec<u8>, CheatcodeError> {
+    let mut args = Args::new(args);
+    let invalid_args = || CheatcodeError::InvalidArgs(name.to_string());
+    match name {
+        "set_caller" => ctx.set_caller(&args.next_string().ok_or_else(invalid_args)?)?,
+        "set_callee" => ctx.set_callee(&args.next_string().ok_or_else(invalid_args)?)?,
+        "set_call_coins" => ctx.set_call_coins(args.next_u64().ok_or_else(invalid_args)?)?,
+        "set_balance" => {
+            let address = args.next_string().ok_or_else(invalid_args)?;
+            let amount = args.next_u64().ok_or_else(invalid_args)?;
+            check_address(&address)?;
+            ctx.ledger.set_balance(&address, amount);
+        }
+        "set_time" => ctx.set_time(args.next_u64().ok_or_else(invalid_args)?),
+        "warp_to_slot" => {
+            let period = args.next_u64().ok_or_else(invalid_args)?;
+            let thread = args.next_u8().ok_or_else(invalid_args)?;
+            ctx.warp_to_slot(period, thread)?;
+        }
+        "set_chain_id" => ctx.set_chain_id(args.next_u64().ok_or_else(invalid_args)?),
+        "expect_revert" => {
+            ctx.expected_revert = Some(args.next_string().ok_or_else(invalid_args)?);
+        }
+        "events" => {
+            // Count (u32) + events (strings)
+            let events = ctx.test_events();
+            let mut res = (events.len() as u32).to_le_bytes().to_vec();
+            for event in events {
+                res.extend_from_slice(&(event.len() as u32).to_le_bytes());
+                res.extend_from_slice(event.as_bytes());
+            }
+            return Ok(res);
+        }
+        "clear_events" => ctx.clear_events(),
+        "snapshot" => return Ok(ctx.snapshot().to_le_bytes().to_vec()),
+        "revert_to" => ctx.revert_to(args.next_u64().ok_or_else(invalid_args)?)?,
+        _ => return Err(CheatcodeError::Unknown(name.to_string())),
+    }
+    Ok(vec![])
+}
+
+/// Result of a test after an expect_revert cheatcode: the test function must revert with a
+/// message containing the expected message (any message if empty)
+pub(crate) fn check_revert(res: Result<(), String>, expected: &str) -> Result<(), String> {
+    match res {
+        Ok(()) => Err(format!(
+            "note: test did not revert as expected (expect_revert: {expected:?})"
+        )),
+        Err(msg) if !msg.contains(expected) => Err(format!(
+            "note: revert did not contain expected string\n     revert message: {msg:?}\n expected substring: {expected:?}"
+        )),
+        Err(_) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{CALLER_ADDRESS, CONTRACT_ADDRESS};
+
+    fn string_arg(s: &str) -> Vec<u8> {
+        let mut arg = (s.len() as u32).to_le_bytes().to_vec();
+        arg.extend_from_slice(s.as_bytes());
+        arg
+    }
+
+    #[test]
+    fn test_run_cheatcode() {
+        let mut ctx = ExecutionContext::new(b"\0asm\x01\0\0\0", 0).unwrap();
+
+        let mut args = string_arg(CALLER_ADDRESS);
+        args.extend_from_slice(&42u64.to_le_bytes());
+        run_cheatcode(&mut ctx, "set_balance", &args).unwrap();
+        assert_eq!(ctx.ledger.balance(CALLER_ADDRESS), 42);
+
+        let mut args = 7u64.to_le_bytes().to_vec();
+        args.push(4);
+        run_cheatcode(&mut ctx, "warp_to_slot", &args).unwrap();
+        assert_eq!(ctx.slot(), (7, 4));
+
+        ctx.events.push("Paused:".to_string());
+        let mut events = 1u32.to_le_bytes().to_vec();
+        events.extend_from_slice(&string_arg("Paused:"));
+        assert_eq!(run_cheatcode(&mut ctx, "events", &[]).unwrap(), events);
+        run_cheatcode(&mut ctx, "clear_events", &[]).unwrap();
+        assert_eq!(run_cheatcode(&mut ctx, "events", &[]).unwrap(), [0; 4]);
+
+        let snapshot = run_cheatcode(&mut ctx, "snapshot", &[]).unwrap();
+        assert_eq!(snapshot, 0u64.to_le_bytes());
+        run_cheatcode(&mut ctx, "expect_revert", &string_arg("Unauthorized")).unwrap();
+        assert_eq!(ctx.expected_revert.as_deref(), Some("Unauthorized"));
+
+        assert_eq!(
+            run_cheatcode(&mut ctx, "set_caller", &[1, 0]),
+            Err(CheatcodeError::InvalidArgs("set_caller".to_string()))
+        );
+        assert_eq!(
+            run_cheatcode(&mut ctx, "set_callee", &string_arg(CALLER_ADDRESS)),
+            Err(CheatcodeError::Execution(
+                ExecutionError::NotASmartContract {
+                    address: CALLER_ADDRESS.to_string()
+                }
+            ))
+        );
+        let mut args = string_arg("AU12");
+        args.extend_from_slice(&42u64.to_le_bytes());
+        assert_eq!(
+            run_cheatcode(&mut ctx, "set_balance", &args),
+            Err(CheatcodeError::Execution(ExecutionError::AddressParse))
+        );
+        assert_eq!(ctx.ledger.balance("AU12"), 0);
+        assert_eq!(
+            run_cheatcode(&mut ctx, "prank", &[]),
+            Err(CheatcodeError::Unknown("prank".to_string()))
+        );
+        assert_eq!(ctx.current_address(), CONTRACT_ADDRESS);
+    }
+
+    #[test]
+    fn test_try_call() {
+        let mut args = string_arg(CONTRACT_ADDRESS);
+        args.extend_from_slice(&string_arg("fail"));
+        args.extend_from_slice(&string_arg("\x01"));
+        args.extend_from_slice(&5u64.to_le_bytes());
+        assert_eq!(
+            TryCall::parse(&args),
+            Ok(TryCall {
+                address: CONTRACT_ADDRESS.to_string(),
+                function: "fail".to_string(),
+                args: vec![1],
+                coins: 5,
+            })
+        );
+        assert_eq!(
+            TryCall::parse(&args[..args.len() - 1]),
+            Err(CheatcodeError::InvalidArgs("try_call".to_string()))
+        );
+
+        assert_eq!(TryCall::result(Ok(vec![7])), [1, 1, 0, 0, 0, 7]);
+        let mut res = vec![0];
+        res.extend_from_slice(&string_arg("abort"));
+        assert_eq!(TryCall::result(Err("abort".to_string())), res);
+    }
+
+    #[test]
+    fn test_check_revert() {
+        assert!(check_revert(Ok(()), "").is_err());
+        assert!(check_revert(Err("abort: Unauthorized".to_string()), "").is_ok());
+        assert!(check_revert(Err("abort: Unauthorized".to_string()), "Unauthorized").is_ok());
+        assert!(check_revert(Err("abort: Paused".to_string()), "Unauthorized").is_err());
+    }
+}
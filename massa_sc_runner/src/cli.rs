@@ -0,0 +1,252 @@
+// std
+use std::path::{Path, PathBuf};
+// third-party
+use clap::{Parser, ValueEnum};
+
+/// Default directory of the compiled modules cache (next to the wasm file)
+const CACHE_DIR: &str = "massa_sc_runner_cache";
+
+/// Command line arguments (a subset of the libtest arguments, so massa_sc_runner can be used as
+/// a cargo runner, see .cargo/config.toml)
+#[derive(Debug, Parser)]
+#[command(about = "Run the unit tests (wasm) of a Rust smart contract", long_about = None)]
+pub(crate) struct Cli {
+    /// Wasm file of the unit tests (e.g. target/wasm32-unknown-unknown/debug/deps/hello_world-XXXXX.wasm)
+    pub wasm_file: PathBuf,
+    /// Only run the tests whose name contains one of the filters
+    pub filters: Vec<String>,
+    /// Exactly match the filters (and the skip filters) instead of a substring match
+    #[arg(long)]
+    pub exact: bool,
+    /// Skip the tests whose name contains the filter (can be repeated)
+    #[arg(long = "skip")]
+    pub skip: Vec<String>,
+    /// List the tests instead of running them
+    #[arg(long)]
+    pub list: bool,
+    /// Only run the ignored tests
+    #[arg(long, conflicts_with = "include_ignored")]
+    pub ignored: bool,
+    /// Run the ignored tests too
+    #[arg(long)]
+    pub include_ignored: bool,
+    /// Print the output of the tests (print, events, ...) instead of capturing it
+    #[arg(long)]
+    pub nocapture: bool,
+    /// Write a JUnit XML report to the given file
+    #[arg(long)]
+    pub junit: Option<PathBuf>,
+    /// Number of threads used to run the tests (default: RUST_TEST_THREADS or the number of CPUs)
+    #[arg(long)]
+    pub test_threads: Option<usize>,
+    /// Directory of the compiled modules cache (default: massa_sc_runner_cache next to the wasm file)
+    #[arg(long)]
+    pub cache_dir: Option<PathBuf>,
+    /// Always compile the wasm module (do not use the compiled modules cache)
+    #[arg(long)]
+    pub no_cache: bool,
+    /// Coins (in nanoMAS) sent by the caller with the call of each test (see get_call_coins)
+    #[arg(long, default_value_t = 0)]
+    pub call_coins: u64,
+    /// Seed of unsafe_random & unsafe_random_f64 (each test starts with this seed)
+    #[arg(long, default_value_t = 0)]
+    pub random_seed: u64,
+    /// Print the gas consumed by each test
+    #[arg(long)]
+    pub gas_report: bool,
+    /// Compare the gas consumed by each test against a baseline file (json: test name -> gas)
+    #[arg(long)]
+    pub gas_baseline: Option<PathBuf>,
+    /// Write the gas consumed by each test to a baseline file
+    #[arg(long)]
+    pub save_gas_baseline: Option<PathBuf>,
+    /// Allowed gas increase (in percent) over the baseline
+    #[arg(long, default_value_t = 5.0)]
+    pub gas_threshold: f64,
+    /// What to do when the gas increase of a test is over the threshold
+    #[arg(long, value_enum, default_value_t = GasRegressionMode::Fail)]
+    pub gas_regression: GasRegressionMode,
+    /// Gas limit of each test
+    #[arg(long, default_value_t = u64::MAX)]
+    pub gas_limit: u64,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Pretty)]
+    pub format: Format,
+    /// Same as --format terse
+    #[arg(short, long)]
+    pub quiet: bool,
+    // Accepted for compatibility with libtest (no effect)
+    #[arg(short = 'Z', hide = true)]
+    _unstable_options: Vec<String>,
+    #[arg(long = "color", hide = true)]
+    _color: Option<String>,
+    #[arg(long = "show-output", hide = true)]
+    _show_output: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Format {
+    /// One line per test
+    Pretty,
+    /// One character per test
+    Terse,
+    /// One json object per line (libtest json format + gas & events)
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum GasRegressionMode {
+    /// Exit with an error (as a failed test)
+    Fail,
+    /// Print a warning only
+    Warn,
+}
+
+impl Cli {
+    pub(crate) fn format(&self) -> Format {
+        if self.quiet {
+            Format::Terse
+        } else {
+            self.format
+        }
+    }
+
+    fn matches(&self, name: &str, filter: &str) -> bool {
+        if self.exact {
+            name == filter
+        } else {
+            name.contains(filter)
+        }
+    }
+
+    /// Return true if the test is selected by the filters (and not skipped)
+    pub(crate) fn is_selected(&self, name: &str) -> bool {
+        let filtered =
+            self.filters.is_empty() || self.filters.iter().any(|f| self.matches(name, f));
+        filtered && !self.skip.iter().any(|f| self.matches(name, f))
+    }
+
+    /// Number of threads used to run the tests
+    pub(crate) fn test_threads(&self) -> usize {
+        self.test_threads
+            .or_else(|| {
+                std::env::var("RUST_TEST_THREADS")
+                    .ok()
+                    .and_then(|n| n.parse().ok())
+            })
+            .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+            .unwrap_or(1)
+            .max(1)
+    }
+
+    /// Directory of the compiled modules cache (None if the cache is disabled)
+    pub(crate) fn cache_dir(&self) -> Option<PathBuf> {
+        if self.no_cache {
+            return None;
+        }
+        self.cache_dir.clone().or_else(|| {
+            let dir = self.wasm_file.parent().unwrap_or(Path::new("."));
+            Some(dir.join(CACHE_DIR))
+        })
+    }
+
+    /// Return true if an ignored test should be run
+    pub(crate) fn run_ignored(&self) -> bool {
+        self.ignored || self.include_ignored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_args() {
+        // cargo test --target wasm32-unknown-unknown -- hello --skip hello_2 --nocapture
+        let cli = Cli::parse_from([
+            "massa_sc_runner",
+            "hello_world-1a2b.wasm",
+            "hello",
+            "--skip",
+            "hello_2",
+            "--nocapture",
+        ]);
+        assert!(cli.nocapture);
+        assert!(cli.is_selected("test_hello_1"));
+        assert!(!cli.is_selected("test_hello_2"));
+        assert!(!cli.is_selected("test_greet"));
+        assert_eq!(cli.format(), Format::Pretty);
+
+        let cli = Cli::parse_from([
+            "massa_sc_runner",
+            "hello_world-1a2b.wasm",
+            "test_hello",
+            "--exact",
+            "-q",
+            "-Z",
+            "unstable-options",
+        ]);
+        assert!(!cli.is_selected("test_hello_1"));
+        assert!(cli.is_selected("test_hello"));
+        assert_eq!(cli.format(), Format::Terse);
+        assert!(!cli.run_ignored());
+
+        let cli = Cli::parse_from([
+            "massa_sc_runner",
+            "target/debug/deps/hello_world-1a2b.wasm",
+            "--test-threads",
+            "1",
+            "--call-coins",
+            "1000000000",
+        ]);
+        assert_eq!(cli.test_threads(), 1);
+        assert_eq!(cli.call_coins, 1_000_000_000);
+        assert_eq!(
+            cli.cache_dir(),
+            Some(PathBuf::from("target/debug/deps").join(CACHE_DIR))
+        );
+        let cli = Cli::parse_from(["massa_sc_runner", "a.wasm", "--no-cache"]);
+        assert_eq!(cli.cache_dir(), None);
+
+        let cli = Cli::parse_from([
+            "massa_sc_runner",
+            "a.wasm",
+            "--format",
+            "json",
+            "--junit",
+            "report.xml",
+        ]);
+        assert_eq!(cli.format(), Format::Json);
+        assert_eq!(cli.junit, Some(PathBuf::from("report.xml")));
+        assert!(!cli.gas_report);
+        assert_eq!(cli.gas_threshold, 5.0);
+        assert_eq!(cli.gas_regression, GasRegressionMode::Fail);
+        assert_eq!(cli.cache_dir(), Some(PathBuf::from(CACHE_DIR)));
+
+        let cli = Cli::parse_from([
+            "massa_sc_runner",
+            "a.wasm",
+            "--gas-report",
+            "--gas-baseline",
+            "gas_baseline.json",
+            "--gas-threshold",
+            "2.5",
+            "--gas-regression",
+            "warn",
+        ]);
+        assert!(cli.gas_report);
+        assert_eq!(cli.gas_baseline, Some(PathBuf::from("gas_baseline.json")));
+        assert_eq!(cli.gas_threshold, 2.5);
+        assert_eq!(cli.gas_regression, GasRegressionMode::Warn);
+
+        assert!(
+            Cli::try_parse_from([
+                "massa_sc_runner",
+                "a.wasm",
+                "--ignored",
+                "--include-ignored"
+            ])
+            .is_err()
+        );
+    }
+}
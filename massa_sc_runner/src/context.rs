@@ -0,0 +1,772 @@
+// std
+use std::fmt;
+// internal
+use crate::ledger::{DEFAULT_BALANCE, Ledger, LedgerError, format_amount};
+
+/// Address of the caller of the unit tests
+pub(crate) const CALLER_ADDRESS: &str = "AU1Yvq49utdezr496dHbRj3TMjqsCh2awggjfGraHoddE7XfEkpY";
+/// Address of the smart contract (the unit tests module)
+pub(crate) const CONTRACT_ADDRESS: &str = "AS1GFocKuZKiSr2Gcu8y69fraPEqs7xYWvEFikTKcThYC38sBFBH";
+
+/// Max depth of nested calls (see MAX_RECURSIVE_CALLS_DEPTH in massa node)
+pub(crate) const MAX_RECURSIVE_CALLS_DEPTH: u16 = 25;
+
+/// Size of the call stack of a unit test: caller & smart contract (never popped)
+const TEST_CALL_STACK_SIZE: usize = 2;
+
+/// Timestamp of the genesis of the Massa mainnet (in ms), time of the slot (0, 0)
+const GENESIS_TIMESTAMP: u64 = 1_705_312_800_000;
+/// Duration of a period (in ms, see T0 in massa node)
+const T0: u64 = 16_000;
+/// Number of threads (see THREAD_COUNT in massa node)
+const THREAD_COUNT: u8 = 32;
+/// Chain id of the Massa mainnet (see CHAINID in massa node)
+const MAINNET_CHAIN_ID: u64 = 77658377;
+
+/// Pseudo random number generator of unsafe_random & unsafe_random_f64 (SplitMix64)
+///
+/// Deterministic: every test starts with the same seed (see --random-seed), so a test using
+/// unsafe_random is reproducible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UnsafeRandom {
+    state: u64,
+}
+
+impl UnsafeRandom {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_i64(&mut self) -> i64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        (z ^ (z >> 31)) as i64
+    }
+
+    /// A number in [0, 1)
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        // Use the 53 high bits (f64 mantissa + 1)
+        ((self.next_i64() as u64) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Execution errors (same messages as the execution context & the interface of a Massa node)
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ExecutionError {
+    #[error(transparent)]
+    Ledger(#[from] LedgerError),
+    #[error("Runtime error: spending from address {address} is not allowed in this context")]
+    SpendingNotAllowed { address: String },
+    #[error("Runtime error: {action} address {address} is not allowed in this context")]
+    WriteNotAllowed {
+        action: WriteAction,
+        address: String,
+    },
+    #[error(
+        "Runtime error: can't set the bytecode of address {address} because this is not a smart contract address"
+    )]
+    BytecodeOfUserAddress { address: String },
+    #[error("called address {address} is not an SC address")]
+    NotASmartContract { address: String },
+    #[error("bytecode not found for address {address}")]
+    BytecodeNotFound { address: String },
+    #[error(
+        "error transferring {} coins from {from} to {to}: {error}",
+        format_amount(.coins)
+    )]
+    CallTransfer {
+        coins: u64,
+        from: String,
+        to: String,
+        error: LedgerError,
+    },
+    #[error("data entry not found")]
+    DataEntryNotFound,
+    #[error("bytecode not found")]
+    BytecodeEntryNotFound,
+    #[error("address parsing error")]
+    AddressParse,
+    #[error("call stack out of bounds")]
+    CallStackOutOfBounds,
+    #[error("recursion depth limit reached")]
+    RecursionLimit,
+    /// Invalid bytecode of a called or deployed smart contract
+    #[error("{0}")]
+    Module(String),
+    #[error("the call stack of the unit test cannot be changed in a nested call")]
+    NestedCall,
+    #[error("invalid slot: ({period}, {thread})")]
+    InvalidSlot { period: u64, thread: u8 },
+    #[error("snapshot {id} not found")]
+    SnapshotNotFound { id: u64 },
+    /// Host function not implemented by the runner: fails the unit test calling it
+    #[error("{function} not supported by massa_sc_runner")]
+    NotSupported { function: &'static str },
+}
+
+/// Write access to an address (see check_write_rights)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WriteAction {
+    SetData,
+    AppendData,
+    DeleteData,
+    SetBytecode,
+}
+
+impl fmt::Display for WriteAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SetData => write!(f, "writing in the datastore of"),
+            Self::AppendData => write!(f, "appending to the datastore of"),
+            Self::DeleteData => write!(f, "deleting from the datastore of"),
+            Self::SetBytecode => write!(f, "setting the bytecode of"),
+        }
+    }
+}
+
+/// Check that a string is a valid Massa address: AU (user) or AS (smart contract) followed by
+/// the base58check encoding of the version (0) & a 32 bytes hash
+pub(crate) fn check_address(address: &str) -> Result<(), ExecutionError> {
+    let data = address
+        .strip_prefix("AU")
+        .or_else(|| address.strip_prefix("AS"))
+        .and_then(|data| bs58::decode(data).with_check(None).into_vec().ok());
+    match data.as_deref() {
+        Some([0, hash @ ..]) if hash.len() == 32 => Ok(()),
+        _ => Err(ExecutionError::AddressParse),
+    }
+}
+
+/// An element of the call stack
+#[derive(Debug, Clone)]
+struct StackElement {
+    address: String,
+    /// Coins sent with the call
+    coins: u64,
+    /// Addresses the call can write to (the called address & the smart contracts it created)
+    owned_addresses: Vec<String>,
+    /// State before the call (restored if the call fails), None for the unit test call stack
+    snapshot: Option<CallSnapshot>,
+}
+
+/// Ledger & events before a call
+#[derive(Debug, Clone)]
+struct CallSnapshot {
+    ledger: Ledger,
+    /// Number of events
+    events: usize,
+    cleared_events: usize,
+}
+
+/// Depth of the call stack before a call (the calls started after it can be rolled back)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CallDepth {
+    stack_len: usize,
+    recursion_counter: u16,
+}
+
+impl StackElement {
+    /// Bottom of the call stack: the caller of the unit test (CALLER_ADDRESS deployed the smart
+    /// contract so it owns it)
+    fn caller(address: &str, callee: &str) -> Self {
+        let mut owned_addresses = vec![address.to_string()];
+        if address == CALLER_ADDRESS {
+            owned_addresses.push(callee.to_string());
+        }
+        Self {
+            address: address.to_string(),
+            coins: 0,
+            owned_addresses,
+            snapshot: None,
+        }
+    }
+
+    /// The smart contract called by the unit test
+    fn callee(address: &str, coins: u64) -> Self {
+        Self {
+            address: address.to_string(),
+            coins,
+            owned_addresses: vec![address.to_string()],
+            snapshot: None,
+        }
+    }
+}
+
+/// World state of a unit test: ledger, call stack, events & time
+///
+/// A unit test is a call from CALLER_ADDRESS to CONTRACT_ADDRESS, the smart contracts created or
+/// called by the test are executed with nested calls (see init_call & finish_call).
+#[derive(Debug, Clone)]
+pub(crate) struct ExecutionContext {
+    pub ledger: Ledger,
+    stack: Vec<StackElement>,
+    recursion_counter: u16,
+    /// Number of smart contracts created (used to generate the addresses)
+    created_addresses: u64,
+    pub events: Vec<String>,
+    /// Number of events cleared by the clear_events cheatcode (the first events)
+    cleared_events: usize,
+    /// Current slot (period, thread) & time (in ms)
+    slot: (u64, u8),
+    time: u64,
+    chain_id: u64,
+    /// Ledgers saved by the snapshot cheatcode (index: snapshot id)
+    snapshots: Vec<Ledger>,
+    /// Message of the revert expected by the expect_revert cheatcode (the test must revert)
+    pub expected_revert: Option<String>,
+    pub random: UnsafeRandom,
+}
+
+impl ExecutionContext {
+    /// Context of a unit test call: the caller & the smart contract (with the unit tests bytecode)
+    /// start with DEFAULT_BALANCE, the call coins are then transferred to the smart contract
+    pub(crate) fn new(bytecode: &[u8], call_coins: u64) -> Result<Self, ExecutionError> {
+        let mut ledger = Ledger::default();
+        ledger.set_balance(CALLER_ADDRESS, DEFAULT_BALANCE);
+        ledger.deploy(CONTRACT_ADDRESS, bytecode)?;
+        ledger.transfer(Some(CALLER_ADDRESS), Some(CONTRACT_ADDRESS), call_coins)?;
+
+        // Note: the caller owns the smart contract (as if it was deployed by the caller), so
+        //       caller_has_write_access is true in the unit tests (see set_caller)
+        let stack = vec![
+            StackElement::caller(CALLER_ADDRESS, CONTRACT_ADDRESS),
+            StackElement::callee(CONTRACT_ADDRESS, call_coins),
+        ];
+        Ok(Self {
+            ledger,
+            stack,
+            recursion_counter: 0,
+            created_addresses: 0,
+            events: vec![],
+            cleared_events: 0,
+            slot: (0, 0),
+            time: GENESIS_TIMESTAMP,
+            chain_id: MAINNET_CHAIN_ID,
+            snapshots: vec![],
+            expected_revert: None,
+            random: UnsafeRandom::new(0),
+        })
+    }
+
+    fn current(&self) -> &StackElement {
+        // Unwrap safe: the unit test call stack is never popped (see finish_call)
+        self.stack.last().unwrap()
+    }
+
+    /// Address being executed (top of the call stack)
+    pub(crate) fn current_address(&self) -> &str {
+        &self.current().address
+    }
+
+    /// Coins sent with the current call
+    pub(crate) fn call_coins(&self) -> u64 {
+        self.current().coins
+    }
+
+    /// Addresses from the bottom to the top of the call stack
+    pub(crate) fn call_stack(&self) -> Vec<String> {
+        self.stack.iter().map(|e| e.address.clone()).collect()
+    }
+
+    pub(crate) fn owned_addresses(&self) -> Vec<String> {
+        self.current().owned_addresses.clone()
+    }
+
+    /// Return true if the caller can write to the current address (e.g. the creator of a smart
+    /// contract)
+    pub(crate) fn caller_has_write_access(&self) -> bool {
+        let current = self.current_address();
+        self.stack
+            .iter()
+            .rev()
+            .nth(1)
+            .is_some_and(|caller| caller.owned_addresses.iter().any(|a| a == current))
+    }
+
+    fn has_write_rights_on(&self, address: &str) -> bool {
+        self.current().owned_addresses.iter().any(|a| a == address)
+    }
+
+    /// Transfer coins from an address owned by the current call
+    pub(crate) fn transfer_coins(
+        &mut self,
+        from: &str,
+        to: &str,
+        amount: u64,
+    ) -> Result<(), ExecutionError> {
+        if !self.has_write_rights_on(from) {
+            return Err(ExecutionError::SpendingNotAllowed {
+                address: from.to_string(),
+            });
+        }
+        Ok(self.ledger.transfer(Some(from), Some(to), amount)?)
+    }
+
+    /// Check that the current call can write to an address (datastore, bytecode)
+    pub(crate) fn check_write_rights(
+        &self,
+        address: &str,
+        action: WriteAction,
+    ) -> Result<(), ExecutionError> {
+        if !self.has_write_rights_on(address) {
+            return Err(ExecutionError::WriteNotAllowed {
+                action,
+                address: address.to_string(),
+            });
+        }
+        if action == WriteAction::SetBytecode && !address.starts_with("AS") {
+            return Err(ExecutionError::BytecodeOfUserAddress {
+                address: address.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Create a smart contract (owned by the current call), return its address
+    pub(crate) fn create_sc(&mut self, bytecode: &[u8]) -> Result<String, ExecutionError> {
+        let creator = self.current_address().to_string();
+        self.created_addresses += 1;
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(creator.as_bytes());
+        hasher.update(&self.created_addresses.to_be_bytes());
+        // Version (0) + hash, as a Massa address (e.g. AS12...)
+        let mut data = vec![0];
+        data.extend_from_slice(hasher.finalize().as_bytes());
+        let address = format!("AS{}", bs58::encode(data).with_check().into_string());
+
+        self.ledger.create_sc_entry(&creator, &address, bytecode)?;
+        // Unwrap safe: the unit test call stack is never popped (see finish_call)
+        self.stack
+            .last_mut()
+            .unwrap()
+            .owned_addresses
+            .push(address.clone());
+        Ok(address)
+    }
+
+    /// Start a call to a smart contract: transfer the coins & push the call stack, return the
+    /// bytecode to execute
+    pub(crate) fn init_call(
+        &mut self,
+        address: &str,
+        coins: u64,
+    ) -> Result<Vec<u8>, ExecutionError> {
+        if !address.starts_with("AS") {
+            return Err(ExecutionError::NotASmartContract {
+                address: address.to_string(),
+            });
+        }
+        // Note: like a Massa node, only a missing address is rejected here, an empty bytecode
+        //       fails later (when the module is compiled)
+        let Some(bytecode) = self.ledger.bytecode(address).map(<[u8]>::to_vec) else {
+            return Err(ExecutionError::BytecodeNotFound {
+                address: address.to_string(),
+            });
+        };
+        let snapshot = CallSnapshot {
+            ledger: self.ledger.clone(),
+            events: self.events.len(),
+            cleared_events: self.cleared_events,
+        };
+        let from = self.current_address().to_string();
+        self.ledger
+            .transfer(Some(&from), Some(address), coins)
+            .map_err(|error| ExecutionError::CallTransfer {
+                coins,
+                from,
+                to: address.to_string(),
+                error,
+            })?;
+        self.stack.push(StackElement {
+            address: address.to_string(),
+            coins,
+            owned_addresses: vec![address.to_string()],
+            snapshot: Some(snapshot),
+        });
+        Ok(bytecode)
+    }
+
+    /// End a (successful) call to a smart contract
+    pub(crate) fn finish_call(&mut self) -> Result<(), ExecutionError> {
+        if self.stack.len() <= TEST_CALL_STACK_SIZE {
+            return Err(ExecutionError::CallStackOutOfBounds);
+        }
+        self.stack.pop();
+        Ok(())
+    }
+
+    /// Roll back the calls that did not finish (failed): the ledger & the events are restored
+    /// to their state before the outermost of these calls
+    pub(crate) fn rollback_calls(&mut self) {
+        self.rollback_calls_to(CallDepth {
+            stack_len: TEST_CALL_STACK_SIZE,
+            recursion_counter: 0,
+        });
+    }
+
+    /// Current depth of the call stack (see rollback_calls_to)
+    pub(crate) fn call_depth(&self) -> CallDepth {
+        CallDepth {
+            stack_len: self.stack.len(),
+            recursion_counter: self.recursion_counter,
+        }
+    }
+
+    /// Roll back the calls started after `depth` that did not finish (see rollback_calls)
+    pub(crate) fn rollback_calls_to(&mut self, depth: CallDepth) {
+        while self.stack.len() > depth.stack_len.max(TEST_CALL_STACK_SIZE) {
+            // Unwrap safe: stack.len() > TEST_CALL_STACK_SIZE
+            let element = self.stack.pop().unwrap();
+            if let Some(snapshot) = element.snapshot {
+                self.ledger = snapshot.ledger;
+                self.events.truncate(snapshot.events);
+                self.cleared_events = snapshot.cleared_events;
+            }
+        }
+        self.recursion_counter = depth.recursion_counter;
+    }
+
+    pub(crate) fn increment_recursion_counter(&mut self) -> Result<(), ExecutionError> {
+        self.recursion_counter += 1;
+        if self.recursion_counter > MAX_RECURSIVE_CALLS_DEPTH {
+            return Err(ExecutionError::RecursionLimit);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn decrement_recursion_counter(&mut self) {
+        self.recursion_counter = self.recursion_counter.saturating_sub(1);
+    }
+
+    pub(crate) fn slot(&self) -> (u64, u8) {
+        self.slot
+    }
+
+    pub(crate) fn time(&self) -> u64 {
+        self.time
+    }
+
+    pub(crate) fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    // Cheatcodes (see cheatcodes.rs)
+
+    /// Check that the call stack only contains the unit test call (caller & callee)
+    fn check_test_stack(&self) -> Result<(), ExecutionError> {
+        if self.stack.len() > TEST_CALL_STACK_SIZE {
+            return Err(ExecutionError::NestedCall);
+        }
+        Ok(())
+    }
+
+    /// Change the caller of the unit test (only CALLER_ADDRESS has write access to the callee)
+    pub(crate) fn set_caller(&mut self, address: &str) -> Result<(), ExecutionError> {
+        self.check_test_stack()?;
+        self.stack[0] = StackElement::caller(address, &self.stack[1].address);
+        Ok(())
+    }
+
+    /// Change the smart contract called by the unit test: the unit tests bytecode is deployed at
+    /// this address if needed, the test then uses its balance & datastore
+    pub(crate) fn set_callee(&mut self, address: &str) -> Result<(), ExecutionError> {
+        if !address.starts_with("AS") {
+            return Err(ExecutionError::NotASmartContract {
+                address: address.to_string(),
+            });
+        }
+        self.check_test_stack()?;
+        if self.ledger.entry(address).is_none() {
+            let bytecode = self
+                .ledger
+                .bytecode(&self.stack[1].address)
+                .unwrap_or_default()
+                .to_vec();
+            self.ledger.deploy(address, &bytecode)?;
+        }
+        self.stack[0] = StackElement::caller(&self.stack[0].address, address);
+        self.stack[1] = StackElement::callee(address, self.stack[1].coins);
+        Ok(())
+    }
+
+    /// Change the coins sent to the unit test (the balances are not changed)
+    pub(crate) fn set_call_coins(&mut self, coins: u64) -> Result<(), ExecutionError> {
+        self.check_test_stack()?;
+        self.stack[1].coins = coins;
+        Ok(())
+    }
+
+    /// Set the current time (in ms), the current slot is the slot at this time
+    pub(crate) fn set_time(&mut self, time: u64) {
+        let slot_index = time.saturating_sub(GENESIS_TIMESTAMP) / (T0 / THREAD_COUNT as u64);
+        self.slot = (
+            slot_index / THREAD_COUNT as u64,
+            (slot_index % THREAD_COUNT as u64) as u8,
+        );
+        self.time = time;
+    }
+
+    /// Set the current slot, the current time is the time of this slot
+    pub(crate) fn warp_to_slot(&mut self, period: u64, thread: u8) -> Result<(), ExecutionError> {
+        let time = period
+            .checked_mul(T0)
+            .and_then(|t| t.checked_add(thread as u64 * (T0 / THREAD_COUNT as u64)))
+            .and_then(|t| t.checked_add(GENESIS_TIMESTAMP));
+        match time {
+            Some(time) if thread < THREAD_COUNT => {
+                self.slot = (period, thread);
+                self.time = time;
+                Ok(())
+            }
+            _ => Err(ExecutionError::InvalidSlot { period, thread }),
+        }
+    }
+
+    pub(crate) fn set_chain_id(&mut self, chain_id: u64) {
+        self.chain_id = chain_id;
+    }
+
+    /// Events emitted since the last clear_events (all the events are reported)
+    pub(crate) fn test_events(&self) -> &[String] {
+        &self.events[self.cleared_events..]
+    }
+
+    pub(crate) fn clear_events(&mut self) {
+        self.cleared_events = self.events.len();
+    }
+
+    /// Save the ledger, return the snapshot id
+    pub(crate) fn snapshot(&mut self) -> u64 {
+        self.snapshots.push(self.ledger.clone());
+        self.snapshots.len() as u64 - 1
+    }
+
+    /// Restore the ledger saved by snapshot (a snapshot can be restored several times)
+    pub(crate) fn revert_to(&mut self, id: u64) -> Result<(), ExecutionError> {
+        let ledger = usize::try_from(id)
+            .ok()
+            .and_then(|index| self.snapshots.get(index))
+            .ok_or(ExecutionError::SnapshotNotFound { id })?;
+        self.ledger = ledger.clone();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BYTECODE: &[u8] = b"\0asm\x01\0\0\0";
+
+    #[test]
+    fn test_call_stack() {
+        let mut ctx = ExecutionContext::new(BYTECODE, 1_000).unwrap();
+        assert_eq!(ctx.current_address(), CONTRACT_ADDRESS);
+        assert_eq!(ctx.call_coins(), 1_000);
+        assert_eq!(
+            ctx.ledger.balance(CONTRACT_ADDRESS),
+            DEFAULT_BALANCE + 1_000
+        );
+        assert!(ctx.caller_has_write_access());
+        assert_eq!(ctx.finish_call(), Err(ExecutionError::CallStackOutOfBounds));
+
+        // Deploy a child contract & call it
+        let child = ctx.create_sc(BYTECODE).unwrap();
+        assert!(child.starts_with("AS1"));
+        assert_ne!(child, ctx.create_sc(BYTECODE).unwrap());
+        assert_eq!(ctx.init_call(&child, 50).unwrap(), BYTECODE);
+        assert_eq!(ctx.current_address(), child);
+        assert_eq!(ctx.call_coins(), 50);
+        assert_eq!(ctx.ledger.balance(&child), 50);
+        assert_eq!(
+            ctx.call_stack(),
+            vec![CALLER_ADDRESS, CONTRACT_ADDRESS, child.as_str()]
+        );
+        // The creator has write access to the child contract (but not the other way around)
+        assert!(ctx.caller_has_write_access());
+        assert!(ctx.check_write_rights(&child, WriteAction::SetData).is_ok());
+        assert_eq!(
+            ctx.check_write_rights(CONTRACT_ADDRESS, WriteAction::DeleteData),
+            Err(ExecutionError::WriteNotAllowed {
+                action: WriteAction::DeleteData,
+                address: CONTRACT_ADDRESS.to_string()
+            })
+        );
+        assert!(matches!(
+            ctx.transfer_coins(CONTRACT_ADDRESS, &child, 1),
+            Err(ExecutionError::SpendingNotAllowed { .. })
+        ));
+        ctx.finish_call().unwrap();
+        assert_eq!(ctx.current_address(), CONTRACT_ADDRESS);
+
+        assert!(matches!(
+            ctx.init_call(CALLER_ADDRESS, 0),
+            Err(ExecutionError::NotASmartContract { .. })
+        ));
+        assert!(matches!(
+            ctx.init_call("AS1unknown", 0),
+            Err(ExecutionError::BytecodeNotFound { .. })
+        ));
+        // An existing smart contract without bytecode can be called
+        ctx.ledger.set_bytecode(&child, &child, &[]).unwrap();
+        assert_eq!(ctx.init_call(&child, 0), Ok(Vec::new()));
+        ctx.finish_call().unwrap();
+    }
+
+    #[test]
+    fn test_rollback_calls() {
+        let mut ctx = ExecutionContext::new(BYTECODE, 0).unwrap();
+        let child = ctx.create_sc(BYTECODE).unwrap();
+        ctx.events.push("created".to_string());
+        let before = ctx.ledger.clone();
+
+        // Nested calls: CONTRACT -> child -> child, the last one fails
+        // Note: the coins sent pay for the child datastore storage costs
+        ctx.init_call(&child, 10_000_000).unwrap();
+        ctx.ledger
+            .set_data(&child, &child, b"key", b"value")
+            .unwrap();
+        ctx.events.push("called".to_string());
+        ctx.init_call(&child, 0).unwrap();
+        ctx.rollback_calls();
+
+        assert_eq!(ctx.current_address(), CONTRACT_ADDRESS);
+        assert_eq!(ctx.ledger, before);
+        assert_eq!(ctx.events, vec!["created"]);
+
+        // Only the calls started after the depth are rolled back (try_call cheatcode)
+        ctx.init_call(&child, 10_000_000).unwrap();
+        let depth = ctx.call_depth();
+        let called = ctx.ledger.clone();
+        ctx.increment_recursion_counter().unwrap();
+        ctx.init_call(&child, 1).unwrap();
+        ctx.ledger
+            .set_data(&child, &child, b"key", b"value")
+            .unwrap();
+        ctx.rollback_calls_to(depth);
+        assert_eq!(ctx.current_address(), child);
+        assert_eq!(ctx.call_depth(), depth);
+        assert_eq!(ctx.ledger, called);
+    }
+
+    #[test]
+    fn test_recursion_limit() {
+        let mut ctx = ExecutionContext::new(BYTECODE, 0).unwrap();
+        for _ in 0..MAX_RECURSIVE_CALLS_DEPTH {
+            ctx.increment_recursion_counter().unwrap();
+        }
+        assert_eq!(
+            ctx.increment_recursion_counter(),
+            Err(ExecutionError::RecursionLimit)
+        );
+        ctx.decrement_recursion_counter();
+        ctx.decrement_recursion_counter();
+        assert!(ctx.increment_recursion_counter().is_ok());
+    }
+
+    #[test]
+    fn test_set_caller_and_callee() {
+        let mut ctx = ExecutionContext::new(BYTECODE, 1_000).unwrap();
+        let user = "AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x";
+        ctx.set_caller(user).unwrap();
+        assert_eq!(ctx.call_stack(), vec![user, CONTRACT_ADDRESS]);
+        assert!(!ctx.caller_has_write_access());
+        ctx.set_caller(CALLER_ADDRESS).unwrap();
+        assert!(ctx.caller_has_write_access());
+
+        let other = "AS12LKs9txoSSy8JgFJgV96m8k5z9pgzjYMYSshwN67mFVuj3bdUV";
+        ctx.set_callee(other).unwrap();
+        assert_eq!(ctx.current_address(), other);
+        assert_eq!(ctx.ledger.balance(other), DEFAULT_BALANCE);
+        assert_eq!(ctx.ledger.bytecode(other), Some(BYTECODE));
+        assert_eq!(ctx.call_coins(), 1_000);
+        assert!(ctx.caller_has_write_access());
+        assert!(matches!(
+            ctx.set_callee(user),
+            Err(ExecutionError::NotASmartContract { .. })
+        ));
+
+        ctx.set_call_coins(5).unwrap();
+        assert_eq!(ctx.call_coins(), 5);
+
+        // The unit test call stack cannot be changed in a nested call
+        let child = ctx.create_sc(BYTECODE).unwrap();
+        ctx.init_call(&child, 0).unwrap();
+        assert_eq!(ctx.set_caller(user), Err(ExecutionError::NestedCall));
+        assert_eq!(ctx.set_call_coins(1), Err(ExecutionError::NestedCall));
+    }
+
+    #[test]
+    fn test_time_and_slot() {
+        let mut ctx = ExecutionContext::new(BYTECODE, 0).unwrap();
+        assert_eq!((ctx.slot(), ctx.time()), ((0, 0), GENESIS_TIMESTAMP));
+
+        ctx.warp_to_slot(10, 3).unwrap();
+        assert_eq!(ctx.slot(), (10, 3));
+        assert_eq!(ctx.time(), GENESIS_TIMESTAMP + 10 * T0 + 3 * 500);
+        assert!(ctx.warp_to_slot(10, THREAD_COUNT).is_err());
+        assert!(ctx.warp_to_slot(u64::MAX, 0).is_err());
+
+        ctx.set_time(GENESIS_TIMESTAMP + 2 * T0 + 1_250);
+        assert_eq!(ctx.slot(), (2, 2));
+        ctx.set_time(0);
+        assert_eq!((ctx.slot(), ctx.time()), ((0, 0), 0));
+    }
+
+    #[test]
+    fn test_clear_events() {
+        let mut ctx = ExecutionContext::new(BYTECODE, 0).unwrap();
+        ctx.events.push("a".to_string());
+        ctx.clear_events();
+        assert!(ctx.test_events().is_empty());
+
+        let child = ctx.create_sc(BYTECODE).unwrap();
+        ctx.events.push("b".to_string());
+        ctx.init_call(&child, 0).unwrap();
+        ctx.events.push("c".to_string());
+        ctx.clear_events();
+        // The events cleared in a failed call are restored
+        ctx.rollback_calls();
+        assert_eq!(ctx.test_events(), ["b"]);
+        assert_eq!(ctx.events, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut ctx = ExecutionContext::new(BYTECODE, 0).unwrap();
+        let id = ctx.snapshot();
+        let before = ctx.ledger.clone();
+        ctx.ledger.set_balance(CALLER_ADDRESS, 1);
+        ctx.revert_to(id).unwrap();
+        assert_eq!(ctx.ledger, before);
+        // A snapshot can be restored several times
+        ctx.ledger.set_balance(CALLER_ADDRESS, 2);
+        ctx.revert_to(id).unwrap();
+        assert_eq!(ctx.ledger, before);
+        assert_eq!(
+            ctx.revert_to(id + 1),
+            Err(ExecutionError::SnapshotNotFound { id: id + 1 })
+        );
+    }
+
+    #[test]
+    fn test_unsafe_random() {
+        let mut random = UnsafeRandom::new(42);
+        let numbers: Vec<i64> = (0..100).map(|_| random.next_i64()).collect();
+        // Same seed: same numbers
+        let mut random = UnsafeRandom::new(42);
+        assert!(numbers.iter().all(|n| *n == random.next_i64()));
+        assert_ne!(UnsafeRandom::new(43).next_i64(), numbers[0]);
+        // Not a constant sequence
+        assert!(numbers.windows(2).all(|w| w[0] != w[1]));
+        assert!(numbers.iter().any(|n| *n < 0));
+
+        let mut random = UnsafeRandom::new(0);
+        for _ in 0..1_000 {
+            let n = random.next_f64();
+            assert!((0.0..1.0).contains(&n));
+        }
+    }
+}
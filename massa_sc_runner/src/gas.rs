@@ -0,0 +1,244 @@
+// std
+use std::collections::BTreeMap;
+use std::path::Path;
+// third-party
+use serde_json::json;
+// internal
+use crate::cli::Format;
+use crate::report::TestResult;
+
+/// Gas baseline: test name -> gas consumed (json file, committed alongside the smart contract)
+pub(crate) type GasBaseline = BTreeMap<String, u64>;
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum GasBaselineError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid json: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Load a gas baseline file
+pub(crate) fn load_baseline(path: &Path) -> Result<GasBaseline, GasBaselineError> {
+    let content = std::fs::read(path)?;
+    Ok(serde_json::from_slice(&content)?)
+}
+
+/// Write the gas consumed by the tests to a baseline file
+///
+/// Note: the entries of the tests not run (filtered out, ignored or failed) are kept
+pub(crate) fn save_baseline(path: &Path, results: &[TestResult]) -> Result<(), GasBaselineError> {
+    let mut baseline = if path.exists() {
+        load_baseline(path)?
+    } else {
+        GasBaseline::new()
+    };
+    for result in results.iter() {
+        if let Some(gas) = result.gas {
+            baseline.insert(result.name.clone(), gas);
+        }
+    }
+    let mut content = serde_json::to_string_pretty(&baseline)?;
+    content.push('\n');
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// A test whose gas consumption grew beyond the threshold
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GasRegression {
+    pub name: String,
+    pub baseline: u64,
+    pub gas: u64,
+}
+
+impl GasRegression {
+    /// Increase in percent
+    pub(crate) fn increase(&self) -> f64 {
+        percent_diff(self.baseline, self.gas)
+    }
+}
+
+fn percent_diff(baseline: u64, gas: u64) -> f64 {
+    if baseline == 0 {
+        if gas == 0 { 0.0 } else { f64::INFINITY }
+    } else {
+        (gas as f64 - baseline as f64) * 100.0 / baseline as f64
+    }
+}
+
+/// Compare the gas consumed by the tests against a baseline
+///
+/// threshold: allowed increase (in percent), tests not in the baseline are not checked
+pub(crate) fn check_regressions(
+    results: &[TestResult],
+    baseline: &GasBaseline,
+    threshold: f64,
+) -> Vec<GasRegression> {
+    results
+        .iter()
+        .filter_map(|result| {
+            let gas = result.gas?;
+            let baseline = *baseline.get(&result.name)?;
+            (percent_diff(baseline, gas) > threshold).then(|| GasRegression {
+                name: result.name.clone(),
+                baseline,
+                gas,
+            })
+        })
+        .collect()
+}
+
+/// Print the gas consumed by each test (with the difference to the baseline if any)
+pub(crate) fn print_gas_report(
+    results: &[TestResult],
+    baseline: Option<&GasBaseline>,
+    format: Format,
+) {
+    if format == Format::Json {
+        for result in results.iter().filter(|r| r.gas.is_some()) {
+            let baseline = baseline.and_then(|b| b.get(&result.name));
+            println!(
+                "{}",
+                json!({"type": "gas", "name": result.name, "gas": result.gas, "baseline": baseline})
+            );
+        }
+        return;
+    }
+
+    let width = results.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    println!("gas report:");
+    for result in results.iter() {
+        let Some(gas) = result.gas else {
+            continue;
+        };
+        let diff = match baseline.and_then(|b| b.get(&result.name)) {
+            Some(baseline) => format!(
+                " (baseline: {baseline}, {:+.2}%)",
+                percent_diff(*baseline, gas)
+            ),
+            None => String::new(),
+        };
+        println!("    {:<width$}  {gas:>16}{diff}", result.name);
+    }
+    println!();
+}
+
+/// Print the gas regressions (as errors or warnings)
+pub(crate) fn print_regressions(
+    regressions: &[GasRegression],
+    threshold: f64,
+    fail: bool,
+    format: Format,
+) {
+    if format == Format::Json {
+        for regression in regressions.iter() {
+            println!(
+                "{}",
+                json!({
+                    "type": "gas_regression",
+                    "name": regression.name,
+                    "gas": regression.gas,
+                    "baseline": regression.baseline,
+                    "increase": regression.increase(),
+                    "event": if fail { "failed" } else { "warning" },
+                })
+            );
+        }
+        return;
+    }
+
+    if regressions.is_empty() {
+        return;
+    }
+    let level = if fail { "error" } else { "warning" };
+    println!("{level}: gas consumption increased by more than {threshold}%:");
+    for regression in regressions.iter() {
+        println!(
+            "    {}: {} -> {} ({:+.2}%)",
+            regression.name,
+            regression.baseline,
+            regression.gas,
+            regression.increase()
+        );
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::TestOutcome;
+    use std::time::Duration;
+
+    fn result(name: &str, gas: Option<u64>) -> TestResult {
+        TestResult {
+            name: name.to_string(),
+            outcome: TestOutcome::Passed,
+            output: String::new(),
+            duration: Duration::ZERO,
+            gas,
+            events: vec![],
+        }
+    }
+
+    #[test]
+    fn test_check_regressions() {
+        let baseline = GasBaseline::from([
+            ("test_a".to_string(), 1_000),
+            ("test_b".to_string(), 1_000),
+            ("test_c".to_string(), 1_000),
+            ("test_d".to_string(), 0),
+        ]);
+        let results = vec![
+            // +5%: below the threshold
+            result("test_a", Some(1_050)),
+            // +20%
+            result("test_b", Some(1_200)),
+            // failed test
+            result("test_c", None),
+            result("test_d", Some(10)),
+            // not in the baseline
+            result("test_e", Some(5_000)),
+        ];
+        let regressions = check_regressions(&results, &baseline, 5.0);
+        assert_eq!(
+            regressions,
+            vec![
+                GasRegression {
+                    name: "test_b".to_string(),
+                    baseline: 1_000,
+                    gas: 1_200
+                },
+                GasRegression {
+                    name: "test_d".to_string(),
+                    baseline: 0,
+                    gas: 10
+                },
+            ]
+        );
+        assert_eq!(regressions[0].increase(), 20.0);
+        assert_eq!(check_regressions(&results, &baseline, 20.0).len(), 1);
+    }
+
+    #[test]
+    fn test_save_baseline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gas_baseline.json");
+        save_baseline(
+            &path,
+            &[result("test_a", Some(1_000)), result("test_b", Some(20))],
+        )
+        .unwrap();
+        // test_b not run: its entry is kept
+        save_baseline(
+            &path,
+            &[result("test_a", Some(900)), result("test_b", None)],
+        )
+        .unwrap();
+        assert_eq!(
+            load_baseline(&path).unwrap(),
+            GasBaseline::from([("test_a".to_string(), 900), ("test_b".to_string(), 20)])
+        );
+    }
+}
@@ -1,14 +1,131 @@
 use function_name::named;
-use massa_sc_runtime::{Interface, InterfaceClone, RuntimeModule};
+use massa_sc_runtime::{Interface, InterfaceClone, RuntimeModule, run_function};
 use std::{
-    collections::{BTreeMap, BTreeSet},
-    sync::{Arc, RwLock},
+    collections::BTreeSet,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
 };
 
-#[derive(Clone, Default)]
+use crate::cache::ModuleRegistry;
+use crate::cheatcodes::{
+    CHEATCODE_PREFIX, CheatcodeError, TRY_CALL_CHEATCODE, TryCall, run_cheatcode,
+};
+use crate::context::{ExecutionContext, ExecutionError, UnsafeRandom, WriteAction, check_address};
+
+#[derive(Clone)]
 pub struct MassaScRunnerInterface {
-    sc_storage: Arc<RwLock<BTreeMap<Vec<u8>, Vec<u8>>>>,
-    // other_sc_storage: Arc<RwLock<BTreeMap<Vec<u8>, Vec<u8>>>>,
+    /// Ledger (balances, bytecodes & datastores), call stack & events
+    context: Arc<Mutex<ExecutionContext>>,
+    /// Compiled modules (called or deployed smart contracts)
+    modules: Arc<ModuleRegistry>,
+    /// Gas limit of the calls run by the try_call cheatcode (see --gas-limit)
+    gas_limit: u64,
+    /// The unit tests import the cheatcode function (see imports_cheatcode)
+    cheatcodes: bool,
+    /// Print the output (print, events, ...) instead of capturing it
+    nocapture: bool,
+    /// Captured output (displayed for failed tests)
+    output: Arc<Mutex<String>>,
+}
+
+impl MassaScRunnerInterface {
+    /// Interface for a unit test call (see ExecutionContext::new)
+    pub fn new(
+        nocapture: bool,
+        bytecode: &[u8],
+        call_coins: u64,
+        modules: Arc<ModuleRegistry>,
+        gas_limit: u64,
+        cheatcodes: bool,
+    ) -> Result<Self, ExecutionError> {
+        Ok(Self {
+            context: Arc::new(Mutex::new(ExecutionContext::new(bytecode, call_coins)?)),
+            modules,
+            gas_limit,
+            cheatcodes,
+            nocapture,
+            output: Default::default(),
+        })
+    }
+
+    fn context(&self) -> MutexGuard<'_, ExecutionContext> {
+        // Note: a host function panicking (poisoned lock) fails the test, the context is still
+        //       usable to report it
+        self.context.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Seed of unsafe_random & unsafe_random_f64 (see --random-seed)
+    pub fn set_random_seed(&self, seed: u64) {
+        self.context().random = UnsafeRandom::new(seed);
+    }
+
+    /// Roll back the calls that failed (see ExecutionContext::rollback_calls)
+    pub fn rollback_calls(&self) {
+        self.context().rollback_calls();
+    }
+
+    /// Message of the revert expected by the test (see the expect_revert cheatcode)
+    pub fn expected_revert(&self) -> Option<String> {
+        self.context().expected_revert.clone()
+    }
+
+    /// Output captured so far (empty with nocapture)
+    pub fn captured_output(&self) -> String {
+        self.output
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Events emitted so far
+    pub fn events(&self) -> Vec<String> {
+        self.context().events.clone()
+    }
+
+    /// try_call cheatcode: call a function of a smart contract, a failure (e.g. a revert) is
+    /// returned instead of interrupting the unit test and the changes of the call are reverted
+    ///
+    /// Note: the call has its own gas limit, the gas it uses is not charged to the unit test
+    fn try_call(&self, args: &[u8]) -> Result<Vec<u8>, CheatcodeError> {
+        let call = TryCall::parse(args)?;
+        let depth = self.context().call_depth();
+        let res = self.call(&call);
+        if res.is_err() {
+            self.context().rollback_calls_to(depth);
+        }
+        Ok(TryCall::result(res))
+    }
+
+    /// Run a call as massa-sc-runtime does for the call ABI function (init_call, run the
+    /// function, finish_call), return the value returned by the function or the error message
+    fn call(&self, call: &TryCall) -> Result<Vec<u8>, String> {
+        let bytecode = self
+            .context()
+            .init_call(&call.address, call.coins)
+            .map_err(|e| e.to_string())?;
+        let module = self.modules.get(&bytecode)?;
+        let response = run_function(
+            self,
+            module,
+            &call.function,
+            &call.args,
+            self.gas_limit,
+            self.modules.gas_costs().clone(),
+            self.modules.exec_limits().clone(),
+        )
+        .map_err(|e| e.to_string())?;
+        self.context().finish_call().map_err(|e| e.to_string())?;
+        Ok(response.ret)
+    }
+
+    fn log(&self, line: String) {
+        if self.nocapture {
+            println!("{line}");
+        } else {
+            let mut output = self.output.lock().unwrap_or_else(PoisonError::into_inner);
+            output.push_str(line.as_str());
+            output.push('\n');
+        }
+    }
 }
 
 impl InterfaceClone for MassaScRunnerInterface {
@@ -20,107 +137,167 @@ impl InterfaceClone for MassaScRunnerInterface {
 #[allow(unused_variables)]
 impl Interface for MassaScRunnerInterface {
     fn increment_recursion_counter(&self) -> massa_sc_runtime::Result<()> {
-        todo!()
+        Ok(self.context().increment_recursion_counter()?)
     }
 
     fn decrement_recursion_counter(&self) -> massa_sc_runtime::Result<()> {
-        todo!()
+        self.context().decrement_recursion_counter();
+        Ok(())
     }
 
+    #[named]
     fn get_interface_version(&self) -> massa_sc_runtime::Result<u32> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn init_call(&self, address: &str, raw_coins: u64) -> massa_sc_runtime::Result<Vec<u8>> {
-        todo!()
+        self.log(format!(
+            "[{}] address: {}, coins: {}",
+            function_name!(),
+            address,
+            raw_coins
+        ));
+        check_address(address)?;
+        Ok(self.context().init_call(address, raw_coins)?)
     }
 
+    #[named]
     fn init_call_wasmv1(
         &self,
         address: &str,
         raw_coins: massa_proto_rs::massa::model::v1::NativeAmount,
     ) -> massa_sc_runtime::Result<Vec<u8>> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn finish_call(&self) -> massa_sc_runtime::Result<()> {
-        todo!()
+        Ok(self.context().finish_call()?)
     }
 
     fn get_balance(&self) -> massa_sc_runtime::Result<u64> {
-        todo!()
+        let context = self.context();
+        Ok(context.ledger.balance(context.current_address()))
     }
 
     fn get_balance_for(&self, address: &str) -> massa_sc_runtime::Result<u64> {
-        todo!()
+        check_address(address)?;
+        Ok(self.context().ledger.balance(address))
     }
 
+    #[named]
     fn get_balance_wasmv1(
         &self,
         address: Option<String>,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::NativeAmount> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn transfer_coins(&self, to_address: &str, raw_amount: u64) -> massa_sc_runtime::Result<()> {
-        todo!()
+        let from_address = self.context().current_address().to_string();
+        self.transfer_coins_for(&from_address, to_address, raw_amount)
     }
 
+    #[named]
     fn transfer_coins_for(
         &self,
         from_address: &str,
         to_address: &str,
         raw_amount: u64,
     ) -> massa_sc_runtime::Result<()> {
-        todo!()
+        self.log(format!(
+            "[{}] from: {}, to: {}, amount: {}",
+            function_name!(),
+            from_address,
+            to_address,
+            raw_amount
+        ));
+        check_address(from_address)?;
+        check_address(to_address)?;
+        // Note: Massa only allows spending from an address with write access (e.g. the smart
+        //       contract being executed)
+        self.context()
+            .transfer_coins(from_address, to_address, raw_amount)?;
+        Ok(())
     }
 
+    #[named]
     fn transfer_coins_wasmv1(
         &self,
         to_address: String,
         raw_amount: massa_proto_rs::massa::model::v1::NativeAmount,
         from_address: Option<String>,
     ) -> massa_sc_runtime::Result<()> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn get_call_coins(&self) -> massa_sc_runtime::Result<u64> {
-        todo!()
+        Ok(self.context().call_coins())
     }
 
+    #[named]
     fn get_call_coins_wasmv1(
         &self,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::NativeAmount> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn raw_set_bytecode(&self, bytecode: &[u8]) -> massa_sc_runtime::Result<()> {
-        todo!()
+        let address = self.context().current_address().to_string();
+        self.raw_set_bytecode_for(&address, bytecode)
     }
 
     fn raw_set_bytecode_for(&self, address: &str, bytecode: &[u8]) -> massa_sc_runtime::Result<()> {
-        todo!()
+        check_address(address)?;
+        let mut context = self.context();
+        context.check_write_rights(address, WriteAction::SetBytecode)?;
+        let payer = context.current_address().to_string();
+        context.ledger.set_bytecode(&payer, address, bytecode)?;
+        Ok(())
     }
 
+    #[named]
     fn set_bytecode_wasmv1(
         &self,
         bytecode: &[u8],
         address: Option<String>,
     ) -> massa_sc_runtime::Result<()> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn create_module(&self, module: &[u8]) -> massa_sc_runtime::Result<String> {
-        todo!()
+        let address = self.context().create_sc(module)?;
+        self.log(format!("[{}] address: {}", function_name!(), address));
+        Ok(address)
     }
 
     fn print(&self, message: &str) -> massa_sc_runtime::Result<()> {
-        println!("{message}");
+        self.log(message.to_string());
         Ok(())
     }
 
     fn get_keys(&self, prefix: Option<&[u8]>) -> massa_sc_runtime::Result<BTreeSet<Vec<u8>>> {
-        todo!()
+        let context = self.context();
+        Ok(context.ledger.get_keys(context.current_address(), prefix))
     }
 
     fn get_keys_for(
@@ -128,47 +305,79 @@ impl Interface for MassaScRunnerInterface {
         address: &str,
         prefix: Option<&[u8]>,
     ) -> massa_sc_runtime::Result<BTreeSet<Vec<u8>>> {
-        todo!()
+        check_address(address)?;
+        let context = self.context();
+        // Note: Massa get_keys_for bail!("data entry not found") if the address does not exist
+        if context.ledger.entry(address).is_none() {
+            return Err(ExecutionError::DataEntryNotFound.into());
+        }
+        Ok(context.ledger.get_keys(address, prefix))
     }
 
+    #[named]
     fn get_ds_keys_wasmv1(
         &self,
         prefix: &[u8],
         address: Option<String>,
     ) -> massa_sc_runtime::Result<BTreeSet<Vec<u8>>> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     #[named]
     fn raw_get_data(&self, key: &[u8]) -> massa_sc_runtime::Result<Vec<u8>> {
-        println!("[{}] key: {:?}", function_name!(), key);
-        let guard = self.sc_storage.read().unwrap();
-        // Note: Massa get_data bail!("data entry not found") if key not found
-        let data = guard.get(key).cloned().unwrap();
-        println!("[{}] data: {:?}", function_name!(), data);
+        self.log(format!("[{}] key: {:?}", function_name!(), key));
+        let address = self.context().current_address().to_string();
+        let data = self.raw_get_data_for(&address, key)?;
+        self.log(format!("[{}] data: {:?}", function_name!(), data));
         Ok(data)
     }
 
     fn raw_get_data_for(&self, address: &str, key: &[u8]) -> massa_sc_runtime::Result<Vec<u8>> {
-        todo!()
+        // Cheatcode call (the massa_test.cheatcode import is redirected to get_data_for)
+        if self.cheatcodes
+            && let Some(cheatcode) = address.strip_prefix(CHEATCODE_PREFIX)
+        {
+            self.log(format!("[cheatcode] {cheatcode}: {key:?}"));
+            // Note: the context must not be locked while the called function is executed
+            if cheatcode == TRY_CALL_CHEATCODE {
+                return Ok(self.try_call(key)?);
+            }
+            return Ok(run_cheatcode(&mut self.context(), cheatcode, key)?);
+        }
+        check_address(address)?;
+        // Note: Massa get_data bail!("data entry not found") if key not found
+        self.context()
+            .ledger
+            .get_data(address, key)
+            .map(|data| data.to_vec())
+            .ok_or(ExecutionError::DataEntryNotFound.into())
     }
 
+    #[named]
     fn get_ds_value_wasmv1(
         &self,
         key: &[u8],
         address: Option<String>,
     ) -> massa_sc_runtime::Result<Vec<u8>> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     #[named]
     fn raw_set_data(&self, key: &[u8], value: &[u8]) -> massa_sc_runtime::Result<()> {
-        // TODO: debug!
-        println!("[{}] key: {:?}, value: {:?}", function_name!(), key, value);
-        // TODO: no unwrap
-        let mut guard = self.sc_storage.write().unwrap();
-        guard.insert(key.to_vec(), value.to_vec());
-        Ok(())
+        self.log(format!(
+            "[{}] key: {:?}, value: {:?}",
+            function_name!(),
+            key,
+            value
+        ));
+        let address = self.context().current_address().to_string();
+        self.raw_set_data_for(&address, key, value)
     }
 
     fn raw_set_data_for(
@@ -177,20 +386,32 @@ impl Interface for MassaScRunnerInterface {
         key: &[u8],
         value: &[u8],
     ) -> massa_sc_runtime::Result<()> {
-        todo!()
+        check_address(address)?;
+        let mut context = self.context();
+        context.check_write_rights(address, WriteAction::SetData)?;
+        // Storage cost is paid by the smart contract being executed (refunded if the value is
+        // smaller)
+        let payer = context.current_address().to_string();
+        context.ledger.set_data(&payer, address, key, value)?;
+        Ok(())
     }
 
+    #[named]
     fn set_ds_value_wasmv1(
         &self,
         key: &[u8],
         value: &[u8],
         address: Option<String>,
     ) -> massa_sc_runtime::Result<()> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn raw_append_data(&self, key: &[u8], value: &[u8]) -> massa_sc_runtime::Result<()> {
-        todo!()
+        let address = self.context().current_address().to_string();
+        self.raw_append_data_for(&address, key, value)
     }
 
     fn raw_append_data_for(
@@ -199,179 +420,263 @@ impl Interface for MassaScRunnerInterface {
         key: &[u8],
         value: &[u8],
     ) -> massa_sc_runtime::Result<()> {
-        todo!()
+        check_address(address)?;
+        let mut context = self.context();
+        context.check_write_rights(address, WriteAction::AppendData)?;
+        let payer = context.current_address().to_string();
+        context.ledger.append_data(&payer, address, key, value)?;
+        Ok(())
     }
 
+    #[named]
     fn append_ds_value_wasmv1(
         &self,
         key: &[u8],
         value: &[u8],
         address: Option<String>,
     ) -> massa_sc_runtime::Result<()> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn raw_delete_data(&self, key: &[u8]) -> massa_sc_runtime::Result<()> {
-        todo!()
+        let address = self.context().current_address().to_string();
+        self.raw_delete_data_for(&address, key)
     }
 
     fn raw_delete_data_for(&self, address: &str, key: &[u8]) -> massa_sc_runtime::Result<()> {
-        todo!()
+        check_address(address)?;
+        let mut context = self.context();
+        context.check_write_rights(address, WriteAction::DeleteData)?;
+        let payer = context.current_address().to_string();
+        context.ledger.delete_data(&payer, address, key)?;
+        Ok(())
     }
 
+    #[named]
     fn delete_ds_entry_wasmv1(
         &self,
         key: &[u8],
         address: Option<String>,
     ) -> massa_sc_runtime::Result<()> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn has_data(&self, key: &[u8]) -> massa_sc_runtime::Result<bool> {
-        Ok(self.sc_storage.read().unwrap().contains_key(key))
+        let context = self.context();
+        Ok(context.ledger.has_data(context.current_address(), key))
     }
 
     fn has_data_for(&self, address: &str, key: &[u8]) -> massa_sc_runtime::Result<bool> {
-        todo!()
+        check_address(address)?;
+        Ok(self.context().ledger.has_data(address, key))
     }
 
+    #[named]
     fn ds_entry_exists_wasmv1(
         &self,
         key: &[u8],
         address: Option<String>,
     ) -> massa_sc_runtime::Result<bool> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn raw_get_bytecode(&self) -> massa_sc_runtime::Result<Vec<u8>> {
-        todo!()
+        let address = self.context().current_address().to_string();
+        self.raw_get_bytecode_for(&address)
     }
 
     fn raw_get_bytecode_for(&self, address: &str) -> massa_sc_runtime::Result<Vec<u8>> {
-        todo!()
+        check_address(address)?;
+        // Note: Massa get_bytecode_for bail!("bytecode not found") if the address does not exist
+        self.context()
+            .ledger
+            .bytecode(address)
+            .map(|bytecode| bytecode.to_vec())
+            .ok_or(ExecutionError::BytecodeEntryNotFound.into())
     }
 
+    #[named]
     fn get_bytecode_wasmv1(&self, address: Option<String>) -> massa_sc_runtime::Result<Vec<u8>> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn get_op_keys(&self, prefix: Option<&[u8]>) -> massa_sc_runtime::Result<Vec<Vec<u8>>> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn get_op_keys_wasmv1(&self, prefix: &[u8]) -> massa_sc_runtime::Result<Vec<Vec<u8>>> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn op_entry_exists(&self, key: &[u8]) -> massa_sc_runtime::Result<bool> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn get_op_data(&self, key: &[u8]) -> massa_sc_runtime::Result<Vec<u8>> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn caller_has_write_access(&self) -> massa_sc_runtime::Result<bool> {
-        Ok(true)
+        Ok(self.context().caller_has_write_access())
     }
 
     fn hash(&self, data: &[u8]) -> massa_sc_runtime::Result<[u8; 32]> {
-        todo!()
+        // Note: Massa hash (massa_hash::Hash) is a blake3 hash
+        Ok(*blake3::hash(data).as_bytes())
     }
 
     fn hash_blake3(&self, bytes: &[u8]) -> massa_sc_runtime::Result<[u8; 32]> {
-        todo!()
+        Ok(*blake3::hash(bytes).as_bytes())
     }
 
+    #[named]
     fn signature_verify(
         &self,
         data: &[u8],
         signature: &str,
         public_key: &str,
     ) -> massa_sc_runtime::Result<bool> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn evm_signature_verify(
         &self,
         message: &[u8],
         signature: &[u8],
         public_key: &[u8],
     ) -> massa_sc_runtime::Result<bool> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn evm_get_address_from_pubkey(&self, public_key: &[u8]) -> massa_sc_runtime::Result<Vec<u8>> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn evm_get_pubkey_from_signature(
         &self,
         hash: &[u8],
         signature: &[u8],
     ) -> massa_sc_runtime::Result<Vec<u8>> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn is_address_eoa(&self, address: &str) -> massa_sc_runtime::Result<bool> {
-        todo!()
+        check_address(address)?;
+        Ok(address.starts_with("AU"))
     }
 
+    #[named]
     fn address_from_public_key(&self, public_key: &str) -> massa_sc_runtime::Result<String> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn validate_address(&self, address: &str) -> massa_sc_runtime::Result<bool> {
-        todo!()
+        Ok(check_address(address).is_ok())
     }
 
     fn get_time(&self) -> massa_sc_runtime::Result<u64> {
-        todo!()
+        Ok(self.context().time())
     }
 
     fn unsafe_random(&self) -> massa_sc_runtime::Result<i64> {
-        todo!()
+        Ok(self.context().random.next_i64())
     }
 
     fn unsafe_random_f64(&self) -> massa_sc_runtime::Result<f64> {
-        todo!()
+        Ok(self.context().random.next_f64())
     }
 
+    #[named]
     fn unsafe_random_wasmv1(&self, num_bytes: u64) -> massa_sc_runtime::Result<Vec<u8>> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn get_current_period(&self) -> massa_sc_runtime::Result<u64> {
-        todo!()
+        Ok(self.context().slot().0)
     }
 
     fn get_current_thread(&self) -> massa_sc_runtime::Result<u8> {
-        todo!()
+        Ok(self.context().slot().1)
     }
 
     fn get_current_slot(&self) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::Slot> {
-        todo!()
+        let (period, thread) = self.context().slot();
+        Ok(massa_proto_rs::massa::model::v1::Slot {
+            period,
+            thread: thread as u32,
+        })
     }
 
     fn get_owned_addresses(&self) -> massa_sc_runtime::Result<Vec<String>> {
-        todo!()
+        Ok(self.context().owned_addresses())
     }
 
     fn get_call_stack(&self) -> massa_sc_runtime::Result<Vec<String>> {
-        Ok(vec![
-            "AU1Yvq49utdezr496dHbRj3TMjqsCh2awggjfGraHoddE7XfEkpY".to_string(),
-            "AS1GFocKuZKiSr2Gcu8y69fraPEqs7xYWvEFikTKcThYC38sBFBH".to_string(),
-        ])
+        Ok(self.context().call_stack())
     }
 
     #[named]
-    fn generate_event(&self, _event: String) -> massa_sc_runtime::Result<()> {
-        println!("[{}] event: {}", function_name!(), _event);
+    fn generate_event(&self, event: String) -> massa_sc_runtime::Result<()> {
+        self.log(format!("[{}] event: {}", function_name!(), event));
+        self.context().events.push(event);
         Ok(())
     }
 
+    #[named]
     fn generate_event_wasmv1(&self, _event: Vec<u8>) -> massa_sc_runtime::Result<()> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn get_module(
@@ -379,7 +684,7 @@ impl Interface for MassaScRunnerInterface {
         bytecode: &[u8],
         gas_limit: u64,
     ) -> massa_sc_runtime::Result<RuntimeModule> {
-        todo!()
+        Ok(self.modules.get(bytecode).map_err(ExecutionError::Module)?)
     }
 
     fn get_tmp_module(
@@ -387,9 +692,10 @@ impl Interface for MassaScRunnerInterface {
         bytecode: &[u8],
         gas_limit: u64,
     ) -> massa_sc_runtime::Result<RuntimeModule> {
-        todo!()
+        Ok(self.modules.get(bytecode).map_err(ExecutionError::Module)?)
     }
 
+    #[named]
     fn send_message(
         &self,
         target_address: &str,
@@ -402,34 +708,50 @@ impl Interface for MassaScRunnerInterface {
         data: &[u8],
         filter: Option<(&str, Option<&[u8]>)>,
     ) -> massa_sc_runtime::Result<()> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn get_origin_operation_id(&self) -> massa_sc_runtime::Result<Option<String>> {
-        todo!()
+        Ok(None)
     }
 
+    #[named]
     fn hash_sha256(&self, bytes: &[u8]) -> massa_sc_runtime::Result<[u8; 32]> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn hash_keccak256(&self, bytes: &[u8]) -> massa_sc_runtime::Result<[u8; 32]> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn chain_id(&self) -> massa_sc_runtime::Result<u64> {
-        todo!()
+        Ok(self.context().chain_id())
     }
 
+    #[named]
     fn get_deferred_call_quote(
         &self,
         target_slot: (u64, u8),
         gas_limit: u64,
         params_size: u64,
     ) -> massa_sc_runtime::Result<(bool, u64)> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn deferred_call_register(
         &self,
         target_addr: &str,
@@ -439,62 +761,98 @@ impl Interface for MassaScRunnerInterface {
         params: &[u8],
         coins: u64,
     ) -> massa_sc_runtime::Result<String> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn deferred_call_exists(&self, id: &str) -> massa_sc_runtime::Result<bool> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn deferred_call_cancel(&self, id: &str) -> massa_sc_runtime::Result<()> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn native_amount_from_str_wasmv1(
         &self,
         amount: &str,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::NativeAmount> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn native_amount_to_string_wasmv1(
         &self,
         amount: &massa_proto_rs::massa::model::v1::NativeAmount,
     ) -> massa_sc_runtime::Result<String> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn check_native_amount_wasmv1(
         &self,
         amount: &massa_proto_rs::massa::model::v1::NativeAmount,
     ) -> massa_sc_runtime::Result<bool> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn add_native_amount_wasmv1(
         &self,
         amount1: &massa_proto_rs::massa::model::v1::NativeAmount,
         amount2: &massa_proto_rs::massa::model::v1::NativeAmount,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::NativeAmount> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn sub_native_amount_wasmv1(
         &self,
         amount1: &massa_proto_rs::massa::model::v1::NativeAmount,
         amount2: &massa_proto_rs::massa::model::v1::NativeAmount,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::NativeAmount> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn scalar_mul_native_amount_wasmv1(
         &self,
         amount: &massa_proto_rs::massa::model::v1::NativeAmount,
         factor: u64,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::NativeAmount> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn scalar_div_rem_native_amount_wasmv1(
         &self,
         dividend: &massa_proto_rs::massa::model::v1::NativeAmount,
@@ -503,72 +861,120 @@ impl Interface for MassaScRunnerInterface {
         massa_proto_rs::massa::model::v1::NativeAmount,
         massa_proto_rs::massa::model::v1::NativeAmount,
     )> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn div_rem_native_amount_wasmv1(
         &self,
         dividend: &massa_proto_rs::massa::model::v1::NativeAmount,
         divisor: &massa_proto_rs::massa::model::v1::NativeAmount,
     ) -> massa_sc_runtime::Result<(u64, massa_proto_rs::massa::model::v1::NativeAmount)> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn check_address_wasmv1(&self, to_check: &str) -> massa_sc_runtime::Result<bool> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn check_pubkey_wasmv1(&self, to_check: &str) -> massa_sc_runtime::Result<bool> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn check_signature_wasmv1(&self, to_check: &str) -> massa_sc_runtime::Result<bool> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn get_address_category_wasmv1(
         &self,
         to_check: &str,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::AddressCategory> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn get_address_version_wasmv1(&self, address: &str) -> massa_sc_runtime::Result<u64> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn get_pubkey_version_wasmv1(&self, pubkey: &str) -> massa_sc_runtime::Result<u64> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn get_signature_version_wasmv1(&self, signature: &str) -> massa_sc_runtime::Result<u64> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn checked_add_native_time_wasmv1(
         &self,
         time1: &massa_proto_rs::massa::model::v1::NativeTime,
         time2: &massa_proto_rs::massa::model::v1::NativeTime,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::NativeTime> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn checked_sub_native_time_wasmv1(
         &self,
         time1: &massa_proto_rs::massa::model::v1::NativeTime,
         time2: &massa_proto_rs::massa::model::v1::NativeTime,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::NativeTime> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn checked_mul_native_time_wasmv1(
         &self,
         time: &massa_proto_rs::massa::model::v1::NativeTime,
         factor: u64,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::NativeTime> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn checked_scalar_div_native_time_wasmv1(
         &self,
         dividend: &massa_proto_rs::massa::model::v1::NativeTime,
@@ -577,56 +983,297 @@ impl Interface for MassaScRunnerInterface {
         massa_proto_rs::massa::model::v1::NativeTime,
         massa_proto_rs::massa::model::v1::NativeTime,
     )> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn checked_div_native_time_wasmv1(
         &self,
         dividend: &massa_proto_rs::massa::model::v1::NativeTime,
         divisor: &massa_proto_rs::massa::model::v1::NativeTime,
     ) -> massa_sc_runtime::Result<(u64, massa_proto_rs::massa::model::v1::NativeTime)> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn base58_check_to_bytes_wasmv1(&self, s: &str) -> massa_sc_runtime::Result<Vec<u8>> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn bytes_to_base58_check_wasmv1(&self, bytes: &[u8]) -> String {
-        todo!()
+        bs58::encode(bytes).with_check().into_string()
     }
 
+    #[named]
     fn compare_address_wasmv1(
         &self,
         left: &str,
         right: &str,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::ComparisonResult> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn compare_native_amount_wasmv1(
         &self,
         left: &massa_proto_rs::massa::model::v1::NativeAmount,
         right: &massa_proto_rs::massa::model::v1::NativeAmount,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::ComparisonResult> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn compare_native_time_wasmv1(
         &self,
         left: &massa_proto_rs::massa::model::v1::NativeTime,
         right: &massa_proto_rs::massa::model::v1::NativeTime,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::ComparisonResult> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
+    #[named]
     fn compare_pub_key_wasmv1(
         &self,
         left: &str,
         right: &str,
     ) -> massa_sc_runtime::Result<massa_proto_rs::massa::model::v1::ComparisonResult> {
-        todo!()
+        Err(ExecutionError::NotSupported {
+            function: function_name!(),
+        }
+        .into())
     }
 
     fn save_gas_remaining_before_subexecution(&self, gas_used_until: u64) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{CALLER_ADDRESS, CONTRACT_ADDRESS};
+    use crate::ledger::DEFAULT_BALANCE;
+    use massa_sc_runtime::CondomLimits;
+
+    /// Interface of unit tests importing the cheatcode function
+    fn interface() -> MassaScRunnerInterface {
+        interface_with_cheatcodes(true)
+    }
+
+    fn interface_with_cheatcodes(cheatcodes: bool) -> MassaScRunnerInterface {
+        let modules = ModuleRegistry::new(crate::load_gas_costs(), CondomLimits::default());
+        MassaScRunnerInterface::new(
+            false,
+            b"\0asm\x01\0\0\0",
+            0,
+            Arc::new(modules),
+            u64::MAX,
+            cheatcodes,
+        )
+        .unwrap()
+    }
+
+    /// Error message of a host function
+    fn error<T: std::fmt::Debug>(res: massa_sc_runtime::Result<T>) -> String {
+        res.unwrap_err().to_string()
+    }
+
+    // Expected messages: see the interface & the execution context of massa-execution-worker
+
+    #[test]
+    fn test_address_errors() {
+        let interface = interface();
+        for address in [
+            "",
+            "AU12",
+            "AS1unknown",
+            &CONTRACT_ADDRESS.replace("AS", "AX"),
+        ] {
+            assert!(!interface.validate_address(address).unwrap());
+            assert_eq!(
+                error(interface.get_balance_for(address)),
+                "address parsing error"
+            );
+            assert_eq!(
+                error(interface.raw_get_data_for(address, b"key")),
+                "address parsing error"
+            );
+        }
+        assert!(interface.validate_address(CALLER_ADDRESS).unwrap());
+        assert!(interface.is_address_eoa(CALLER_ADDRESS).unwrap());
+        assert!(!interface.is_address_eoa(CONTRACT_ADDRESS).unwrap());
+        assert_eq!(
+            error(interface.init_call(CALLER_ADDRESS, 0)),
+            format!("called address {CALLER_ADDRESS} is not an SC address")
+        );
+    }
+
+    #[test]
+    fn test_unsafe_random() {
+        let interface = interface();
+        let first = interface.unsafe_random().unwrap();
+        assert_ne!(interface.unsafe_random().unwrap(), first);
+        assert!((0.0..1.0).contains(&interface.unsafe_random_f64().unwrap()));
+        // Each test starts with the seed: same numbers
+        interface.set_random_seed(0);
+        assert_eq!(interface.unsafe_random().unwrap(), first);
+        interface.set_random_seed(1);
+        assert_ne!(interface.unsafe_random().unwrap(), first);
+    }
+
+    #[test]
+    fn test_cheatcodes_only_when_imported() {
+        let address = format!("{CHEATCODE_PREFIX}snapshot");
+        assert_eq!(
+            interface().raw_get_data_for(&address, b"").unwrap(),
+            0u64.to_le_bytes()
+        );
+        // get_data_for of a Massa node
+        assert_eq!(
+            error(interface_with_cheatcodes(false).raw_get_data_for(&address, b"")),
+            "address parsing error"
+        );
+    }
+
+    #[test]
+    fn test_try_call_errors() {
+        let interface = interface();
+        let try_call = |address: &str, coins: u64| {
+            let mut args = Vec::new();
+            for arg in [address.as_bytes(), b"f", b""] {
+                args.extend_from_slice(&(arg.len() as u32).to_le_bytes());
+                args.extend_from_slice(arg);
+            }
+            args.extend_from_slice(&coins.to_le_bytes());
+            let address = format!("{CHEATCODE_PREFIX}{TRY_CALL_CHEATCODE}");
+            interface.raw_get_data_for(&address, &args).unwrap()
+        };
+        let depth = interface.context().call_depth();
+
+        // The failure is returned to the unit test
+        let message = format!("called address {CALLER_ADDRESS} is not an SC address");
+        assert_eq!(try_call(CALLER_ADDRESS, 0), TryCall::result(Err(message)));
+        let address = interface.create_module(b"\0asm\x01\0\0\0").unwrap();
+        assert_eq!(try_call(&address, 2 * DEFAULT_BALANCE)[0], 0);
+        assert_eq!(interface.context().call_depth(), depth);
+        assert_eq!(interface.get_balance_for(&address).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_not_supported() {
+        let interface = interface();
+        assert_eq!(
+            error(interface.get_op_data(b"key")),
+            "get_op_data not supported by massa_sc_runner"
+        );
+        assert_eq!(
+            error(interface.hash_sha256(b"massa")),
+            "hash_sha256 not supported by massa_sc_runner"
+        );
+        assert_eq!(
+            error(interface.deferred_call_exists("D1")),
+            "deferred_call_exists not supported by massa_sc_runner"
+        );
+        // wasmv1 ABI (massa_rust_sc wasmv1 feature, experimental)
+        assert_eq!(
+            error(interface.get_op_keys_wasmv1(b"")),
+            "get_op_keys_wasmv1 not supported by massa_sc_runner"
+        );
+        assert_eq!(
+            interface.bytes_to_base58_check_wasmv1(b"massa"),
+            "2Pkc9jRbEPHNc"
+        );
+        // The hash of the Massa node (blake3)
+        assert_eq!(
+            interface.hash(b"massa").unwrap(),
+            *blake3::hash(b"massa").as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_missing_data_errors() {
+        let interface = interface();
+        assert_eq!(
+            error(interface.raw_get_data(b"key")),
+            "data entry not found"
+        );
+        // A new smart contract has no datastore entry
+        let address = interface.create_module(b"\0asm\x01\0\0\0").unwrap();
+        assert!(interface.validate_address(&address).unwrap());
+        assert_eq!(
+            error(interface.raw_get_data_for(&address, b"key")),
+            "data entry not found"
+        );
+        // Unknown address (no ledger entry)
+        interface.context().ledger = Default::default();
+        assert_eq!(interface.get_balance_for(CALLER_ADDRESS).unwrap(), 0);
+        assert_eq!(
+            error(interface.get_keys_for(CALLER_ADDRESS, None)),
+            "data entry not found"
+        );
+        assert_eq!(
+            error(interface.raw_get_bytecode_for(CALLER_ADDRESS)),
+            "bytecode not found"
+        );
+        assert_eq!(
+            error(interface.raw_delete_data(b"key")),
+            format!(
+                "Runtime error: could not delete data entry [107, 101, 121] for address {CONTRACT_ADDRESS}: entry or address does not exist"
+            )
+        );
+        assert_eq!(
+            error(interface.raw_append_data(b"key", b"value")),
+            format!(
+                "Runtime error: appending to the datastore of address {CONTRACT_ADDRESS} failed: entry [107, 101, 121] not found"
+            )
+        );
+    }
+
+    #[test]
+    fn test_write_and_transfer_errors() {
+        let interface = interface();
+        assert_eq!(
+            error(interface.raw_set_data_for(CALLER_ADDRESS, b"key", b"value")),
+            format!(
+                "Runtime error: writing in the datastore of address {CALLER_ADDRESS} is not allowed in this context"
+            )
+        );
+        assert_eq!(
+            error(interface.raw_set_bytecode_for(CALLER_ADDRESS, b"")),
+            format!(
+                "Runtime error: setting the bytecode of address {CALLER_ADDRESS} is not allowed in this context"
+            )
+        );
+        assert_eq!(
+            error(interface.transfer_coins_for(CALLER_ADDRESS, CONTRACT_ADDRESS, 1)),
+            format!(
+                "Runtime error: spending from address {CALLER_ADDRESS} is not allowed in this context"
+            )
+        );
+        assert_eq!(
+            error(interface.transfer_coins(CALLER_ADDRESS, 2 * DEFAULT_BALANCE)),
+            format!(
+                "Runtime error: failed to transfer 2000 from spending address {CONTRACT_ADDRESS} due to insufficient balance 1000"
+            )
+        );
+        // The failed calls have no effect
+        assert_eq!(interface.get_balance().unwrap(), DEFAULT_BALANCE);
+        assert!(interface.get_keys(None).unwrap().is_empty());
+    }
+}
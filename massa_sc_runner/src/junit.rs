@@ -0,0 +1,170 @@
+// std
+use std::fmt::Write;
+use std::path::Path;
+use std::time::Duration;
+// internal
+use crate::report::{Summary, TestOutcome, TestResult};
+
+/// Escape a string for a xml attribute or text
+fn xml_escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '\'' => res.push_str("&apos;"),
+            // Control characters are not allowed in xml 1.0
+            c if (c as u32) < 0x20 && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => res.push(c),
+        }
+    }
+    res
+}
+
+/// JUnit XML report of a test run
+///
+/// The gas consumed is stored as a property of the test case, the emitted events
+/// (one per line) in system-out (after the captured output of a failed test).
+pub(crate) fn to_junit(
+    suite_name: &str,
+    results: &[TestResult],
+    summary: &Summary,
+    duration: Duration,
+) -> String {
+    let suite_name = xml_escape(suite_name);
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push('\n');
+    xml.push_str("<testsuites>\n");
+    // Unwrap safe: writing to a String cannot fail
+    writeln!(
+        xml,
+        r#"  <testsuite name="{suite_name}" tests="{}" failures="{}" errors="0" skipped="{}" time="{:.3}">"#,
+        results.len(),
+        summary.failed,
+        summary.ignored,
+        duration.as_secs_f64()
+    )
+    .unwrap();
+
+    for result in results.iter() {
+        write!(
+            xml,
+            r#"    <testcase classname="{suite_name}" name="{}" time="{:.3}""#,
+            xml_escape(&result.name),
+            result.duration.as_secs_f64()
+        )
+        .unwrap();
+        if result.outcome == TestOutcome::Ignored {
+            xml.push_str(">\n      <skipped/>\n    </testcase>\n");
+            continue;
+        }
+        xml.push_str(">\n");
+
+        if let Some(gas) = result.gas {
+            writeln!(
+                xml,
+                r#"      <properties><property name="gas" value="{gas}"/></properties>"#
+            )
+            .unwrap();
+        }
+        let mut system_out = String::new();
+        if let TestOutcome::Failed(msg) = &result.outcome {
+            writeln!(
+                xml,
+                r#"      <failure message="{}">{}</failure>"#,
+                xml_escape(msg),
+                xml_escape(msg)
+            )
+            .unwrap();
+            system_out.push_str(&result.output);
+        }
+        for event in result.events.iter() {
+            writeln!(system_out, "event: {event}").unwrap();
+        }
+        if !system_out.is_empty() {
+            writeln!(
+                xml,
+                "      <system-out>{}</system-out>",
+                xml_escape(&system_out)
+            )
+            .unwrap();
+        }
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n");
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// Write the JUnit XML report of a test run
+pub(crate) fn write_junit(
+    path: &Path,
+    suite_name: &str,
+    results: &[TestResult],
+    summary: &Summary,
+    duration: Duration,
+) -> std::io::Result<()> {
+    std::fs::write(path, to_junit(suite_name, results, summary, duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_junit() {
+        let results = vec![
+            TestResult {
+                name: "test_greet".to_string(),
+                outcome: TestOutcome::Passed,
+                output: String::new(),
+                duration: Duration::from_millis(12),
+                gas: Some(4_200),
+                events: vec!["Hello, Massa!".to_string()],
+            },
+            TestResult {
+                name: "test_hello".to_string(),
+                outcome: TestOutcome::Failed("error: a < b".to_string()),
+                output: "[raw_get_data] key: [1]\n".to_string(),
+                duration: Duration::from_millis(3),
+                gas: None,
+                events: vec![],
+            },
+            TestResult::ignored("test_slow".to_string()),
+        ];
+        let summary = Summary::new(&results, 0);
+
+        let expected = concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            "\n",
+            "<testsuites>\n",
+            r#"  <testsuite name="hello_world" tests="3" failures="1" errors="0" skipped="1" time="0.020">"#,
+            "\n",
+            r#"    <testcase classname="hello_world" name="test_greet" time="0.012">"#,
+            "\n",
+            r#"      <properties><property name="gas" value="4200"/></properties>"#,
+            "\n",
+            "      <system-out>event: Hello, Massa!\n</system-out>\n",
+            "    </testcase>\n",
+            r#"    <testcase classname="hello_world" name="test_hello" time="0.003">"#,
+            "\n",
+            r#"      <failure message="error: a &lt; b">error: a &lt; b</failure>"#,
+            "\n",
+            "      <system-out>[raw_get_data] key: [1]\n</system-out>\n",
+            "    </testcase>\n",
+            r#"    <testcase classname="hello_world" name="test_slow" time="0.000">"#,
+            "\n",
+            "      <skipped/>\n",
+            "    </testcase>\n",
+            "  </testsuite>\n",
+            "</testsuites>\n",
+        );
+        assert_eq!(
+            to_junit("hello_world", &results, &summary, Duration::from_millis(20)),
+            expected
+        );
+    }
+}
@@ -0,0 +1,532 @@
+// std
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Cost of a byte of storage, in nanoMAS (see LEDGER_COST_PER_BYTE in massa node: 0.0001 MAS)
+pub(crate) const LEDGER_COST_PER_BYTE: u64 = 100_000;
+
+/// Cost of a new ledger entry (address), in nanoMAS: 0.001 MAS
+pub(crate) const LEDGER_ENTRY_BASE_COST: u64 = 1_000_000;
+
+/// Base size of a datastore entry (see LEDGER_ENTRY_DATASTORE_BASE_SIZE in massa node)
+pub(crate) const LEDGER_ENTRY_DATASTORE_BASE_SIZE: u64 = 4;
+
+/// Initial balance of the caller & of the smart contract (in nanoMAS): 1_000 MAS
+pub(crate) const DEFAULT_BALANCE: u64 = 1_000 * 1_000_000_000;
+
+/// Ledger errors (same messages as the speculative ledger of a Massa node, amounts in MAS)
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LedgerError {
+    #[error("Runtime error: spending address {address} not found")]
+    SpendingAddressNotFound { address: String },
+    #[error(
+        "Runtime error: failed to transfer {} from spending address {address} due to insufficient balance {}",
+        format_amount(.amount),
+        format_amount(.balance)
+    )]
+    InsufficientBalance {
+        amount: u64,
+        address: String,
+        balance: u64,
+    },
+    #[error(
+        "Runtime error: overflow in crediting address {address} balance {} due to adding {}",
+        format_amount(.balance),
+        format_amount(.amount)
+    )]
+    Overflow {
+        amount: u64,
+        address: String,
+        balance: u64,
+    },
+    #[error(
+        "Runtime error: cannot transfer coins to non-existing smart contract address {address}"
+    )]
+    NonExistingSc { address: String },
+    #[error(
+        "Runtime error: insufficient amount {} to create credited address {address}",
+        format_amount(.amount)
+    )]
+    InsufficientAmountToCreate { amount: u64, address: String },
+    #[error("Runtime error: could not create SC address {address}: target address already exists")]
+    AddressExists { address: String },
+    #[error("Runtime error: could not create SC address {address}: {error}")]
+    CreateSc {
+        address: String,
+        error: Box<LedgerError>,
+    },
+    #[error("Runtime error: could not set data for address {address}: entry does not exist")]
+    SetDataAddressNotFound { address: String },
+    #[error("Runtime error: could not set bytecode for address {address}: entry does not exist")]
+    SetBytecodeAddressNotFound { address: String },
+    #[error(
+        "Runtime error: appending to the datastore of address {address} failed: entry {key:?} not found"
+    )]
+    AppendDataEntryNotFound { address: String, key: Vec<u8> },
+    #[error(
+        "Runtime error: could not delete data entry {key:?} for address {address}: entry or address does not exist"
+    )]
+    DeleteDataEntryNotFound { address: String, key: Vec<u8> },
+}
+
+/// Format an amount in MAS (as the Amount of a Massa node, e.g. 1.5 or 1000)
+pub(crate) fn format_amount(amount: &u64) -> String {
+    let mas = amount / 1_000_000_000;
+    match amount % 1_000_000_000 {
+        0 => mas.to_string(),
+        nano => format!("{mas}.{nano:09}").trim_end_matches('0').to_string(),
+    }
+}
+
+/// Storage cost of a datastore entry (in nanoMAS)
+pub(crate) fn datastore_entry_cost(key: &[u8], value: &[u8]) -> u64 {
+    (LEDGER_ENTRY_DATASTORE_BASE_SIZE + key.len() as u64 + value.len() as u64)
+        .saturating_mul(LEDGER_COST_PER_BYTE)
+}
+
+/// Storage cost of a bytecode (in nanoMAS)
+pub(crate) fn bytecode_cost(bytecode: &[u8]) -> u64 {
+    (bytecode.len() as u64).saturating_mul(LEDGER_COST_PER_BYTE)
+}
+
+/// An address in the ledger
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct LedgerEntry {
+    /// Balance in nanoMAS
+    pub balance: u64,
+    /// Bytecode (empty for a user address)
+    pub bytecode: Vec<u8>,
+    pub datastore: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+/// Balances, bytecodes & datastores of the addresses (as the ledger of a Massa node)
+///
+/// An unknown address has a balance of 0, coins can be transferred to it (the entry is created).
+/// Storage costs are paid by the address writing (usually the smart contract being executed).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Ledger {
+    entries: BTreeMap<String, LedgerEntry>,
+}
+
+impl Ledger {
+    pub(crate) fn entry(&self, address: &str) -> Option<&LedgerEntry> {
+        self.entries.get(address)
+    }
+
+    fn entry_mut(&mut self, address: &str) -> Option<&mut LedgerEntry> {
+        self.entries.get_mut(address)
+    }
+
+    pub(crate) fn balance(&self, address: &str) -> u64 {
+        self.entry(address)
+            .map(|entry| entry.balance)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn set_balance(&mut self, address: &str, amount: u64) {
+        self.entries.entry(address.to_string()).or_default().balance = amount;
+    }
+
+    /// Transfer coins between 2 addresses
+    ///
+    /// from: None to mint coins (e.g. storage refund), to: None to burn coins (e.g. storage cost)
+    pub(crate) fn transfer(
+        &mut self,
+        from: Option<&str>,
+        to: Option<&str>,
+        amount: u64,
+    ) -> Result<(), LedgerError> {
+        // Check both sides before updating (no partial transfer)
+        let from_balance = match from {
+            Some(from) => {
+                let balance = self.entry(from).map(|entry| entry.balance).ok_or_else(|| {
+                    LedgerError::SpendingAddressNotFound {
+                        address: from.to_string(),
+                    }
+                })?;
+                let new_balance = balance.checked_sub(amount).ok_or_else(|| {
+                    LedgerError::InsufficientBalance {
+                        amount,
+                        address: from.to_string(),
+                        balance,
+                    }
+                })?;
+                Some((from, new_balance))
+            }
+            None => None,
+        };
+        // Note: a transfer to self leaves the balance unchanged (once checked)
+        if from.is_some() && from == to {
+            return Ok(());
+        }
+        let to_balance = match (to, to.and_then(|to| self.entry(to))) {
+            (Some(to), Some(entry)) => {
+                let new_balance =
+                    entry
+                        .balance
+                        .checked_add(amount)
+                        .ok_or_else(|| LedgerError::Overflow {
+                            amount,
+                            address: to.to_string(),
+                            balance: entry.balance,
+                        })?;
+                Some((to, new_balance))
+            }
+            (Some(to), None) if to.starts_with("AS") => {
+                return Err(LedgerError::NonExistingSc {
+                    address: to.to_string(),
+                });
+            }
+            // A new user address: the cost of the ledger entry is taken from the amount
+            (Some(to), None) => {
+                let new_balance = amount.checked_sub(LEDGER_ENTRY_BASE_COST).ok_or_else(|| {
+                    LedgerError::InsufficientAmountToCreate {
+                        amount,
+                        address: to.to_string(),
+                    }
+                })?;
+                Some((to, new_balance))
+            }
+            (None, _) => None,
+        };
+
+        if let Some((from, balance)) = from_balance {
+            self.set_balance(from, balance);
+        }
+        if let Some((to, balance)) = to_balance {
+            self.set_balance(to, balance);
+        }
+        Ok(())
+    }
+
+    /// Charge a storage cost difference to the payer (refunded if the new cost is lower)
+    fn charge_storage(
+        &mut self,
+        payer: &str,
+        old_cost: u64,
+        new_cost: u64,
+    ) -> Result<(), LedgerError> {
+        if new_cost > old_cost {
+            self.transfer(Some(payer), None, new_cost - old_cost)
+        } else {
+            self.transfer(None, Some(payer), old_cost - new_cost)
+        }
+    }
+
+    /// Create a smart contract address (the creator pays the entry & the bytecode storage costs)
+    pub(crate) fn create_sc_entry(
+        &mut self,
+        creator: &str,
+        address: &str,
+        bytecode: &[u8],
+    ) -> Result<(), LedgerError> {
+        if self.entries.contains_key(address) {
+            return Err(LedgerError::AddressExists {
+                address: address.to_string(),
+            });
+        }
+        self.transfer(
+            Some(creator),
+            None,
+            LEDGER_ENTRY_BASE_COST.saturating_add(bytecode_cost(bytecode)),
+        )
+        .map_err(|error| LedgerError::CreateSc {
+            address: address.to_string(),
+            error: Box::new(error),
+        })?;
+        self.entries.insert(
+            address.to_string(),
+            LedgerEntry {
+                bytecode: bytecode.to_vec(),
+                ..Default::default()
+            },
+        );
+        Ok(())
+    }
+
+    /// Add a smart contract deployed before the unit test (storage costs are not charged), with
+    /// a balance of DEFAULT_BALANCE
+    pub(crate) fn deploy(&mut self, address: &str, bytecode: &[u8]) -> Result<(), LedgerError> {
+        if self.entries.contains_key(address) {
+            return Err(LedgerError::AddressExists {
+                address: address.to_string(),
+            });
+        }
+        self.entries.insert(
+            address.to_string(),
+            LedgerEntry {
+                balance: DEFAULT_BALANCE,
+                bytecode: bytecode.to_vec(),
+                ..Default::default()
+            },
+        );
+        Ok(())
+    }
+
+    pub(crate) fn bytecode(&self, address: &str) -> Option<&[u8]> {
+        self.entry(address).map(|entry| entry.bytecode.as_slice())
+    }
+
+    /// Set the bytecode of an address (the bytecode size difference is charged to the payer)
+    pub(crate) fn set_bytecode(
+        &mut self,
+        payer: &str,
+        address: &str,
+        bytecode: &[u8],
+    ) -> Result<(), LedgerError> {
+        let not_found = || LedgerError::SetBytecodeAddressNotFound {
+            address: address.to_string(),
+        };
+        let old_cost = bytecode_cost(&self.entry(address).ok_or_else(not_found)?.bytecode);
+        self.charge_storage(payer, old_cost, bytecode_cost(bytecode))?;
+        self.entry_mut(address).ok_or_else(not_found)?.bytecode = bytecode.to_vec();
+        Ok(())
+    }
+
+    pub(crate) fn get_data(&self, address: &str, key: &[u8]) -> Option<&[u8]> {
+        self.entry(address)
+            .and_then(|entry| entry.datastore.get(key))
+            .map(|value| value.as_slice())
+    }
+
+    pub(crate) fn has_data(&self, address: &str, key: &[u8]) -> bool {
+        self.get_data(address, key).is_some()
+    }
+
+    /// Keys of the datastore of an address (with an optional prefix)
+    pub(crate) fn get_keys(&self, address: &str, prefix: Option<&[u8]>) -> BTreeSet<Vec<u8>> {
+        let prefix = prefix.unwrap_or_default();
+        self.entry(address)
+            .map(|entry| {
+                entry
+                    .datastore
+                    .keys()
+                    .filter(|key| key.starts_with(prefix))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Set a datastore entry of an address (the storage cost difference is charged to the payer)
+    pub(crate) fn set_data(
+        &mut self,
+        payer: &str,
+        address: &str,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(), LedgerError> {
+        let not_found = || LedgerError::SetDataAddressNotFound {
+            address: address.to_string(),
+        };
+        let old_cost = self
+            .entry(address)
+            .ok_or_else(not_found)?
+            .datastore
+            .get(key)
+            .map_or(0, |old_value| datastore_entry_cost(key, old_value));
+        self.charge_storage(payer, old_cost, datastore_entry_cost(key, value))?;
+        self.entry_mut(address)
+            .ok_or_else(not_found)?
+            .datastore
+            .insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    /// Append data to an existing datastore entry of an address
+    pub(crate) fn append_data(
+        &mut self,
+        payer: &str,
+        address: &str,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(), LedgerError> {
+        let mut new_value = self
+            .get_data(address, key)
+            .ok_or_else(|| LedgerError::AppendDataEntryNotFound {
+                address: address.to_string(),
+                key: key.to_vec(),
+            })?
+            .to_vec();
+        new_value.extend_from_slice(value);
+        self.set_data(payer, address, key, &new_value)
+    }
+
+    /// Delete a datastore entry of an address (the storage cost is refunded to the payer)
+    pub(crate) fn delete_data(
+        &mut self,
+        payer: &str,
+        address: &str,
+        key: &[u8],
+    ) -> Result<(), LedgerError> {
+        let old_value = self
+            .entry_mut(address)
+            .and_then(|entry| entry.datastore.remove(key))
+            .ok_or_else(|| LedgerError::DeleteDataEntryNotFound {
+                address: address.to_string(),
+                key: key.to_vec(),
+            })?;
+        self.charge_storage(payer, datastore_entry_cost(key, &old_value), 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x";
+    const BOB: &str = "AU1Yvq49utdezr496dHbRj3TMjqsCh2awggjfGraHoddE7XfEkpY";
+    const SC: &str = "AS1GFocKuZKiSr2Gcu8y69fraPEqs7xYWvEFikTKcThYC38sBFBH";
+
+    #[test]
+    fn test_transfer() {
+        let mut ledger = Ledger::default();
+        ledger.set_balance(ALICE, 100);
+        ledger.set_balance(SC, 0);
+
+        ledger.transfer(Some(ALICE), Some(SC), 30).unwrap();
+        assert_eq!(ledger.balance(ALICE), 70);
+        assert_eq!(ledger.balance(SC), 30);
+
+        assert_eq!(
+            ledger.transfer(Some(SC), Some(ALICE), 31),
+            Err(LedgerError::InsufficientBalance {
+                amount: 31,
+                address: SC.to_string(),
+                balance: 30
+            })
+        );
+        // Balances unchanged
+        assert_eq!(ledger.balance(ALICE), 70);
+        assert_eq!(ledger.balance(SC), 30);
+
+        ledger.transfer(Some(SC), Some(SC), 30).unwrap();
+        assert_eq!(ledger.balance(SC), 30);
+        ledger.transfer(Some(SC), None, 10).unwrap();
+        assert_eq!(ledger.balance(SC), 20);
+        ledger.set_balance(ALICE, u64::MAX);
+        assert!(matches!(
+            ledger.transfer(Some(SC), Some(ALICE), 1),
+            Err(LedgerError::Overflow { .. })
+        ));
+
+        // Unknown addresses
+        assert_eq!(
+            ledger.transfer(Some(BOB), Some(ALICE), 0),
+            Err(LedgerError::SpendingAddressNotFound {
+                address: BOB.to_string()
+            })
+        );
+        assert_eq!(
+            ledger.transfer(Some(ALICE), Some("AS1unknown"), 1),
+            Err(LedgerError::NonExistingSc {
+                address: "AS1unknown".to_string()
+            })
+        );
+        // The cost of the new ledger entry is taken from the amount
+        assert_eq!(
+            ledger.transfer(Some(ALICE), Some(BOB), LEDGER_ENTRY_BASE_COST - 1),
+            Err(LedgerError::InsufficientAmountToCreate {
+                amount: LEDGER_ENTRY_BASE_COST - 1,
+                address: BOB.to_string()
+            })
+        );
+        ledger.transfer(Some(ALICE), Some(BOB), 3_000_000).unwrap();
+        assert_eq!(ledger.balance(BOB), 3_000_000 - LEDGER_ENTRY_BASE_COST);
+    }
+
+    #[test]
+    fn test_error_messages() {
+        // See massa-execution-worker speculative_ledger.rs
+        let error = LedgerError::InsufficientBalance {
+            amount: 1_500_000_000,
+            address: SC.to_string(),
+            balance: 20,
+        };
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Runtime error: failed to transfer 1.5 from spending address {SC} due to insufficient balance 0.00000002"
+            )
+        );
+        let error = LedgerError::DeleteDataEntryNotFound {
+            address: SC.to_string(),
+            key: b"key".to_vec(),
+        };
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Runtime error: could not delete data entry [107, 101, 121] for address {SC}: entry or address does not exist"
+            )
+        );
+        assert_eq!(format_amount(&0), "0");
+        assert_eq!(format_amount(&DEFAULT_BALANCE), "1000");
+        assert_eq!(format_amount(&1), "0.000000001");
+    }
+
+    #[test]
+    fn test_storage_cost() {
+        let mut ledger = Ledger::default();
+        let cost = datastore_entry_cost(b"key", b"value");
+        assert_eq!(cost, (4 + 3 + 5) * LEDGER_COST_PER_BYTE);
+        ledger.set_balance(SC, cost);
+
+        ledger.set_data(SC, SC, b"key", b"value").unwrap();
+        assert_eq!(ledger.balance(SC), 0);
+        assert_eq!(
+            ledger.set_data(SC, SC, b"key", b"value_2"),
+            Err(LedgerError::InsufficientBalance {
+                amount: 2 * LEDGER_COST_PER_BYTE,
+                address: SC.to_string(),
+                balance: 0
+            })
+        );
+        assert_eq!(ledger.get_data(SC, b"key"), Some(b"value".as_slice()));
+        // Smaller value: refund
+        ledger.set_data(SC, SC, b"key", b"v").unwrap();
+        assert_eq!(ledger.balance(SC), 4 * LEDGER_COST_PER_BYTE);
+        // Delete: refund
+        ledger.delete_data(SC, SC, b"key").unwrap();
+        assert_eq!(ledger.balance(SC), cost);
+        assert!(!ledger.has_data(SC, b"key"));
+        assert!(matches!(
+            ledger.delete_data(SC, SC, b"key"),
+            Err(LedgerError::DeleteDataEntryNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_datastore() {
+        let mut ledger = Ledger::default();
+        ledger.set_balance(ALICE, DEFAULT_BALANCE);
+        ledger.create_sc_entry(ALICE, SC, b"\0asm").unwrap();
+        assert_eq!(
+            ledger.balance(ALICE),
+            DEFAULT_BALANCE - LEDGER_ENTRY_BASE_COST - 4 * LEDGER_COST_PER_BYTE
+        );
+        assert_eq!(ledger.bytecode(SC), Some(b"\0asm".as_slice()));
+        assert!(matches!(
+            ledger.create_sc_entry(ALICE, SC, b""),
+            Err(LedgerError::AddressExists { .. })
+        ));
+
+        // Alice pays the storage costs of the datastore of the smart contract
+        ledger.set_data(ALICE, SC, b"counter", b"1").unwrap();
+        ledger.append_data(ALICE, SC, b"counter", b"2").unwrap();
+        ledger.set_data(ALICE, SC, b"owner", b"alice").unwrap();
+        assert_eq!(ledger.get_data(SC, b"counter"), Some(b"12".as_slice()));
+        assert!(matches!(
+            ledger.append_data(ALICE, SC, b"unknown", b"2"),
+            Err(LedgerError::AppendDataEntryNotFound { .. })
+        ));
+        assert_eq!(
+            ledger.get_keys(SC, Some(b"c")),
+            BTreeSet::from([b"counter".to_vec()])
+        );
+        assert_eq!(ledger.get_keys(SC, None).len(), 2);
+        assert_eq!(ledger.balance(SC), 0);
+        assert!(matches!(
+            ledger.set_data(ALICE, "AS1unknown", b"key", b"value"),
+            Err(LedgerError::SetDataAddressNotFound { .. })
+        ));
+    }
+}
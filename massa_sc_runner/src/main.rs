@@ -1,12 +1,42 @@
+mod cache;
+mod cheatcodes;
+mod cli;
+mod context;
+mod gas;
 mod interface;
+mod junit;
+mod ledger;
+mod report;
+mod test_attrs;
 
 // std
+use std::collections::HashMap;
 use std::io::Write;
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+use std::time::Instant;
 // third-party
-use massa_sc_runtime::{Compiler, CondomLimits, GasCosts, Interface, RuntimeModule, run_function};
+use clap::Parser;
+use massa_sc_runtime::{CondomLimits, GasCosts, Interface, RuntimeModule, run_function};
 use tempfile::NamedTempFile;
 // internal
+use cache::{ModuleCache, ModuleRegistry, compile};
+use cheatcodes::{CHEATCODE_IMPORT, check_revert, imports_cheatcode};
+use cli::{Cli, GasRegressionMode};
+use gas::{check_regressions, load_baseline, print_gas_report, print_regressions, save_baseline};
 use interface::MassaScRunnerInterface;
+use junit::write_junit;
+use massa_wasm_tools::{
+    PostOptions, WasmToolsError, custom_section_data, exported_functions, imports_wasmv1_abi,
+    post_process, rename_imports,
+};
+use report::{
+    FAILURE_EXIT_CODE, TestOutcome, TestResult, print_header, print_list, print_result,
+    print_summary,
+};
+use test_attrs::{TEST_ATTRS_SECTION, TestAttrs, parse_test_attrs};
 
 const UNIT_TEST_PREFIX: &str = "__wasm_test_unit__";
 
@@ -15,104 +45,348 @@ const GAS_COSTS_FILE: &str = include_str!(concat!(
     "/resources/abi_gas_costs.json"
 ));
 
-fn main() {
-    // TODO: debug!
-    println!("args: {:?}", std::env::args());
-    // println!("Should run with wasm now...");
-    let wasm_file = std::env::args().nth(1).unwrap();
-    println!("Wasm file: {wasm_file}");
-    let test_filter = std::env::args().nth(2);
-    println!("Test filter: {test_filter:?}");
-
-    let limit = u64::MAX;
-
-    // Load gas costs
+/// Gas costs of the Massa ABI (see resources/abi_gas_costs.json)
+fn load_gas_costs() -> GasCosts {
     let mut temp_file = NamedTempFile::new().expect("Cannot create temp file");
     temp_file
         .write_all(GAS_COSTS_FILE.as_bytes())
         .expect("Cannot write to temp file");
     temp_file.flush().expect("Cannot flush temp file");
-    let temp_path = temp_file.path().to_path_buf();
     // Note: GasCosts can only be initialized from a file :-/
-    let gas_costs = GasCosts::new(temp_path).expect("Failed to load gas costs");
+    GasCosts::new(temp_file.path().to_path_buf()).expect("Failed to load gas costs")
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let format = cli.format();
+
+    let limit = cli.gas_limit;
 
-    // let module = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/wasm/basic_func.wasm"));
-    let bytecode = std::fs::read(wasm_file).unwrap();
+    let gas_costs = load_gas_costs();
 
-    // List wasm functions
-    // Note: cannot access Wasmer module (hidden in RuntimeModule struct from massa-sc-runtime)
-    //       so we need to do it manually
+    let bytecode = std::fs::read(&cli.wasm_file)
+        .unwrap_or_else(|e| panic!("Cannot read wasm file {}: {e}", cli.wasm_file.display()));
+    // Lower the wasm features not supported by Massa (e.g. unit tests built with a stable
+    // toolchain, see massa_wasm_tools)
+    let (bytecode, _report) = post_process(&bytecode, &PostOptions::default())
+        .unwrap_or_else(|e| panic!("Cannot load wasm file {}: {e}", cli.wasm_file.display()));
+    // The wasmv1 ABI (massa_rust_sc wasmv1 feature, experimental) is not simulated
+    if imports_wasmv1_abi(&bytecode)
+        .unwrap_or_else(|e| panic!("Cannot load wasm file {}: {e}", cli.wasm_file.display()))
+    {
+        panic!(
+            "Cannot run the unit tests of {}: the wasmv1 ABI is not supported by massa_sc_runner",
+            cli.wasm_file.display()
+        );
+    }
+    // Cheatcodes (massa_rust_sc testing feature)
+    let cheatcodes = imports_cheatcode(&bytecode)
+        .unwrap_or_else(|e| panic!("Cannot load wasm file {}: {e}", cli.wasm_file.display()));
+    let bytecode = rename_imports(&bytecode, &[CHEATCODE_IMPORT])
+        .unwrap_or_else(|e| panic!("Cannot load wasm file {}: {e}", cli.wasm_file.display()));
 
-    let unit_test_functions = get_wasm_functions(bytecode.as_slice(), test_filter);
+    let unit_tests = get_unit_tests(bytecode.as_slice())
+        .unwrap_or_else(|e| panic!("Cannot list unit tests {}: {e}", cli.wasm_file.display()));
+    let test_count = unit_tests.len();
+    let unit_tests: Vec<_> = unit_tests
+        .into_iter()
+        .filter(|t| cli.is_selected(t.name.as_str()))
+        .filter(|t| !cli.ignored || t.attrs.ignore)
+        .collect();
+    let filtered_out = test_count - unit_tests.len();
 
-    for f in unit_test_functions {
-        println!("Running unit test: {f}");
+    if cli.list {
+        let names: Vec<_> = unit_tests.iter().map(|t| t.name.as_str()).collect();
+        print_list(&names, format);
+        return;
+    }
 
-        let exec_limits = CondomLimits::default();
-        let interface: Box<dyn Interface> = Box::new(MassaScRunnerInterface::default());
+    let start = Instant::now();
+    // Compile once (or load the compiled module from the cache), instantiated for each test
+    let exec_limits = CondomLimits::default();
+    let module = match cli.cache_dir() {
+        Some(dir) => ModuleCache::new(dir, test_binary_name(&cli.wasm_file)).load(
+            &bytecode,
+            &gas_costs,
+            &exec_limits,
+        ),
+        None => compile(&bytecode, &gas_costs, &exec_limits),
+    }
+    .unwrap_or_else(|e| panic!("{e}"));
 
-        let runtime_module = RuntimeModule::new(
-            bytecode.as_slice(),
-            gas_costs.clone(),
-            Compiler::SP,
-            exec_limits.clone(),
-        )
-        .unwrap();
+    // Modules of the smart contracts called or deployed by the tests are added when needed
+    let modules = Arc::new(ModuleRegistry::new(gas_costs.clone(), exec_limits.clone()));
+    modules.insert(&bytecode, module.clone());
+
+    print_header(unit_tests.len(), format);
+    let tests = Tests {
+        unit_tests: &unit_tests,
+        bytecode: &bytecode,
+        module: &module,
+        modules: &modules,
+        cheatcodes,
+    };
+    let results = run_tests(&tests, &gas_costs, &exec_limits, limit, &cli);
+
+    let duration = start.elapsed();
+    let summary = print_summary(&results, filtered_out, duration, format);
+
+    // Gas report & regressions
+    let baseline = cli.gas_baseline.as_ref().map(|path| {
+        load_baseline(path)
+            .unwrap_or_else(|e| panic!("Cannot load gas baseline {}: {e}", path.display()))
+    });
+    if cli.gas_report {
+        print_gas_report(&results, baseline.as_ref(), format);
+    }
+    let gas_regression_failed = match &baseline {
+        Some(baseline) => {
+            let regressions = check_regressions(&results, baseline, cli.gas_threshold);
+            let fail = cli.gas_regression == GasRegressionMode::Fail;
+            print_regressions(&regressions, cli.gas_threshold, fail, format);
+            fail && !regressions.is_empty()
+        }
+        None => false,
+    };
+    if let Some(path) = &cli.save_gas_baseline {
+        save_baseline(path, &results)
+            .unwrap_or_else(|e| panic!("Cannot save gas baseline {}: {e}", path.display()));
+    }
+
+    if let Some(junit) = &cli.junit {
+        let suite_name = suite_name(&cli.wasm_file);
+        write_junit(junit, &suite_name, &results, &summary, duration)
+            .unwrap_or_else(|e| panic!("Cannot write JUnit report {}: {e}", junit.display()));
+    }
+    if !summary.success() || gas_regression_failed {
+        std::process::exit(FAILURE_EXIT_CODE);
+    }
+}
+
+/// Name of the unit tests wasm file, without extension (e.g. hello_world-1a2b)
+fn test_binary_name(wasm_file: &Path) -> String {
+    wasm_file
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Name of the test suite: the crate name (e.g. hello_world-1a2b.wasm -> hello_world)
+fn suite_name(wasm_file: &Path) -> String {
+    let stem = test_binary_name(wasm_file);
+    match stem.rsplit_once('-') {
+        Some((name, _hash)) => name.to_string(),
+        None => stem,
+    }
+}
 
-        let res = run_function(
+/// A unit test function (see wasm-test crate)
+struct UnitTest {
+    /// Exported function name (with the unit test prefix)
+    function: String,
+    /// Test name (as written in the source code)
+    name: String,
+    attrs: TestAttrs,
+}
+
+/// The unit tests to run & the compiled modules
+struct Tests<'a> {
+    unit_tests: &'a [UnitTest],
+    /// Bytecode of the unit tests module (deployed at CONTRACT_ADDRESS)
+    bytecode: &'a [u8],
+    module: &'a RuntimeModule,
+    modules: &'a Arc<ModuleRegistry>,
+    /// The unit tests import the cheatcode function (see imports_cheatcode)
+    cheatcodes: bool,
+}
+
+/// Run the unit tests (in parallel, see --test-threads), return the results in the tests order
+///
+/// The results are printed as soon as a test is done (as libtest)
+fn run_tests(
+    tests: &Tests,
+    gas_costs: &GasCosts,
+    exec_limits: &CondomLimits,
+    limit: u64,
+    cli: &Cli,
+) -> Vec<TestResult> {
+    let format = cli.format();
+    let next_test = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+    let unit_tests = tests.unit_tests;
+    let mut results: Vec<Option<TestResult>> = vec![None; unit_tests.len()];
+
+    std::thread::scope(|s| {
+        for _ in 0..cli.test_threads().min(unit_tests.len()) {
+            let module = tests.module.clone();
+            let tx = tx.clone();
+            let next_test = &next_test;
+            s.spawn(move || {
+                loop {
+                    let index = next_test.fetch_add(1, Ordering::Relaxed);
+                    let Some(test) = unit_tests.get(index) else {
+                        break;
+                    };
+                    let result = if test.attrs.ignore && !cli.run_ignored() {
+                        TestResult::ignored(test.name.clone())
+                    } else {
+                        run_test(
+                            module.clone(),
+                            tests,
+                            test,
+                            gas_costs,
+                            exec_limits,
+                            limit,
+                            cli,
+                        )
+                    };
+                    if tx.send((index, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        // Only the workers hold a sender now: the loop ends when all the tests are done
+        drop(tx);
+
+        for (index, result) in rx {
+            print_result(&result, format);
+            results[index] = Some(result);
+        }
+    });
+
+    results.into_iter().flatten().collect()
+}
+
+/// Run a unit test function in a new instance of the module (with a new ledger)
+#[allow(clippy::too_many_arguments)]
+fn run_test(
+    module: RuntimeModule,
+    tests: &Tests,
+    test: &UnitTest,
+    gas_costs: &GasCosts,
+    exec_limits: &CondomLimits,
+    limit: u64,
+    cli: &Cli,
+) -> TestResult {
+    let runner_interface = match MassaScRunnerInterface::new(
+        cli.nocapture,
+        tests.bytecode,
+        cli.call_coins,
+        tests.modules.clone(),
+        limit,
+        tests.cheatcodes,
+    ) {
+        Ok(runner_interface) => runner_interface,
+        Err(e) => {
+            return TestResult {
+                outcome: TestOutcome::Failed(format!("Cannot initialize the ledger: {e}")),
+                ..TestResult::ignored(test.name.clone())
+            };
+        }
+    };
+    runner_interface.set_random_seed(cli.random_seed);
+    let interface: Box<dyn Interface> = Box::new(runner_interface.clone());
+
+    // Note: a panic in the interface (a bug of the runner) is reported as a test failure
+    //       (even for a should_panic test)
+    let start = Instant::now();
+    let res = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        run_function(
             &*interface,
-            runtime_module,
-            f.as_str(),
+            module,
+            test.function.as_str(),
             &[],
             limit,
             gas_costs.clone(),
             exec_limits.clone(),
-        );
+        )
+        .map(|response| limit.saturating_sub(response.remaining_gas))
+        .map_err(|e| e.to_string())
+    }));
+    let duration = start.elapsed();
+    // The calls interrupted by the failure are reverted (as in a Massa node): the reported events
+    // are the ones of the calls that succeeded
+    if !matches!(res, Ok(Ok(_))) {
+        runner_interface.rollback_calls();
+    }
+
+    let expected_revert = runner_interface.expected_revert();
+    let gas = match (&res, &expected_revert) {
+        (Ok(Ok(gas)), None) => Some(*gas),
+        _ => None,
+    };
+    let outcome = match res {
+        Ok(res) => {
+            let res = res.map(|_gas| ());
+            let res = match expected_revert {
+                Some(expected) => check_revert(res, &expected),
+                None => res,
+            };
+            test.attrs.outcome(res)
+        }
+        Err(panic) => {
+            let msg = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            TestOutcome::Failed(format!("Runner panicked: {msg}"))
+        }
+    };
+
+    // Gas consumed is only reported for passed tests (e.g. not for a test that did not panic as
+    // expected), so it can be compared against a baseline
+    let gas = gas.filter(|_| outcome == TestOutcome::Passed);
 
-        println!("wasm vm res: {res:#?}");
+    TestResult {
+        name: test.name.clone(),
+        outcome,
+        output: runner_interface.captured_output(),
+        duration,
+        gas,
+        events: runner_interface.events(),
     }
 }
 
-fn get_wasm_functions(wasm_content: &[u8], test_filter: Option<String>) -> Vec<String> {
-    use wasmer::{Engine, ExternType, Module, Store};
-
-    let engine = Engine::default();
-    let store = Store::new(engine);
-    let module = Module::new(&store, wasm_content).unwrap();
-
-    // Debug: list all functions in wasm file
-    /*
-    module
-        .exports()
-        .for_each(|export| {
-            if let ExternType::Function(_f) = export.ty() {
-                println!("export name: --{}--", export.name());
-            }
-        });
-    */
-
-    module
-        .exports()
-        .filter_map(|export| {
-            if let ExternType::Function(_f) = export.ty()
-                && export.name().starts_with(UNIT_TEST_PREFIX)
-            {
-                if let Some(filter) = &test_filter {
-                    if export.name().contains(filter) {
-                        // Test name matched the test filter
-                        Some(export.name().to_string())
-                    } else {
-                        // Test name filtered out
-                        None
-                    }
-                } else {
-                    // No test_filter
-                    Some(export.name().to_string())
-                }
-            } else {
-                // Not a wasm function
-                None
-            }
+/// List the unit tests (exported functions with the unit test prefix), sorted by name
+fn get_unit_tests(wasm_content: &[u8]) -> Result<Vec<UnitTest>, WasmToolsError> {
+    // #[ignore] / #[should_panic] attributes
+    let mut test_attrs: HashMap<String, TestAttrs> =
+        custom_section_data(wasm_content, TEST_ATTRS_SECTION)?
+            .into_iter()
+            .flat_map(parse_test_attrs)
+            .collect();
+
+    let mut unit_tests: Vec<_> = exported_functions(wasm_content)?
+        .into_iter()
+        .filter_map(|function| {
+            // Not a unit test function if there is no prefix
+            let name = function.strip_prefix(UNIT_TEST_PREFIX)?.to_string();
+            let attrs = test_attrs.remove(&name).unwrap_or_default();
+            Some(UnitTest {
+                function,
+                name,
+                attrs,
+            })
         })
-        .collect()
+        .collect();
+    unit_tests.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(unit_tests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suite_name() {
+        assert_eq!(
+            suite_name(Path::new(
+                "target/wasm32-unknown-unknown/debug/deps/hello_world-1a2b3c.wasm"
+            )),
+            "hello_world"
+        );
+        assert_eq!(suite_name(Path::new("tests.wasm")), "tests");
+    }
 }
@@ -0,0 +1,268 @@
+// std
+use std::io::Write;
+use std::time::Duration;
+// third-party
+use serde_json::json;
+// internal
+use crate::cli::Format;
+
+/// Exit code used by libtest when some tests failed
+pub(crate) const FAILURE_EXIT_CODE: i32 = 101;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TestOutcome {
+    Passed,
+    /// Failure message (trap, abort message, runner error, ...)
+    Failed(String),
+    Ignored,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TestResult {
+    pub name: String,
+    pub outcome: TestOutcome,
+    /// Captured output (print, events, ...)
+    pub output: String,
+    pub duration: Duration,
+    /// Gas consumed (None if the test was not run or failed)
+    pub gas: Option<u64>,
+    /// Events emitted by the test
+    pub events: Vec<String>,
+}
+
+impl TestResult {
+    pub(crate) fn ignored(name: String) -> Self {
+        Self {
+            name,
+            outcome: TestOutcome::Ignored,
+            output: String::new(),
+            duration: Duration::ZERO,
+            gas: None,
+            events: vec![],
+        }
+    }
+
+    /// Test result as a libtest json event (with gas & events)
+    fn to_json(&self) -> serde_json::Value {
+        let mut value = json!({
+            "type": "test",
+            "name": self.name,
+            "event": match self.outcome {
+                TestOutcome::Passed => "ok",
+                TestOutcome::Failed(_) => "failed",
+                TestOutcome::Ignored => "ignored",
+            },
+        });
+        if self.outcome != TestOutcome::Ignored {
+            value["exec_time"] = json!(self.duration.as_secs_f64());
+            value["gas"] = json!(self.gas);
+            value["events"] = json!(self.events);
+        }
+        if let TestOutcome::Failed(msg) = &self.outcome {
+            value["message"] = json!(msg);
+            value["stdout"] = json!(self.output);
+        }
+        value
+    }
+}
+
+/// Print the tests (libtest style: test_name: test)
+pub(crate) fn print_list(names: &[&str], format: Format) {
+    for name in names.iter() {
+        println!("{name}: test");
+    }
+    if format == Format::Pretty {
+        println!();
+        println!("{} tests, 0 benchmarks", names.len());
+    }
+}
+
+/// Print the header (libtest style: running N tests)
+pub(crate) fn print_header(test_count: usize, format: Format) {
+    if format == Format::Json {
+        println!(
+            "{}",
+            json!({"type": "suite", "event": "started", "test_count": test_count})
+        );
+        return;
+    }
+    let plural = if test_count == 1 { "" } else { "s" };
+    println!();
+    println!("running {test_count} test{plural}");
+}
+
+/// Print the status of a test (libtest style: test name ... ok, or a single character if terse)
+pub(crate) fn print_result(result: &TestResult, format: Format) {
+    match format {
+        Format::Pretty => {
+            let status = match result.outcome {
+                TestOutcome::Passed => "ok",
+                TestOutcome::Failed(_) => "FAILED",
+                TestOutcome::Ignored => "ignored",
+            };
+            println!("test {} ... {status}", result.name);
+        }
+        Format::Terse => {
+            let status = match result.outcome {
+                TestOutcome::Passed => '.',
+                TestOutcome::Failed(_) => 'F',
+                TestOutcome::Ignored => 'i',
+            };
+            print!("{status}");
+            std::io::stdout().flush().ok();
+        }
+        Format::Json => println!("{}", result.to_json()),
+    }
+}
+
+/// Counts of a test run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Summary {
+    pub passed: usize,
+    pub failed: usize,
+    pub ignored: usize,
+    pub filtered_out: usize,
+}
+
+impl Summary {
+    pub(crate) fn new(results: &[TestResult], filtered_out: usize) -> Self {
+        let count = |f: fn(&TestOutcome) -> bool| results.iter().filter(|r| f(&r.outcome)).count();
+        Self {
+            passed: count(|o| *o == TestOutcome::Passed),
+            failed: count(|o| matches!(o, TestOutcome::Failed(_))),
+            ignored: count(|o| *o == TestOutcome::Ignored),
+            filtered_out,
+        }
+    }
+
+    pub(crate) fn success(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+/// Print the failure messages (with the captured output) & the summary line, return the summary
+pub(crate) fn print_summary(
+    results: &[TestResult],
+    filtered_out: usize,
+    duration: Duration,
+    format: Format,
+) -> Summary {
+    let summary = Summary::new(results, filtered_out);
+    if format == Format::Json {
+        println!(
+            "{}",
+            json!({
+                "type": "suite",
+                "event": if summary.success() { "ok" } else { "failed" },
+                "passed": summary.passed,
+                "failed": summary.failed,
+                "ignored": summary.ignored,
+                "measured": 0,
+                "filtered_out": summary.filtered_out,
+                "exec_time": duration.as_secs_f64(),
+            })
+        );
+        return summary;
+    }
+
+    if format == Format::Terse {
+        println!();
+    }
+
+    let failures: Vec<_> = results
+        .iter()
+        .filter_map(|r| match &r.outcome {
+            TestOutcome::Failed(msg) => Some((r, msg.as_str())),
+            _ => None,
+        })
+        .collect();
+
+    if !failures.is_empty() {
+        println!();
+        println!("failures:");
+        for (result, msg) in failures.iter() {
+            println!();
+            println!("---- {} stdout ----", result.name);
+            print!("{}", result.output);
+            println!("{msg}");
+        }
+        println!();
+        println!("failures:");
+        for (result, _) in failures.iter() {
+            println!("    {}", result.name);
+        }
+    }
+
+    println!();
+    println!(
+        "test result: {}. {} passed; {} failed; {} ignored; 0 measured; {} filtered out; finished in {:.2}s",
+        if summary.success() { "ok" } else { "FAILED" },
+        summary.passed,
+        summary.failed,
+        summary.ignored,
+        summary.filtered_out,
+        duration.as_secs_f64()
+    );
+    println!();
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(name: &str, outcome: TestOutcome) -> TestResult {
+        TestResult {
+            name: name.to_string(),
+            outcome,
+            output: String::new(),
+            duration: Duration::from_millis(5),
+            gas: Some(1_000),
+            events: vec!["Paused".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let results = vec![
+            result("a", TestOutcome::Passed),
+            result(
+                "b",
+                TestOutcome::Failed("RuntimeError: unreachable".to_string()),
+            ),
+            result("c", TestOutcome::Ignored),
+            result("d", TestOutcome::Passed),
+        ];
+        let summary = Summary::new(&results, 3);
+        assert_eq!(
+            summary,
+            Summary {
+                passed: 2,
+                failed: 1,
+                ignored: 1,
+                filtered_out: 3
+            }
+        );
+        assert!(!summary.success());
+        assert!(Summary::new(&results[..1], 0).success());
+    }
+
+    #[test]
+    fn test_result_json() {
+        let value = result("a", TestOutcome::Passed).to_json();
+        assert_eq!(
+            value,
+            json!({"type": "test", "name": "a", "event": "ok", "exec_time": 0.005, "gas": 1000, "events": ["Paused"]})
+        );
+
+        let value = result("b", TestOutcome::Failed("unreachable".to_string())).to_json();
+        assert_eq!(value["event"], "failed");
+        assert_eq!(value["message"], "unreachable");
+
+        let value = TestResult::ignored("c".to_string()).to_json();
+        assert_eq!(
+            value,
+            json!({"type": "test", "name": "c", "event": "ignored"})
+        );
+    }
+}
@@ -0,0 +1,139 @@
+// std
+use std::collections::HashMap;
+// third-party
+use serde::Deserialize;
+// internal
+use crate::report::TestOutcome;
+
+/// Name of the wasm custom section where the test attributes are stored
+/// (see massa_rust_sc::ignore & massa_rust_sc::should_panic)
+///
+/// Each entry is a json object followed by '\n'
+pub(crate) const TEST_ATTRS_SECTION: &str = "massa_test";
+
+/// Attributes of a unit test (#[ignore], #[should_panic])
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub(crate) struct TestAttrs {
+    #[serde(default)]
+    pub ignore: bool,
+    #[serde(default)]
+    pub should_panic: bool,
+    /// Expected substring of the failure message (should_panic only)
+    pub expected: Option<String>,
+}
+
+impl TestAttrs {
+    /// Outcome of a test given the result of the test function (Err: failure message)
+    pub(crate) fn outcome(&self, res: Result<(), String>) -> TestOutcome {
+        match (res, self.should_panic) {
+            (Ok(()), false) => TestOutcome::Passed,
+            (Err(msg), false) => TestOutcome::Failed(msg),
+            (Ok(()), true) => {
+                TestOutcome::Failed("note: test did not panic as expected".to_string())
+            }
+            (Err(msg), true) => match &self.expected {
+                Some(expected) if !msg.contains(expected.as_str()) => TestOutcome::Failed(format!(
+                    "note: panic did not contain expected string\n      panic message: {msg:?}\n expected substring: {expected:?}"
+                )),
+                _ => TestOutcome::Passed,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Entry {
+    test: String,
+    #[serde(flatten)]
+    attrs: TestAttrs,
+}
+
+/// Parse the content of the test attributes section, return: test name -> attributes
+///
+/// Note: a test can have several entries (one per attribute), they are merged
+pub(crate) fn parse_test_attrs(section: &[u8]) -> HashMap<String, TestAttrs> {
+    let mut res: HashMap<String, TestAttrs> = HashMap::new();
+    let entries = section
+        .split(|b| *b == b'\n')
+        .filter_map(|line| serde_json::from_slice::<Entry>(line).ok());
+    for entry in entries {
+        let attrs = res.entry(entry.test).or_default();
+        attrs.ignore |= entry.attrs.ignore;
+        attrs.should_panic |= entry.attrs.should_panic;
+        if entry.attrs.expected.is_some() {
+            attrs.expected = entry.attrs.expected;
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_test_attrs() {
+        let section = concat!(
+            r#"{"test":"test_slow","ignore":true}"#,
+            "\n",
+            r#"{"test":"test_overflow","should_panic":true,"expected":"SafeMath: addition overflow"}"#,
+            "\n",
+            r#"{"test":"test_overflow","ignore":true}"#,
+            "\n",
+            r#"{"test":"test_revert","should_panic":true}"#,
+            "\n",
+        );
+        let attrs = parse_test_attrs(section.as_bytes());
+        assert_eq!(attrs.len(), 3);
+        assert_eq!(
+            attrs["test_slow"],
+            TestAttrs {
+                ignore: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            attrs["test_overflow"],
+            TestAttrs {
+                ignore: true,
+                should_panic: true,
+                expected: Some("SafeMath: addition overflow".to_string()),
+            }
+        );
+        assert_eq!(
+            attrs["test_revert"],
+            TestAttrs {
+                should_panic: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_outcome() {
+        let abort =
+            || Err("error: SafeMath: addition overflow at massa_rust_sc:0 col: 0".to_string());
+        let attrs = TestAttrs::default();
+        assert_eq!(attrs.outcome(Ok(())), TestOutcome::Passed);
+        assert!(matches!(attrs.outcome(abort()), TestOutcome::Failed(_)));
+
+        let attrs = TestAttrs {
+            should_panic: true,
+            ..Default::default()
+        };
+        assert_eq!(attrs.outcome(abort()), TestOutcome::Passed);
+        assert!(matches!(attrs.outcome(Ok(())), TestOutcome::Failed(_)));
+
+        let attrs = TestAttrs {
+            should_panic: true,
+            expected: Some("addition overflow".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(attrs.outcome(abort()), TestOutcome::Passed);
+        let attrs = TestAttrs {
+            expected: Some("division by zero".to_string()),
+            ..attrs
+        };
+        assert!(matches!(attrs.outcome(abort()), TestOutcome::Failed(_)));
+    }
+}
@@ -0,0 +1,244 @@
+// third-party
+use wasmparser::ValType::{self, F64, I32, I64};
+
+/// Wasm module of the Massa ABI functions
+pub const MASSA_MODULE: &str = "massa";
+/// Wasm module of the AssemblyScript runtime functions (abort, trace, ...)
+pub const ENV_MODULE: &str = "env";
+
+/// A function that a smart contract can import
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AbiImport {
+    pub module: &'static str,
+    pub name: &'static str,
+    /// Signature (params, results)
+    pub signature: (&'static [ValType], &'static [ValType]),
+}
+
+const fn import(
+    module: &'static str,
+    name: &'static str,
+    params: &'static [ValType],
+    results: &'static [ValType],
+) -> AbiImport {
+    AbiImport {
+        module,
+        name,
+        signature: (params, results),
+    }
+}
+
+const fn massa(
+    name: &'static str,
+    params: &'static [ValType],
+    results: &'static [ValType],
+) -> AbiImport {
+    import(MASSA_MODULE, name, params, results)
+}
+
+/// Functions provided by the node for AssemblyScript smart contracts (see massa-sc-runtime)
+///
+/// Note: strings & arrays are pointers (i32), bool is an i32 and u64 an i64
+pub const AS_ABI_IMPORTS: &[AbiImport] = &[
+    // env (AssemblyScript runtime)
+    import(ENV_MODULE, "abort", &[I32, I32, I32, I32], &[]),
+    import(
+        ENV_MODULE,
+        "trace",
+        &[I32, I32, F64, F64, F64, F64, F64],
+        &[],
+    ),
+    import(ENV_MODULE, "seed", &[], &[F64]),
+    import(ENV_MODULE, "Date.now", &[], &[F64]),
+    import(ENV_MODULE, "console.log", &[I32], &[]),
+    import(ENV_MODULE, "console.info", &[I32], &[]),
+    import(ENV_MODULE, "console.warn", &[I32], &[]),
+    import(ENV_MODULE, "console.error", &[I32], &[]),
+    import(ENV_MODULE, "console.debug", &[I32], &[]),
+    // massa
+    massa("assembly_script_print", &[I32], &[]),
+    massa("assembly_script_call", &[I32, I32, I32, I64], &[I32]),
+    massa("assembly_script_get_remaining_gas", &[], &[I64]),
+    massa("assembly_script_create_sc", &[I32], &[I32]),
+    massa("assembly_script_caller_has_write_access", &[], &[I32]),
+    massa("assembly_script_function_exists", &[I32, I32], &[I32]),
+    massa("assembly_script_get_keys", &[I32], &[I32]),
+    massa("assembly_script_get_keys_for", &[I32, I32], &[I32]),
+    massa("assembly_script_get_op_keys", &[], &[I32]),
+    massa("assembly_script_get_op_keys_prefix", &[I32], &[I32]),
+    massa("assembly_script_has_op_key", &[I32], &[I32]),
+    massa("assembly_script_get_op_data", &[I32], &[I32]),
+    massa("assembly_script_set_data", &[I32, I32], &[]),
+    massa("assembly_script_set_data_for", &[I32, I32, I32], &[]),
+    massa("assembly_script_append_data", &[I32, I32], &[]),
+    massa("assembly_script_append_data_for", &[I32, I32, I32], &[]),
+    massa("assembly_script_get_data", &[I32], &[I32]),
+    massa("assembly_script_get_data_for", &[I32, I32], &[I32]),
+    massa("assembly_script_has_data", &[I32], &[I32]),
+    massa("assembly_script_has_data_for", &[I32, I32], &[I32]),
+    massa("assembly_script_delete_data", &[I32], &[]),
+    massa("assembly_script_delete_data_for", &[I32, I32], &[]),
+    massa("assembly_script_get_owned_addresses", &[], &[I32]),
+    massa("assembly_script_get_call_stack", &[], &[I32]),
+    massa("assembly_script_generate_event", &[I32], &[]),
+    massa("assembly_script_transfer_coins", &[I32, I64], &[]),
+    massa("assembly_script_transfer_coins_for", &[I32, I32, I64], &[]),
+    massa("assembly_script_get_balance", &[], &[I64]),
+    massa("assembly_script_get_balance_for", &[I32], &[I64]),
+    massa("assembly_script_hash", &[I32], &[I32]),
+    massa("assembly_script_hash_sha256", &[I32], &[I32]),
+    massa("assembly_script_keccak256_hash", &[I32], &[I32]),
+    massa("assembly_script_signature_verify", &[I32, I32, I32], &[I32]),
+    massa(
+        "assembly_script_evm_signature_verify",
+        &[I32, I32, I32],
+        &[I32],
+    ),
+    massa(
+        "assembly_script_evm_get_address_from_pubkey",
+        &[I32],
+        &[I32],
+    ),
+    massa(
+        "assembly_script_evm_get_pubkey_from_signature",
+        &[I32, I32],
+        &[I32],
+    ),
+    massa("assembly_script_is_address_eoa", &[I32], &[I32]),
+    massa("assembly_script_address_from_public_key", &[I32], &[I32]),
+    massa("assembly_script_validate_address", &[I32], &[I32]),
+    massa("assembly_script_unsafe_random", &[], &[I64]),
+    massa("assembly_script_unsafe_random_f64", &[], &[F64]),
+    massa("assembly_script_get_call_coins", &[], &[I64]),
+    massa("assembly_script_get_time", &[], &[I64]),
+    massa(
+        "assembly_script_send_message",
+        &[I32, I32, I64, I32, I64, I32, I64, I64, I64, I32, I32, I32],
+        &[],
+    ),
+    massa("assembly_script_get_origin_operation_id", &[], &[I32]),
+    massa("assembly_script_get_current_period", &[], &[I64]),
+    massa("assembly_script_get_current_thread", &[], &[I32]),
+    massa("assembly_script_set_bytecode", &[I32], &[]),
+    massa("assembly_script_set_bytecode_for", &[I32, I32], &[]),
+    massa("assembly_script_get_bytecode", &[], &[I32]),
+    massa("assembly_script_get_bytecode_for", &[I32], &[I32]),
+    massa("assembly_script_local_execution", &[I32, I32, I32], &[I32]),
+    massa("assembly_script_local_call", &[I32, I32, I32], &[I32]),
+    massa("assembly_script_chain_id", &[], &[I64]),
+    massa("assembly_script_deferred_call_exists", &[I32], &[I32]),
+    massa("assembly_script_deferred_call_cancel", &[I32], &[]),
+    // (period: u64, thread: u8, max_gas: u64, params_size: u64) -> (available, price) (Args)
+    massa(
+        "assembly_script_get_deferred_call_quote",
+        &[I64, I32, I64, I64],
+        &[I32],
+    ),
+    // (address, function, period: u64, thread: u8, max_gas: u64, params, coins: u64) -> id
+    massa(
+        "assembly_script_deferred_call_register",
+        &[I32, I32, I64, I32, I64, I32, I64],
+        &[I32],
+    ),
+];
+
+/// Functions provided by the node for wasmv1 smart contracts
+///
+/// All functions take a pointer to a protobuf request and return a pointer to the response
+pub const WASMV1_ABI_IMPORTS: &[&str] = &[
+    "abi_abort",
+    "abi_add_native_amount",
+    "abi_address_from_public_key",
+    "abi_append_ds_value",
+    "abi_base58_check_to_bytes",
+    "abi_bytes_to_base58_check",
+    "abi_call",
+    "abi_caller_has_write_access",
+    "abi_chain_id",
+    "abi_check_address",
+    "abi_check_native_amount",
+    "abi_check_pubkey",
+    "abi_check_signature",
+    "abi_checked_add_native_time",
+    "abi_checked_div_native_time",
+    "abi_checked_mul_native_time",
+    "abi_checked_scalar_div_native_time",
+    "abi_checked_sub_native_time",
+    "abi_compare_address",
+    "abi_compare_native_amount",
+    "abi_compare_native_time",
+    "abi_compare_pub_key",
+    "abi_create_sc",
+    "abi_deferred_call_cancel",
+    "abi_deferred_call_exists",
+    "abi_deferred_call_register",
+    "abi_delete_ds_entry",
+    "abi_div_rem_native_amount",
+    "abi_ds_entry_exists",
+    "abi_evm_get_address_from_pubkey",
+    "abi_evm_get_pubkey_from_signature",
+    "abi_evm_verify_signature",
+    "abi_function_exists",
+    "abi_generate_event",
+    "abi_get_address_category",
+    "abi_get_address_version",
+    "abi_get_balance",
+    "abi_get_bytecode",
+    "abi_get_call_coins",
+    "abi_get_call_stack",
+    "abi_get_current_slot",
+    "abi_get_deferred_call_quote",
+    "abi_get_ds_keys",
+    "abi_get_ds_value",
+    "abi_get_native_time",
+    "abi_get_op_data",
+    "abi_get_op_keys",
+    "abi_get_origin_operation_id",
+    "abi_get_owned_addresses",
+    "abi_get_pubkey_version",
+    "abi_get_remaining_gas",
+    "abi_get_signature_version",
+    "abi_hash_blake3",
+    "abi_hash_keccak256",
+    "abi_hash_sha256",
+    "abi_is_address_eoa",
+    "abi_local_call",
+    "abi_local_execution",
+    "abi_native_amount_from_string",
+    "abi_native_amount_to_string",
+    "abi_op_entry_exists",
+    "abi_scalar_div_rem_native_amount",
+    "abi_scalar_mul_native_amount",
+    "abi_send_async_message",
+    "abi_set_bytecode",
+    "abi_set_ds_value",
+    "abi_sub_native_amount",
+    "abi_transfer_coins",
+    "abi_unsafe_random",
+    "abi_verify_signature",
+];
+
+const WASMV1_PARAMS: &[ValType] = &[I32];
+const WASMV1_RESULTS: &[ValType] = &[I32];
+
+/// Find an import in the ABI functions (of AssemblyScript or wasmv1 smart contracts)
+pub fn find_import(module: &str, name: &str, wasmv1: bool) -> Option<AbiImport> {
+    if wasmv1 {
+        if module != MASSA_MODULE {
+            return None;
+        }
+        WASMV1_ABI_IMPORTS
+            .iter()
+            .find(|abi_name| **abi_name == name)
+            .map(|abi_name| AbiImport {
+                module: MASSA_MODULE,
+                name: abi_name,
+                signature: (WASMV1_PARAMS, WASMV1_RESULTS),
+            })
+    } else {
+        AS_ABI_IMPORTS
+            .iter()
+            .find(|import| import.module == module && import.name == name)
+            .copied()
+    }
+}
@@ -0,0 +1,765 @@
+//! Tools to make a wasm module (built with a stable Rust toolchain) compatible with Massa
+//!
+//! Recent Rust versions (1.82+) enable several wasm features by default (sign-ext,
+//! reference-types, multivalue, nontrapping-fptoint, ...) but Massa only accepts the
+//! MVP feature set + bulk memory. [post_process] lowers the unsupported instructions and
+//! [validate] checks that a smart contract can be deployed (features, imports & size).
+
+mod imports;
+mod lower;
+
+// third-party
+use wasm_encoder::reencode::Reencode;
+use wasmparser::{BinaryReaderError, FuncType, Parser, Payload, TypeRef, Validator, WasmFeatures};
+// internal
+pub use imports::{
+    AS_ABI_IMPORTS, AbiImport, ENV_MODULE, MASSA_MODULE, WASMV1_ABI_IMPORTS, find_import,
+};
+pub use lower::LoweringStats;
+use lower::{Lowering, TARGET_FEATURES_SECTION};
+
+/// Wasm features supported by Massa
+pub const MASSA_WASM_FEATURES: WasmFeatures = WasmFeatures::MVP.union(WasmFeatures::BULK_MEMORY);
+
+/// Custom section with the smart contract ABI (see massa_rust_sc::export)
+pub const ABI_SECTION: &str = "massa_abi";
+
+/// Max size of a smart contract bytecode (see MAX_BYTECODE_LENGTH in massa node)
+pub const MAX_BYTECODE_SIZE: usize = 10_000_000;
+
+/// First byte of a wasmv1 smart contract bytecode (AssemblyScript bytecode has no prefix)
+const WASMV1_VERSION: u8 = 1;
+
+#[derive(thiserror::Error, Debug)]
+pub enum WasmToolsError {
+    #[error("Invalid wasm module: {0}")]
+    Parse(#[from] BinaryReaderError),
+    #[error("Cannot rewrite wasm module: {0}")]
+    Reencode(#[from] wasm_encoder::reencode::Error),
+    #[error("Unsupported wasm feature (only MVP + bulk memory are supported): {0}")]
+    Unsupported(BinaryReaderError),
+    #[error("Bytecode is too large: {size} bytes (max: {MAX_BYTECODE_SIZE} bytes)")]
+    TooLarge { size: usize },
+    #[error("Unknown import: {module}.{name}")]
+    UnknownImport { module: String, name: String },
+    #[error("Invalid import: {module}.{name}, expected: {expected}, found: {found}")]
+    InvalidImport {
+        module: String,
+        name: String,
+        expected: String,
+        found: String,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PostOptions {
+    /// Remove the custom sections (name, producers, ...)
+    pub strip_custom_sections: bool,
+    /// Custom sections to keep when stripping (the ABI section is always kept)
+    pub keep_sections: Vec<String>,
+    /// Prefix a wasmv1 smart contract with the version byte expected by the node (see
+    /// [add_wasmv1_prefix]), e.g. not for the unit tests run by massa_sc_runner
+    pub wasmv1_prefix: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PostReport {
+    /// Size of the input module (in bytes)
+    pub input_size: usize,
+    /// Size of the output module (in bytes)
+    pub output_size: usize,
+    pub lowered: LoweringStats,
+    /// Custom sections removed
+    pub stripped_sections: Vec<String>,
+    /// The output is a wasmv1 smart contract (prefixed with 0x01)
+    pub wasmv1: bool,
+}
+
+/// Rewrite a wasm module so it can be deployed on Massa
+///
+/// * sign-ext instructions are replaced by shifts
+/// * saturating float to int conversions are replaced by calls to (added) helper functions
+/// * reference-types encodings are rewritten in their MVP form (e.g. call_indirect)
+/// * the target_features custom section is removed, other custom sections are removed
+///   if [PostOptions::strip_custom_sections] is set
+/// * a wasmv1 smart contract is prefixed with 0x01 if [PostOptions::wasmv1_prefix] is set
+///
+/// The wasm features of the output module are then validated (see [validate_features]).
+pub fn post_process(
+    bytecode: &[u8],
+    options: &PostOptions,
+) -> Result<(Vec<u8>, PostReport), WasmToolsError> {
+    let input_size = bytecode.len();
+    let (bytecode, _) = split_wasmv1_prefix(bytecode);
+    let mut keep_sections = options.keep_sections.clone();
+    keep_sections.push(ABI_SECTION.to_string());
+
+    let mut lowering = Lowering::new(
+        bytecode,
+        options
+            .strip_custom_sections
+            .then_some(keep_sections.as_slice()),
+    )?;
+    let mut module = wasm_encoder::Module::new();
+    lowering
+        .parse_core_module(&mut module, Parser::new(0), bytecode)
+        .map_err(|e| match e {
+            wasm_encoder::reencode::Error::ParseError(e) => WasmToolsError::Parse(e),
+            e => WasmToolsError::Reencode(e),
+        })?;
+    let output = module.finish();
+
+    validate_features(&output)?;
+    let output = if options.wasmv1_prefix {
+        add_wasmv1_prefix(output)?
+    } else {
+        output
+    };
+
+    let report = PostReport {
+        input_size,
+        output_size: output.len(),
+        lowered: lowering.stats,
+        stripped_sections: lowering.stripped_sections,
+        wasmv1: output.first() == Some(&WASMV1_VERSION),
+    };
+    Ok((output, report))
+}
+
+/// Split the version byte of a wasmv1 smart contract: (wasm module, is wasmv1)
+fn split_wasmv1_prefix(bytecode: &[u8]) -> (&[u8], bool) {
+    match bytecode.split_first() {
+        Some((&WASMV1_VERSION, bytecode)) => (bytecode, true),
+        _ => (bytecode, false),
+    }
+}
+
+/// Return true if the wasm module imports functions of the wasmv1 ABI (`massa.abi_*`, e.g. a
+/// smart contract built with the wasmv1 feature of massa_rust_sc)
+pub fn imports_wasmv1_abi(bytecode: &[u8]) -> Result<bool, WasmToolsError> {
+    for payload in Parser::new(0).parse_all(bytecode) {
+        if let Payload::ImportSection(section) = payload? {
+            for import in section {
+                let import = import?;
+                if import.module == MASSA_MODULE && WASMV1_ABI_IMPORTS.contains(&import.name) {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Prefix a wasmv1 smart contract with its version byte (0x01): the node only executes a
+/// bytecode with the wasmv1 ABI if it is prefixed (a wasm module starts with 0x00)
+///
+/// Other bytecode (AssemblyScript ABI or already prefixed) is returned unchanged.
+pub fn add_wasmv1_prefix(bytecode: Vec<u8>) -> Result<Vec<u8>, WasmToolsError> {
+    if split_wasmv1_prefix(&bytecode).1 || !imports_wasmv1_abi(&bytecode)? {
+        return Ok(bytecode);
+    }
+    let mut output = Vec::with_capacity(1 + bytecode.len());
+    output.push(WASMV1_VERSION);
+    output.extend_from_slice(&bytecode);
+    Ok(output)
+}
+
+/// Check that a wasm module only uses the features supported by Massa (MVP + bulk memory)
+pub fn validate_features(bytecode: &[u8]) -> Result<(), WasmToolsError> {
+    Validator::new_with_features(MASSA_WASM_FEATURES)
+        .validate_all(bytecode)
+        .map_err(WasmToolsError::Unsupported)?;
+    Ok(())
+}
+
+/// Check that all the imports are ABI functions (with the right signature)
+pub fn validate_imports(bytecode: &[u8], wasmv1: bool) -> Result<(), WasmToolsError> {
+    let mut types = vec![];
+    for payload in Parser::new(0).parse_all(bytecode) {
+        match payload? {
+            Payload::TypeSection(section) => {
+                for ty in section.into_iter_err_on_gc_types() {
+                    types.push(ty?);
+                }
+            }
+            Payload::ImportSection(section) => {
+                for import in section {
+                    let import = import?;
+                    check_import(&import, &types, wasmv1)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn check_import(
+    import: &wasmparser::Import,
+    types: &[FuncType],
+    wasmv1: bool,
+) -> Result<(), WasmToolsError> {
+    let abi_import = find_import(import.module, import.name, wasmv1).ok_or_else(|| {
+        WasmToolsError::UnknownImport {
+            module: import.module.to_string(),
+            name: import.name.to_string(),
+        }
+    })?;
+    let (params, results) = abi_import.signature;
+    let expected = FuncType::new(params.iter().copied(), results.iter().copied());
+    let found = match import.ty {
+        TypeRef::Func(index) => types.get(index as usize),
+        _ => None,
+    };
+    match found {
+        Some(found) if *found == expected => Ok(()),
+        found => Err(WasmToolsError::InvalidImport {
+            module: import.module.to_string(),
+            name: import.name.to_string(),
+            expected: expected.to_string(),
+            found: found
+                .map(|found| found.to_string())
+                .unwrap_or(format!("{:?}", import.ty)),
+        }),
+    }
+}
+
+/// Check that a smart contract can be deployed on Massa: bytecode size, wasm features & imports
+///
+/// wasmv1 bytecode (prefixed with 0x01) is supported
+pub fn validate(bytecode: &[u8]) -> Result<(), WasmToolsError> {
+    if bytecode.len() > MAX_BYTECODE_SIZE {
+        return Err(WasmToolsError::TooLarge {
+            size: bytecode.len(),
+        });
+    }
+    let (bytecode, wasmv1) = split_wasmv1_prefix(bytecode);
+    validate_features(bytecode)?;
+    validate_imports(bytecode, wasmv1)
+}
+
+/// Return true if the module has a target_features custom section (e.g. not yet post processed)
+pub fn has_target_features(bytecode: &[u8]) -> bool {
+    Parser::new(0).parse_all(bytecode).any(|payload| {
+        matches!(payload, Ok(wasmparser::Payload::CustomSection(section)) if section.name() == TARGET_FEATURES_SECTION)
+    })
+}
+
+/// Names of the exported functions (in the export section order)
+pub fn exported_functions(bytecode: &[u8]) -> Result<Vec<String>, WasmToolsError> {
+    let mut functions = vec![];
+    for payload in Parser::new(0).parse_all(bytecode) {
+        if let Payload::ExportSection(section) = payload? {
+            for export in section {
+                let export = export?;
+                if export.kind == wasmparser::ExternalKind::Func {
+                    functions.push(export.name.to_string());
+                }
+            }
+        }
+    }
+    Ok(functions)
+}
+
+/// Imported functions: (module, name) (in the import section order)
+pub fn imported_functions(bytecode: &[u8]) -> Result<Vec<(String, String)>, WasmToolsError> {
+    let mut functions = vec![];
+    for payload in Parser::new(0).parse_all(bytecode) {
+        if let Payload::ImportSection(section) = payload? {
+            for import in section {
+                let import = import?;
+                if matches!(import.ty, TypeRef::Func(_)) {
+                    functions.push((import.module.to_string(), import.name.to_string()));
+                }
+            }
+        }
+    }
+    Ok(functions)
+}
+
+/// Content of the custom sections with the given name (a section can appear several times)
+pub fn custom_section_data<'a>(
+    bytecode: &'a [u8],
+    name: &str,
+) -> Result<Vec<&'a [u8]>, WasmToolsError> {
+    let mut sections = vec![];
+    for payload in Parser::new(0).parse_all(bytecode) {
+        if let Payload::CustomSection(section) = payload?
+            && section.name() == name
+        {
+            sections.push(section.data());
+        }
+    }
+    Ok(sections)
+}
+
+/// An import rename: `(module, name)` -> `(new module, new name)`
+pub type ImportRename<'a> = ((&'a str, &'a str), (&'a str, &'a str));
+
+/// Rename the imported functions: `(module, name)` -> `(new module, new name)` (the signatures
+/// are kept, e.g. to redirect an import to an ABI function with the same signature)
+pub fn rename_imports(
+    bytecode: &[u8],
+    renames: &[ImportRename<'_>],
+) -> Result<Vec<u8>, WasmToolsError> {
+    let mut module = wasm_encoder::Module::new();
+    RenameImports { renames }
+        .parse_core_module(&mut module, Parser::new(0), bytecode)
+        .map_err(|e| match e {
+            wasm_encoder::reencode::Error::ParseError(e) => WasmToolsError::Parse(e),
+            e => WasmToolsError::Reencode(e),
+        })?;
+    Ok(module.finish())
+}
+
+struct RenameImports<'a> {
+    renames: &'a [ImportRename<'a>],
+}
+
+impl Reencode for RenameImports<'_> {
+    type Error = std::convert::Infallible;
+
+    fn parse_import(
+        &mut self,
+        imports: &mut wasm_encoder::ImportSection,
+        import: wasmparser::Import<'_>,
+    ) -> Result<(), wasm_encoder::reencode::Error<Self::Error>> {
+        let (module, name) = self
+            .renames
+            .iter()
+            .find(|(from, _to)| *from == (import.module, import.name))
+            .map_or((import.module, import.name), |(_from, to)| *to);
+        imports.import(module, name, self.entity_type(import.ty)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use wasm_encoder::{
+        CodeSection, CustomSection, EntityType, ExportKind, ExportSection, Function,
+        FunctionSection, ImportSection, Instruction, Module, TypeSection, ValType,
+    };
+
+    /// A module with: (func (param ..) (result ..) local.get 0 <ops>...) exported as "f"
+    /// and some custom sections
+    fn module(params: &[ValType], results: &[ValType], ops: &[Instruction]) -> Vec<u8> {
+        let mut module = Module::new();
+        let mut types = TypeSection::new();
+        types.ty().function(params.to_vec(), results.to_vec());
+        module.section(&types);
+        let mut functions = FunctionSection::new();
+        functions.function(0);
+        module.section(&functions);
+        let mut exports = ExportSection::new();
+        exports.export("f", ExportKind::Func, 0);
+        module.section(&exports);
+        let mut code = CodeSection::new();
+        let mut f = Function::new([]);
+        f.instruction(&Instruction::LocalGet(0));
+        for op in ops {
+            f.instruction(op);
+        }
+        f.instruction(&Instruction::End);
+        code.function(&f);
+        module.section(&code);
+        module.section(&CustomSection {
+            name: TARGET_FEATURES_SECTION.into(),
+            data: b"\x01+\x08sign-ext".into(),
+        });
+        module.section(&CustomSection {
+            name: ABI_SECTION.into(),
+            data: b"{}".into(),
+        });
+        module.section(&CustomSection {
+            name: "producers".into(),
+            data: b"\x00".into(),
+        });
+        module.finish()
+    }
+
+    fn custom_sections(bytecode: &[u8]) -> Vec<String> {
+        Parser::new(0)
+            .parse_all(bytecode)
+            .filter_map(|payload| match payload.unwrap() {
+                wasmparser::Payload::CustomSection(section) => Some(section.name().to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_post_process_sign_ext() {
+        let wasm = module(
+            &[ValType::I32],
+            &[ValType::I64],
+            &[
+                Instruction::I32Extend8S,
+                Instruction::I64ExtendI32U,
+                Instruction::I64Extend32S,
+            ],
+        );
+        assert!(matches!(
+            validate(&wasm),
+            Err(WasmToolsError::Unsupported(_))
+        ));
+        assert!(has_target_features(&wasm));
+
+        let (output, report) = post_process(&wasm, &PostOptions::default()).unwrap();
+        assert!(validate(&output).is_ok());
+        assert!(!has_target_features(&output));
+        assert_eq!(report.lowered.sign_ext, 2);
+        assert_eq!(report.output_size, output.len());
+        assert_eq!(custom_sections(&output), vec![ABI_SECTION, "producers"]);
+    }
+
+    #[test]
+    fn test_exports_and_sections() {
+        let wasm = module(&[ValType::I32], &[ValType::I32], &[]);
+        assert_eq!(exported_functions(&wasm).unwrap(), vec!["f"]);
+        assert_eq!(
+            custom_section_data(&wasm, ABI_SECTION).unwrap(),
+            vec![b"{}".as_slice()]
+        );
+        assert!(custom_section_data(&wasm, "massa_test").unwrap().is_empty());
+    }
+
+    #[test]
+    #[ignore = "builds a smart contract (requires the wasm32-unknown-unknown target)"]
+    fn test_contract_abi_section() {
+        // The ABI entries emitted by #[export] (massa_rust_sc_macros) are kept by the linker and
+        // by post_process
+        let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+        let status = Command::new(cargo)
+            .current_dir(workspace)
+            .args(["build", "--release", "--target", "wasm32-unknown-unknown"])
+            .args(["-p", "hello_world"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let target_dir =
+            std::env::var_os("CARGO_TARGET_DIR").map_or(workspace.join("target"), PathBuf::from);
+        let wasm = target_dir.join("wasm32-unknown-unknown/release/hello_world.wasm");
+        let wasm = std::fs::read(wasm).unwrap();
+
+        let options = PostOptions {
+            strip_custom_sections: true,
+            ..Default::default()
+        };
+        let (output, _) = post_process(&wasm, &options).unwrap();
+        let abi = custom_section_data(&output, ABI_SECTION).unwrap().concat();
+        let abi = String::from_utf8(abi).unwrap();
+        assert!(abi.lines().any(|entry| entry
+            == r#"{"type":"function","name":"greet","inputs":[{"name":"name","type":"string"}],"outputs":[{"type":"string"}]}"#));
+    }
+
+    #[test]
+    fn test_post_process_trunc_sat() {
+        let wasm = module(
+            &[ValType::F64],
+            &[ValType::I32],
+            &[
+                Instruction::I32TruncSatF64S,
+                Instruction::F64ConvertI32S,
+                Instruction::I32TruncSatF64U,
+            ],
+        );
+        assert!(validate(&wasm).is_err());
+
+        let options = PostOptions {
+            strip_custom_sections: true,
+            ..Default::default()
+        };
+        let (output, report) = post_process(&wasm, &options).unwrap();
+        assert!(validate(&output).is_ok());
+        assert_eq!(report.lowered.trunc_sat, 2);
+        assert_eq!(
+            report.stripped_sections,
+            vec![TARGET_FEATURES_SECTION, "producers"]
+        );
+        assert_eq!(custom_sections(&output), vec![ABI_SECTION]);
+
+        // 2 helper functions added (after the exported function)
+        let functions =
+            Parser::new(0)
+                .parse_all(&output)
+                .find_map(|payload| match payload.unwrap() {
+                    wasmparser::Payload::FunctionSection(section) => Some(section.count()),
+                    _ => None,
+                });
+        assert_eq!(functions, Some(3));
+    }
+
+    /// Lower `op` (a trunc_sat instruction) and run the helper function with wasmi for each input,
+    /// the result must be the same as the Rust cast (saturating, NaN -> 0)
+    fn check_trunc_sat<P, R>(
+        op: Instruction,
+        (param, result): (ValType, ValType),
+        inputs: &[P],
+        cast: fn(P) -> R,
+    ) where
+        P: wasmi::WasmParams + Copy + std::fmt::Debug,
+        R: wasmi::WasmResults + PartialEq + std::fmt::Debug,
+    {
+        let wasm = module(&[param], &[result], &[op]);
+        let (output, report) = post_process(&wasm, &PostOptions::default()).unwrap();
+        assert!(validate(&output).is_ok());
+        assert_eq!(report.lowered.trunc_sat, 1);
+
+        let engine = wasmi::Engine::default();
+        let module = wasmi::Module::new(&engine, &output).unwrap();
+        let mut store = wasmi::Store::new(&engine, ());
+        let instance = wasmi::Linker::<()>::new(&engine)
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        let f = instance.get_typed_func::<P, R>(&store, "f").unwrap();
+        for input in inputs {
+            assert_eq!(
+                f.call(&mut store, *input).unwrap(),
+                cast(*input),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_trunc_sat_helpers() {
+        let inputs = [
+            f64::NAN,
+            -f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            0.0,
+            -0.0,
+            0.99,
+            -0.5,
+            -0.99,
+            -1.0,
+            -1.5,
+            42.7,
+            -42.7,
+            // largest f32 below 2^31
+            2f64.powi(31) - 128.0,
+            2f64.powi(31) - 1.0,
+            2f64.powi(31),
+            -2f64.powi(31),
+            -2f64.powi(31) - 1.0,
+            2f64.powi(32) - 1.0,
+            2f64.powi(32),
+            2f64.powi(63),
+            -2f64.powi(63),
+            2f64.powi(64),
+            1e30,
+            -1e30,
+            f64::MAX,
+            f64::MIN,
+            f64::MIN_POSITIVE,
+        ];
+        let inputs_f32: Vec<f32> = inputs.iter().map(|x| *x as f32).collect();
+
+        // Note: unsigned results are returned as i32 / i64 (same bits)
+        check_trunc_sat(
+            Instruction::I32TruncSatF32S,
+            (ValType::F32, ValType::I32),
+            &inputs_f32,
+            |x| x as i32,
+        );
+        check_trunc_sat(
+            Instruction::I32TruncSatF32U,
+            (ValType::F32, ValType::I32),
+            &inputs_f32,
+            |x| x as u32 as i32,
+        );
+        check_trunc_sat(
+            Instruction::I32TruncSatF64S,
+            (ValType::F64, ValType::I32),
+            &inputs,
+            |x| x as i32,
+        );
+        check_trunc_sat(
+            Instruction::I32TruncSatF64U,
+            (ValType::F64, ValType::I32),
+            &inputs,
+            |x| x as u32 as i32,
+        );
+        check_trunc_sat(
+            Instruction::I64TruncSatF32S,
+            (ValType::F32, ValType::I64),
+            &inputs_f32,
+            |x| x as i64,
+        );
+        check_trunc_sat(
+            Instruction::I64TruncSatF32U,
+            (ValType::F32, ValType::I64),
+            &inputs_f32,
+            |x| x as u64 as i64,
+        );
+        check_trunc_sat(
+            Instruction::I64TruncSatF64S,
+            (ValType::F64, ValType::I64),
+            &inputs,
+            |x| x as i64,
+        );
+        check_trunc_sat(
+            Instruction::I64TruncSatF64U,
+            (ValType::F64, ValType::I64),
+            &inputs,
+            |x| x as u64 as i64,
+        );
+    }
+
+    #[test]
+    fn test_post_process_unsupported() {
+        // multivalue cannot be lowered
+        let wasm = module(
+            &[ValType::I32],
+            &[ValType::I32, ValType::I32],
+            &[Instruction::LocalGet(0)],
+        );
+        assert!(matches!(
+            post_process(&wasm, &PostOptions::default()),
+            Err(WasmToolsError::Unsupported(_))
+        ));
+    }
+
+    /// A module importing a function: (import <module> <name> (func (param ..) (result ..)))
+    fn module_with_import(
+        module: &str,
+        name: &str,
+        params: &[ValType],
+        results: &[ValType],
+    ) -> Vec<u8> {
+        let mut wasm = Module::new();
+        let mut types = TypeSection::new();
+        types.ty().function(params.to_vec(), results.to_vec());
+        wasm.section(&types);
+        let mut imports = ImportSection::new();
+        imports.import(module, name, EntityType::Function(0));
+        wasm.section(&imports);
+        wasm.finish()
+    }
+
+    #[test]
+    fn test_rename_imports() {
+        let wasm = module_with_import(
+            "massa_test",
+            "cheatcode",
+            &[ValType::I32; 2],
+            &[ValType::I32],
+        );
+        assert!(matches!(
+            validate(&wasm),
+            Err(WasmToolsError::UnknownImport { module, .. }) if module == "massa_test"
+        ));
+
+        let renames = [(
+            ("massa_test", "cheatcode"),
+            (MASSA_MODULE, "assembly_script_get_data_for"),
+        )];
+        let output = rename_imports(&wasm, &renames).unwrap();
+        assert!(validate(&output).is_ok());
+        assert_eq!(
+            imported_functions(&output).unwrap(),
+            vec![(
+                MASSA_MODULE.to_string(),
+                "assembly_script_get_data_for".to_string()
+            )]
+        );
+        // Other imports are left unchanged
+        assert_eq!(rename_imports(&output, &renames).unwrap(), output);
+    }
+
+    #[test]
+    fn test_validate_imports() {
+        let wasm = module_with_import(
+            MASSA_MODULE,
+            "assembly_script_get_data",
+            &[ValType::I32],
+            &[ValType::I32],
+        );
+        assert!(validate(&wasm).is_ok());
+        let wasm = module_with_import(ENV_MODULE, "abort", &[ValType::I32; 4], &[]);
+        assert!(validate(&wasm).is_ok());
+
+        // unknown function
+        let wasm = module_with_import(
+            MASSA_MODULE,
+            "assembly_script_get_dat",
+            &[ValType::I32],
+            &[],
+        );
+        assert!(matches!(
+            validate(&wasm),
+            Err(WasmToolsError::UnknownImport { name, .. }) if name == "assembly_script_get_dat"
+        ));
+        // wrong module
+        let wasm = module_with_import(
+            "env",
+            "assembly_script_get_data",
+            &[ValType::I32],
+            &[ValType::I32],
+        );
+        assert!(matches!(
+            validate(&wasm),
+            Err(WasmToolsError::UnknownImport { .. })
+        ));
+        // wrong signature (e.g. u64 instead of i32)
+        let wasm = module_with_import(
+            MASSA_MODULE,
+            "assembly_script_get_data",
+            &[ValType::I64],
+            &[ValType::I32],
+        );
+        assert!(matches!(
+            validate(&wasm),
+            Err(WasmToolsError::InvalidImport { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_wasmv1_and_size() {
+        let wasm = module_with_import(
+            MASSA_MODULE,
+            "abi_generate_event",
+            &[ValType::I32],
+            &[ValType::I32],
+        );
+        // Only available for wasmv1 bytecode
+        assert!(validate(&wasm).is_err());
+        let mut wasmv1 = vec![WASMV1_VERSION];
+        wasmv1.extend_from_slice(&wasm);
+        assert!(validate(&wasmv1).is_ok());
+
+        // Prefix added by post_process
+        assert!(imports_wasmv1_abi(&wasm).unwrap());
+        let options = PostOptions {
+            wasmv1_prefix: true,
+            ..Default::default()
+        };
+        let (output, report) = post_process(&wasm, &options).unwrap();
+        assert_eq!(output, wasmv1);
+        assert!(report.wasmv1);
+        // Already prefixed
+        assert_eq!(post_process(&wasmv1, &options).unwrap().0, wasmv1);
+        assert_eq!(add_wasmv1_prefix(wasmv1.clone()).unwrap(), wasmv1);
+        // Only prefixed if requested
+        let (output, report) = post_process(&wasm, &PostOptions::default()).unwrap();
+        assert_eq!((output, report.wasmv1), (wasm, false));
+        // AssemblyScript ABI: no prefix
+        let wasm = module_with_import(ENV_MODULE, "abort", &[ValType::I32; 4], &[]);
+        assert!(!imports_wasmv1_abi(&wasm).unwrap());
+        assert_eq!(add_wasmv1_prefix(wasm.clone()).unwrap(), wasm);
+
+        let too_large = vec![0; MAX_BYTECODE_SIZE + 1];
+        assert!(matches!(
+            validate(&too_large),
+            Err(WasmToolsError::TooLarge { .. })
+        ));
+    }
+}
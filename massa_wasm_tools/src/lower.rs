@@ -0,0 +1,385 @@
+// std
+use std::convert::Infallible;
+// third-party
+use wasm_encoder::reencode::{self, Reencode};
+use wasm_encoder::{
+    BlockType, CodeSection, CustomSection, FunctionSection, Instruction, Module, TypeSection,
+    ValType,
+};
+use wasmparser::{CustomSectionReader, FunctionBody, Operator, Parser, Payload};
+
+/// Custom section emitted by rustc / LLVM (list of the wasm features used)
+///
+/// Always removed as it is no longer accurate once the module has been lowered
+pub(crate) const TARGET_FEATURES_SECTION: &str = "target_features";
+
+/// Saturating float to int conversions (nontrapping-fptoint proposal)
+///
+/// These instructions are replaced by a call to a helper function (added to the module)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TruncSat {
+    I32F32S,
+    I32F32U,
+    I32F64S,
+    I32F64U,
+    I64F32S,
+    I64F32U,
+    I64F64S,
+    I64F64U,
+}
+
+impl TruncSat {
+    const ALL: [TruncSat; 8] = [
+        TruncSat::I32F32S,
+        TruncSat::I32F32U,
+        TruncSat::I32F64S,
+        TruncSat::I32F64U,
+        TruncSat::I64F32S,
+        TruncSat::I64F32U,
+        TruncSat::I64F64S,
+        TruncSat::I64F64U,
+    ];
+
+    fn from_operator(op: &Operator) -> Option<Self> {
+        let trunc_sat = match op {
+            Operator::I32TruncSatF32S => TruncSat::I32F32S,
+            Operator::I32TruncSatF32U => TruncSat::I32F32U,
+            Operator::I32TruncSatF64S => TruncSat::I32F64S,
+            Operator::I32TruncSatF64U => TruncSat::I32F64U,
+            Operator::I64TruncSatF32S => TruncSat::I64F32S,
+            Operator::I64TruncSatF32U => TruncSat::I64F32U,
+            Operator::I64TruncSatF64S => TruncSat::I64F64S,
+            Operator::I64TruncSatF64U => TruncSat::I64F64U,
+            _ => return None,
+        };
+        Some(trunc_sat)
+    }
+
+    fn is_i64(&self) -> bool {
+        matches!(
+            self,
+            TruncSat::I64F32S | TruncSat::I64F32U | TruncSat::I64F64S | TruncSat::I64F64U
+        )
+    }
+
+    fn is_f64(&self) -> bool {
+        matches!(
+            self,
+            TruncSat::I32F64S | TruncSat::I32F64U | TruncSat::I64F64S | TruncSat::I64F64U
+        )
+    }
+
+    fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            TruncSat::I32F32S | TruncSat::I32F64S | TruncSat::I64F32S | TruncSat::I64F64S
+        )
+    }
+
+    fn param(&self) -> ValType {
+        if self.is_f64() {
+            ValType::F64
+        } else {
+            ValType::F32
+        }
+    }
+
+    fn result(&self) -> ValType {
+        if self.is_i64() {
+            ValType::I64
+        } else {
+            ValType::I32
+        }
+    }
+
+    /// Trapping conversion (MVP instruction)
+    fn trunc(&self) -> Instruction<'static> {
+        match self {
+            TruncSat::I32F32S => Instruction::I32TruncF32S,
+            TruncSat::I32F32U => Instruction::I32TruncF32U,
+            TruncSat::I32F64S => Instruction::I32TruncF64S,
+            TruncSat::I32F64U => Instruction::I32TruncF64U,
+            TruncSat::I64F32S => Instruction::I64TruncF32S,
+            TruncSat::I64F32U => Instruction::I64TruncF32U,
+            TruncSat::I64F64S => Instruction::I64TruncF64S,
+            TruncSat::I64F64U => Instruction::I64TruncF64U,
+        }
+    }
+
+    /// Push a float constant (the type of the parameter)
+    fn float_const(&self, value: f64) -> Instruction<'static> {
+        if self.is_f64() {
+            Instruction::F64Const(value.into())
+        } else {
+            Instruction::F32Const((value as f32).into())
+        }
+    }
+
+    /// Push an integer constant (the type of the result)
+    fn int_const(&self, value: i64) -> Instruction<'static> {
+        if self.is_i64() {
+            Instruction::I64Const(value)
+        } else {
+            Instruction::I32Const(value as i32)
+        }
+    }
+
+    /// Function body (MVP instructions only) with the semantics of the saturating conversion:
+    /// NaN -> 0, values out of range are clamped to the min / max of the integer type
+    fn helper(&self) -> wasm_encoder::Function {
+        // Note: all bounds are exactly representable in f32 & f64
+        let (min, max, lower_bound, upper_bound) = match (self.is_signed(), self.is_i64()) {
+            (true, false) => (
+                i32::MIN as i64,
+                i32::MAX as i64,
+                -2f64.powi(31),
+                2f64.powi(31),
+            ),
+            (true, true) => (i64::MIN, i64::MAX, -2f64.powi(63), 2f64.powi(63)),
+            // u32::MAX / u64::MAX (same bits as -1)
+            (false, false) => (0, -1, -1.0, 2f64.powi(32)),
+            (false, true) => (0, -1, -1.0, 2f64.powi(64)),
+        };
+        let (eq, ge, lt, le) = if self.is_f64() {
+            (
+                Instruction::F64Eq,
+                Instruction::F64Ge,
+                Instruction::F64Lt,
+                Instruction::F64Le,
+            )
+        } else {
+            (
+                Instruction::F32Eq,
+                Instruction::F32Ge,
+                Instruction::F32Lt,
+                Instruction::F32Le,
+            )
+        };
+        let result = BlockType::Result(self.result());
+
+        let mut f = wasm_encoder::Function::new([]);
+        // NaN
+        f.instruction(&Instruction::LocalGet(0))
+            .instruction(&Instruction::LocalGet(0))
+            .instruction(&eq)
+            .instruction(&Instruction::If(result))
+            // x >= upper bound
+            .instruction(&Instruction::LocalGet(0))
+            .instruction(&self.float_const(upper_bound))
+            .instruction(&ge)
+            .instruction(&Instruction::If(result))
+            .instruction(&self.int_const(max))
+            .instruction(&Instruction::Else)
+            // x < lower bound (signed) or x <= -1.0 (unsigned)
+            .instruction(&Instruction::LocalGet(0))
+            .instruction(&self.float_const(lower_bound))
+            .instruction(if self.is_signed() { &lt } else { &le })
+            .instruction(&Instruction::If(result))
+            .instruction(&self.int_const(min))
+            .instruction(&Instruction::Else)
+            .instruction(&Instruction::LocalGet(0))
+            .instruction(&self.trunc())
+            .instruction(&Instruction::End)
+            .instruction(&Instruction::End)
+            .instruction(&Instruction::Else)
+            .instruction(&self.int_const(0))
+            .instruction(&Instruction::End)
+            .instruction(&Instruction::End);
+        f
+    }
+}
+
+/// Replace a sign extension instruction (sign-ext proposal) with a pair of shifts
+fn lower_sign_ext(op: &Operator, f: &mut wasm_encoder::Function) -> bool {
+    let (is_i64, shift) = match op {
+        Operator::I32Extend8S => (false, 24),
+        Operator::I32Extend16S => (false, 16),
+        Operator::I64Extend8S => (true, 56),
+        Operator::I64Extend16S => (true, 48),
+        Operator::I64Extend32S => (true, 32),
+        _ => return false,
+    };
+    if is_i64 {
+        f.instruction(&Instruction::I64Const(shift))
+            .instruction(&Instruction::I64Shl)
+            .instruction(&Instruction::I64Const(shift))
+            .instruction(&Instruction::I64ShrS);
+    } else {
+        f.instruction(&Instruction::I32Const(shift as i32))
+            .instruction(&Instruction::I32Shl)
+            .instruction(&Instruction::I32Const(shift as i32))
+            .instruction(&Instruction::I32ShrS);
+    }
+    true
+}
+
+/// Number of instructions rewritten by [Lowering]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoweringStats {
+    /// sign-ext instructions (e.g. i32.extend8_s)
+    pub sign_ext: usize,
+    /// nontrapping-fptoint instructions (e.g. i32.trunc_sat_f64_s)
+    pub trunc_sat: usize,
+}
+
+/// Reencode a module (see [Reencode]) with only MVP (+ bulk memory) instructions
+///
+/// Note: re-encoding also fixes the encodings that are rejected by a MVP parser
+///       (e.g. call_indirect table index written as a 5 bytes leb128 with reference types)
+pub(crate) struct Lowering<'a> {
+    /// Custom sections to keep (None: keep all custom sections)
+    keep_sections: Option<&'a [String]>,
+    /// Custom sections removed
+    pub(crate) stripped_sections: Vec<String>,
+    pub(crate) stats: LoweringStats,
+    /// Number of types in the original module
+    type_count: u32,
+    /// Number of functions (imported + defined) in the original module
+    function_count: u32,
+    /// Helper functions to add (for saturating conversions)
+    helpers: Vec<TruncSat>,
+}
+
+impl<'a> Lowering<'a> {
+    /// Scan the module to find the helper functions to add
+    pub(crate) fn new(
+        bytecode: &[u8],
+        keep_sections: Option<&'a [String]>,
+    ) -> Result<Self, wasmparser::BinaryReaderError> {
+        let mut type_count = 0;
+        let mut function_count = 0;
+        let mut used = [false; TruncSat::ALL.len()];
+
+        for payload in Parser::new(0).parse_all(bytecode) {
+            match payload? {
+                Payload::TypeSection(section) => {
+                    for rec_group in section {
+                        type_count += rec_group?.types().len() as u32;
+                    }
+                }
+                Payload::ImportSection(section) => {
+                    for import in section {
+                        if matches!(import?.ty, wasmparser::TypeRef::Func(_)) {
+                            function_count += 1;
+                        }
+                    }
+                }
+                Payload::FunctionSection(section) => {
+                    function_count += section.count();
+                }
+                Payload::CodeSectionEntry(body) => {
+                    let mut reader = body.get_operators_reader()?;
+                    while !reader.eof() {
+                        if let Some(trunc_sat) = TruncSat::from_operator(&reader.read()?) {
+                            used[trunc_sat as usize] = true;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            keep_sections,
+            stripped_sections: vec![],
+            stats: LoweringStats::default(),
+            type_count,
+            function_count,
+            helpers: TruncSat::ALL
+                .into_iter()
+                .filter(|trunc_sat| used[*trunc_sat as usize])
+                .collect(),
+        })
+    }
+
+    /// Function index of the helper function
+    fn helper_index(&self, trunc_sat: TruncSat) -> u32 {
+        // Unwrap safe: all helpers are collected in Lowering::new
+        let position = self.helpers.iter().position(|h| *h == trunc_sat).unwrap();
+        self.function_count + position as u32
+    }
+}
+
+impl Reencode for Lowering<'_> {
+    type Error = Infallible;
+
+    fn parse_type_section(
+        &mut self,
+        types: &mut TypeSection,
+        section: wasmparser::TypeSectionReader<'_>,
+    ) -> Result<(), reencode::Error<Self::Error>> {
+        reencode::utils::parse_type_section(self, types, section)?;
+        for helper in self.helpers.iter() {
+            types.ty().function([helper.param()], [helper.result()]);
+        }
+        Ok(())
+    }
+
+    fn parse_function_section(
+        &mut self,
+        functions: &mut FunctionSection,
+        section: wasmparser::FunctionSectionReader<'_>,
+    ) -> Result<(), reencode::Error<Self::Error>> {
+        reencode::utils::parse_function_section(self, functions, section)?;
+        for i in 0..self.helpers.len() as u32 {
+            functions.function(self.type_count + i);
+        }
+        Ok(())
+    }
+
+    fn parse_code_section(
+        &mut self,
+        code: &mut CodeSection,
+        section: wasmparser::CodeSectionReader<'_>,
+    ) -> Result<(), reencode::Error<Self::Error>> {
+        reencode::utils::parse_code_section(self, code, section)?;
+        for helper in self.helpers.iter() {
+            code.function(&helper.helper());
+        }
+        Ok(())
+    }
+
+    fn parse_function_body(
+        &mut self,
+        code: &mut CodeSection,
+        func: FunctionBody<'_>,
+    ) -> Result<(), reencode::Error<Self::Error>> {
+        let mut f = self.new_function_with_parsed_locals(&func)?;
+        let mut reader = func.get_operators_reader()?;
+        while !reader.eof() {
+            let op = reader.read()?;
+            if lower_sign_ext(&op, &mut f) {
+                self.stats.sign_ext += 1;
+            } else if let Some(trunc_sat) = TruncSat::from_operator(&op) {
+                f.instruction(&Instruction::Call(self.helper_index(trunc_sat)));
+                self.stats.trunc_sat += 1;
+            } else {
+                f.instruction(&self.instruction(op)?);
+            }
+        }
+        code.function(&f);
+        Ok(())
+    }
+
+    fn parse_custom_section(
+        &mut self,
+        module: &mut Module,
+        section: CustomSectionReader<'_>,
+    ) -> Result<(), reencode::Error<Self::Error>> {
+        let keep = match self.keep_sections {
+            _ if section.name() == TARGET_FEATURES_SECTION => false,
+            Some(keep_sections) => keep_sections.iter().any(|name| name == section.name()),
+            None => true,
+        };
+        if keep {
+            // Note: copy the section as is (no need to parse the name section)
+            module.section(&CustomSection {
+                name: section.name().into(),
+                data: section.data().into(),
+            });
+        } else {
+            self.stripped_sections.push(section.name().to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+// std
+use std::path::PathBuf;
+// third-party
+use clap::{Parser, Subcommand};
+// internal
+use massa_wasm_tools::{PostOptions, post_process, validate};
+
+#[derive(Debug, Parser)]
+#[command(about = "Make wasm smart contracts compatible with Massa", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Lower unsupported wasm features (sign-ext, nontrapping-fptoint, ...) & validate
+    ///
+    /// A wasmv1 smart contract (importing the abi_* functions) is prefixed with 0x01
+    Post {
+        /// Input wasm file (e.g. target/wasm32-unknown-unknown/release/hello_world.wasm)
+        input: PathBuf,
+        /// Output wasm file (Default to: overwrite the input file)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Remove the custom sections (except the ABI section)
+        #[arg(long)]
+        strip_custom_sections: bool,
+        /// Custom section to keep when stripping (can be repeated)
+        #[arg(long = "keep-section")]
+        keep_sections: Vec<String>,
+    },
+    /// Check that a smart contract can be deployed (wasm features, imports & size)
+    Validate { input: PathBuf },
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let res = match cli.command {
+        Command::Post {
+            input,
+            output,
+            strip_custom_sections,
+            keep_sections,
+        } => {
+            let options = PostOptions {
+                strip_custom_sections,
+                keep_sections,
+                wasmv1_prefix: true,
+            };
+            post(&input, output.as_ref().unwrap_or(&input), &options)
+        }
+        Command::Validate { input } => std::fs::read(&input)
+            .map_err(|e| format!("Cannot read {}: {e}", input.display()))
+            .and_then(|bytecode| validate(&bytecode).map_err(|e| e.to_string()))
+            .map(|_| println!("{}: ok", input.display())),
+    };
+
+    if let Err(e) = res {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn post(input: &PathBuf, output: &PathBuf, options: &PostOptions) -> Result<(), String> {
+    let bytecode =
+        std::fs::read(input).map_err(|e| format!("Cannot read {}: {e}", input.display()))?;
+    let (bytecode, report) = post_process(&bytecode, options).map_err(|e| e.to_string())?;
+    std::fs::write(output, bytecode)
+        .map_err(|e| format!("Cannot write {}: {e}", output.display()))?;
+
+    println!("Generated wasm file: {}", output.display());
+    println!(
+        "Lowered instructions: sign-ext: {}, nontrapping-fptoint: {}",
+        report.lowered.sign_ext, report.lowered.trunc_sat
+    );
+    if !report.stripped_sections.is_empty() {
+        println!(
+            "Removed custom sections: {}",
+            report.stripped_sections.join(", ")
+        );
+    }
+    if report.wasmv1 {
+        println!("wasmv1 smart contract (prefixed with 0x01)");
+    }
+    println!(
+        "Size: {} bytes (input: {} bytes)",
+        report.output_size, report.input_size
+    );
+    Ok(())
+}